@@ -0,0 +1,364 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::stream::{subscribe, Trade};
+
+const SPIKE_STREAM_CAPACITY: usize = 256;
+
+// Gaps longer than this are not replayed minute by minute, the baseline is fully decayed by then
+const MAX_GAP_MINUTES: u64 = 120;
+
+static SPIKE_STREAM: LazyLock<broadcast::Sender<VolumeSpike>> =
+    LazyLock::new(|| broadcast::channel(SPIKE_STREAM_CAPACITY).0);
+
+#[derive(Debug, Clone)]
+pub struct AnomalyConfig {
+    /// Smoothing factor of the per-minute moving averages, higher reacts faster
+    pub alpha: f64,
+    /// Completed minutes a token needs before it can be flagged
+    pub warmup_minutes: u32,
+    /// Minimum ratio between the current minute and the baseline
+    pub spike_multiple: f64,
+    /// Minimum z-score of the current minute against the baseline
+    pub z_threshold: f64,
+    /// Ignore minutes with fewer trades than this, filters out dust activity
+    pub min_trades: u32,
+    /// Seconds before the same token can be flagged again
+    pub cooldown_secs: u64,
+    /// Tokens without trades for this many seconds are forgotten
+    pub idle_secs: u64,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.1,
+            warmup_minutes: 15,
+            spike_multiple: 3.0,
+            z_threshold: 3.0,
+            min_trades: 10,
+            cooldown_secs: 30 * 60,
+            idle_secs: 6 * 60 * 60,
+        }
+    }
+}
+
+/// Raised when a token trades well above its own recent activity
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct VolumeSpike {
+    #[serde(rename = "mintAddress")]
+    pub mint_address: String,
+    /// Unix timestamp of the start of the minute that spiked
+    pub minute: u64,
+    pub trades: u32,
+    /// Traded base token amount in the minute
+    pub volume: f64,
+    #[serde(rename = "baselineTrades")]
+    pub baseline_trades: f64,
+    #[serde(rename = "baselineVolume")]
+    pub baseline_volume: f64,
+    /// Largest of the trade-rate and volume multiples over the baseline
+    pub multiple: f64,
+    #[serde(rename = "zScore")]
+    pub z_score: f64,
+}
+
+/// Exponentially weighted mean and variance of a per-minute series
+#[derive(Debug, Clone, Default)]
+struct Ewma {
+    mean: f64,
+    variance: f64,
+    seeded: bool,
+}
+
+impl Ewma {
+    fn update(&mut self, value: f64, alpha: f64) {
+        // Start from the first observation rather than ramping up from zero
+        if !self.seeded {
+            self.mean = value;
+            self.seeded = true;
+            return;
+        }
+
+        let diff = value - self.mean;
+        let increment = alpha * diff;
+        self.mean += increment;
+        self.variance = (1.0 - alpha) * (self.variance + diff * increment);
+    }
+
+    fn multiple(&self, value: f64) -> f64 {
+        if self.mean > 0.0 {
+            value / self.mean
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    fn z_score(&self, value: f64) -> f64 {
+        let std_dev = self.variance.sqrt();
+        if std_dev > f64::EPSILON {
+            (value - self.mean) / std_dev
+        } else if value > self.mean {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TokenActivity {
+    minute: u64,
+    trades: u32,
+    volume: f64,
+    trade_rate: Ewma,
+    volume_rate: Ewma,
+    samples: u32,
+    flagged_minute: Option<u64>,
+    cooldown_until: u64,
+}
+
+impl TokenActivity {
+    fn new(minute: u64) -> Self {
+        Self {
+            minute,
+            trades: 0,
+            volume: 0.0,
+            trade_rate: Ewma::default(),
+            volume_rate: Ewma::default(),
+            samples: 0,
+            flagged_minute: None,
+            cooldown_until: 0,
+        }
+    }
+
+    // Fold the finished minute and any silent minutes since into the baselines
+    fn roll(&mut self, minute: u64, alpha: f64) {
+        let silent = ((minute - self.minute) / 60 - 1).min(MAX_GAP_MINUTES);
+
+        self.trade_rate.update(self.trades as f64, alpha);
+        self.volume_rate.update(self.volume, alpha);
+        for _ in 0..silent {
+            self.trade_rate.update(0.0, alpha);
+            self.volume_rate.update(0.0, alpha);
+        }
+
+        self.samples = self.samples.saturating_add(1 + silent as u32);
+        self.minute = minute;
+        self.trades = 0;
+        self.volume = 0.0;
+    }
+}
+
+/// Tracks per-token trade rate and volume baselines and flags spikes against them
+pub struct AnomalyDetector {
+    config: AnomalyConfig,
+    tokens: HashMap<String, TokenActivity>,
+    last_prune: u64,
+}
+
+impl AnomalyDetector {
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self {
+            config,
+            tokens: HashMap::new(),
+            last_prune: 0,
+        }
+    }
+
+    /// Record a trade against its base token, returns a spike if this trade tipped it over
+    pub fn record(&mut self, trade: &Trade) -> Option<VolumeSpike> {
+        let now = trade.block_time;
+        let minute = now - now % 60;
+        self.prune(now);
+
+        let config = &self.config;
+        let activity = self
+            .tokens
+            .entry(trade.base_mint_address.clone())
+            .or_insert_with(|| TokenActivity::new(minute));
+
+        // Late trades from an earlier minute are counted in the current one
+        if minute > activity.minute {
+            activity.roll(minute, config.alpha);
+        }
+        activity.trades += 1;
+        activity.volume += trade.base_size.abs();
+
+        if activity.samples < config.warmup_minutes
+            || activity.trades < config.min_trades
+            || activity.flagged_minute == Some(activity.minute)
+            || now < activity.cooldown_until
+        {
+            return None;
+        }
+
+        let trades = activity.trades as f64;
+        let trade_multiple = activity.trade_rate.multiple(trades);
+        let volume_multiple = activity.volume_rate.multiple(activity.volume);
+        let trade_z = activity.trade_rate.z_score(trades);
+        let volume_z = activity.volume_rate.z_score(activity.volume);
+
+        let trade_spike = trade_multiple >= config.spike_multiple && trade_z >= config.z_threshold;
+        let volume_spike =
+            volume_multiple >= config.spike_multiple && volume_z >= config.z_threshold;
+        if !trade_spike && !volume_spike {
+            return None;
+        }
+
+        activity.flagged_minute = Some(activity.minute);
+        activity.cooldown_until = now + config.cooldown_secs;
+
+        Some(VolumeSpike {
+            mint_address: trade.base_mint_address.clone(),
+            minute: activity.minute,
+            trades: activity.trades,
+            volume: activity.volume,
+            baseline_trades: activity.trade_rate.mean,
+            baseline_volume: activity.volume_rate.mean,
+            multiple: trade_multiple.max(volume_multiple),
+            z_score: trade_z.max(volume_z),
+        })
+    }
+
+    // Forget idle tokens, at most once a minute
+    fn prune(&mut self, now: u64) {
+        if now < self.last_prune + 60 {
+            return;
+        }
+        self.last_prune = now;
+
+        let cutoff = now.saturating_sub(self.config.idle_secs);
+        self.tokens.retain(|_, activity| activity.minute >= cutoff);
+    }
+}
+
+/// Subscribe to volume spikes detected on the trade stream
+pub fn subscribe_spikes() -> broadcast::Receiver<VolumeSpike> {
+    SPIKE_STREAM.subscribe()
+}
+
+/// Run the detector over the live trade stream, publishing spikes until the stream closes
+pub async fn detect_spikes(config: AnomalyConfig) {
+    let mut detector = AnomalyDetector::new(config);
+    let mut trades = subscribe();
+
+    loop {
+        match trades.recv().await {
+            Ok(trade) => {
+                if let Some(spike) = detector.record(&trade) {
+                    tracing::info!(
+                        "Volume spike on {}: {} trades, {:.1}x baseline",
+                        spike.mint_address,
+                        spike.trades,
+                        spike.multiple
+                    );
+                    let _ = SPIKE_STREAM.send(spike);
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Anomaly detector lagged, skipped {} trades", skipped);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn trade(block_time: u64, base_size: f64) -> Trade {
+        Trade {
+            signature: "sig".to_string(),
+            slot: 0,
+            block_time,
+            program_id: String::new(),
+            program: None,
+            market_id: String::new(),
+            base_mint_address: MINT.to_string(),
+            quote_mint_address: String::new(),
+            authority_address: String::new(),
+            fee_payer: String::new(),
+            price: 1.0,
+            base_size,
+            quote_size: base_size,
+            fee: 0.0,
+        }
+    }
+
+    fn config() -> AnomalyConfig {
+        AnomalyConfig {
+            warmup_minutes: 5,
+            min_trades: 3,
+            cooldown_secs: 600,
+            ..Default::default()
+        }
+    }
+
+    // Feed `per_minute` trades in each minute starting at `from`, returning the spikes raised
+    fn feed(
+        detector: &mut AnomalyDetector,
+        from: u64,
+        minutes: u64,
+        per_minute: u64,
+    ) -> Vec<VolumeSpike> {
+        let mut spikes = Vec::new();
+        for minute in 0..minutes {
+            for i in 0..per_minute {
+                let time = (from + minute) * 60 + i % 60;
+                spikes.extend(detector.record(&trade(time, 100.0)));
+            }
+        }
+        spikes
+    }
+
+    #[test]
+    fn test_no_spike_during_warmup() {
+        let mut detector = AnomalyDetector::new(config());
+        assert!(feed(&mut detector, 0, 1, 2).is_empty());
+        assert!(feed(&mut detector, 1, 3, 50).is_empty());
+    }
+
+    #[test]
+    fn test_steady_activity_is_not_flagged() {
+        let mut detector = AnomalyDetector::new(config());
+        assert!(feed(&mut detector, 0, 60, 4).is_empty());
+    }
+
+    #[test]
+    fn test_spike_and_cooldown() {
+        let mut detector = AnomalyDetector::new(config());
+        feed(&mut detector, 0, 20, 4);
+
+        let spikes = feed(&mut detector, 20, 1, 40);
+        assert_eq!(spikes.len(), 1);
+        assert_eq!(spikes[0].mint_address, MINT);
+        assert_eq!(spikes[0].minute, 20 * 60);
+        assert!(spikes[0].multiple >= 3.0);
+
+        // Still hot a few minutes later, but within the cooldown
+        assert!(feed(&mut detector, 21, 5, 40).is_empty());
+
+        // Quiet down, then spike again once the cooldown has passed
+        feed(&mut detector, 26, 30, 4);
+        assert_eq!(feed(&mut detector, 56, 1, 40).len(), 1);
+    }
+
+    #[test]
+    fn test_idle_tokens_are_pruned() {
+        let mut detector = AnomalyDetector::new(config());
+        feed(&mut detector, 0, 1, 1);
+
+        let later = AnomalyConfig::default().idle_secs + 120;
+        let mut other = trade(later, 1.0);
+        other.base_mint_address = "other".to_string();
+        detector.record(&other);
+
+        assert!(!detector.tokens.contains_key(MINT));
+    }
+}
@@ -0,0 +1,40 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+static CONNECTED: AtomicBool = AtomicBool::new(false);
+static TRADES: AtomicU64 = AtomicU64::new(0);
+// Unix seconds of the last trade received, 0 before the first one
+static LAST_TRADE_AT: AtomicI64 = AtomicI64::new(0);
+
+/// State of the Vybe live feed, for operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamHealth {
+    pub connected: bool,
+    /// Trades received since the process started
+    pub trades: u64,
+    /// Unix seconds of the last trade received
+    pub last_trade_at: Option<i64>,
+}
+
+pub fn stream_health() -> StreamHealth {
+    let last_trade_at = LAST_TRADE_AT.load(Ordering::Relaxed);
+    StreamHealth {
+        connected: CONNECTED.load(Ordering::Relaxed),
+        trades: TRADES.load(Ordering::Relaxed),
+        last_trade_at: (last_trade_at > 0).then_some(last_trade_at),
+    }
+}
+
+pub(crate) fn set_connected(connected: bool) {
+    CONNECTED.store(connected, Ordering::Relaxed);
+}
+
+pub(crate) fn record_trade() {
+    TRADES.fetch_add(1, Ordering::Relaxed);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64);
+    LAST_TRADE_AT.store(now, Ordering::Relaxed);
+}
@@ -1,18 +1,42 @@
+mod anomaly;
+mod health;
+mod price;
+mod stream;
+mod subscription;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 mod ws;
 
+use std::sync::Arc;
 use utils::ENV_CONFIG;
 use ws::{VybeWebSocket, VybeWebSocketConfig};
 
+pub use anomaly::{detect_spikes, subscribe_spikes, AnomalyConfig, AnomalyDetector, VolumeSpike};
+pub use health::{stream_health, StreamHealth};
+pub use price::{latest_price, usd_price, TokenPrice, SOL_MINT, USDC_MINT, USDT_MINT};
+pub use stream::{publish, subscribe, subscribe_filtered, Trade, TradeSubscription};
+pub use subscription::{set_tracked_wallets, set_watched_mints};
+pub use ws::{TradeFilter, TradingProgram, VybeMessage};
+
 pub async fn aggregate() {
     let config = VybeWebSocketConfig {
         websocket_uri: "wss://api.vybenetwork.xyz/live".to_string(),
         api_key: ENV_CONFIG.vibe_api_key.to_string(),
-        on_message: Some(Box::new(|message| {
-            println!("Received message: {:?}", message);
+        configure_updates: Some(subscription::configure_updates()),
+        on_message: Some(Arc::new(publish)),
+        on_connect: Some(Arc::new(|| {
+            health::set_connected(true);
+            tracing::info!("Connected to the Vybe live feed");
+        })),
+        on_disconnect: Some(Arc::new(|| {
+            health::set_connected(false);
+            tracing::warn!("Disconnected from the Vybe live feed");
         })),
         ..Default::default()
     };
 
+    tokio::spawn(detect_spikes(AnomalyConfig::default()));
+
     let mut ws = VybeWebSocket::new(config);
     // ws.connect().await;
     //  Spawn the websocket connection on a separate task
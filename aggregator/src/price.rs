@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+use crate::stream::Trade;
+
+pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+pub const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
+
+// Last known USD price of every mint seen on the trade stream
+static PRICES: LazyLock<RwLock<HashMap<String, TokenPrice>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenPrice {
+    pub usd: f64,
+    pub block_time: u64,
+}
+
+fn is_stable(mint: &str) -> bool {
+    mint == USDC_MINT || mint == USDT_MINT
+}
+
+fn quote_usd(mint: &str) -> Option<f64> {
+    if is_stable(mint) {
+        Some(1.0)
+    } else {
+        latest_price(mint).map(|price| price.usd)
+    }
+}
+
+/// USD price of the non-quote token of a trade, priced through USDC/USDT directly or through SOL.
+///
+/// Returns the priced mint with its price, or `None` when neither side has a known USD value.
+pub fn usd_price(trade: &Trade) -> Option<(&str, f64)> {
+    if trade.price <= 0.0 {
+        return None;
+    }
+
+    let base = trade.base_mint_address.as_str();
+    let quote = trade.quote_mint_address.as_str();
+
+    // Pools are usually quoted in SOL or a stable, but some list the token as the quote side
+    if is_stable(quote) || quote == SOL_MINT {
+        Some((base, trade.price * quote_usd(quote)?))
+    } else if is_stable(base) || base == SOL_MINT {
+        Some((quote, quote_usd(base)? / trade.price))
+    } else {
+        None
+    }
+}
+
+/// Update the price book from a trade, returning the priced mint and its USD price
+pub fn record_price(trade: &Trade) -> Option<(String, f64)> {
+    let (mint, usd) = usd_price(trade)?;
+    let mint = mint.to_string();
+
+    let mut prices = PRICES.write().unwrap();
+    let newer = prices
+        .get(&mint)
+        .is_none_or(|price| price.block_time <= trade.block_time);
+    if newer {
+        prices.insert(
+            mint.clone(),
+            TokenPrice {
+                usd,
+                block_time: trade.block_time,
+            },
+        );
+    }
+
+    Some((mint, usd))
+}
+
+/// Last USD price seen on the trade stream for `mint`
+pub fn latest_price(mint: &str) -> Option<TokenPrice> {
+    PRICES.read().unwrap().get(mint).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn trade(base: &str, quote: &str, price: f64, block_time: u64) -> Trade {
+        Trade {
+            signature: "sig".to_string(),
+            slot: 0,
+            block_time,
+            program_id: String::new(),
+            program: None,
+            market_id: String::new(),
+            base_mint_address: base.to_string(),
+            quote_mint_address: quote.to_string(),
+            authority_address: String::new(),
+            fee_payer: String::new(),
+            price,
+            base_size: 1.0,
+            quote_size: price,
+            fee: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_usd_price() {
+        assert_eq!(
+            usd_price(&trade(BONK, USDC_MINT, 0.00002, 1)),
+            Some((BONK, 0.00002))
+        );
+        assert_eq!(
+            usd_price(&trade(USDC_MINT, BONK, 50000.0, 1)),
+            Some((BONK, 0.00002))
+        );
+        assert_eq!(usd_price(&trade(BONK, "unknown", 1.0, 1)), None);
+
+        record_price(&trade(SOL_MINT, USDC_MINT, 150.0, 1));
+        let via_sol = trade(BONK, SOL_MINT, 0.0000002, 2);
+        let (mint, usd) = usd_price(&via_sol).unwrap();
+        assert_eq!(mint, BONK);
+        assert!((usd - 0.00003).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_stale_trades_do_not_overwrite() {
+        let mint = "stale-test-mint";
+        record_price(&trade(mint, USDC_MINT, 2.0, 10));
+        record_price(&trade(mint, USDC_MINT, 1.0, 5));
+        assert_eq!(latest_price(mint).unwrap().usd, 2.0);
+    }
+}
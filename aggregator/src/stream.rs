@@ -0,0 +1,275 @@
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    mpsc::{self, error::TrySendError},
+};
+
+use crate::health::record_trade;
+use crate::price::record_price;
+use crate::ws::{TradingProgram, VybeMessage};
+
+// Capacity of the shared broadcast channel, receivers lagging further behind are dropped
+const TRADE_STREAM_CAPACITY: usize = 4096;
+
+// Global fan-out of normalized trades coming from the Vybe websocket
+static TRADE_STREAM: LazyLock<broadcast::Sender<Trade>> =
+    LazyLock::new(|| broadcast::channel(TRADE_STREAM_CAPACITY).0);
+
+/// A trade from the Vybe live feed with numeric fields parsed
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Trade {
+    pub signature: String,
+    pub slot: u64,
+    #[serde(rename = "blockTime")]
+    pub block_time: u64,
+    #[serde(rename = "programId")]
+    pub program_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program: Option<&'static str>,
+    #[serde(rename = "marketId")]
+    pub market_id: String,
+    #[serde(rename = "baseMintAddress")]
+    pub base_mint_address: String,
+    #[serde(rename = "quoteMintAddress")]
+    pub quote_mint_address: String,
+    #[serde(rename = "authorityAddress")]
+    pub authority_address: String,
+    #[serde(rename = "feePayer")]
+    pub fee_payer: String,
+    pub price: f64,
+    #[serde(rename = "baseSize")]
+    pub base_size: f64,
+    #[serde(rename = "quoteSize")]
+    pub quote_size: f64,
+    pub fee: f64,
+}
+
+impl Trade {
+    /// Normalize a raw websocket message, returns `None` if a numeric field is malformed
+    pub fn from_message(message: VybeMessage) -> Option<Self> {
+        let program = program_name(&message.program_id);
+
+        Some(Self {
+            price: message.price.parse().ok()?,
+            base_size: message.base_size.parse().ok()?,
+            quote_size: message.quote_size.parse().ok()?,
+            fee: message.fee.parse().ok()?,
+            signature: message.signature,
+            slot: message.slot,
+            block_time: message.block_time,
+            program_id: message.program_id,
+            program,
+            market_id: message.market_id,
+            base_mint_address: message.base_mint_address,
+            quote_mint_address: message.quote_mint_address,
+            authority_address: message.authority_address,
+            fee_payer: message.fee_payer,
+        })
+    }
+
+    /// The token the authority received and how much of it.
+    ///
+    /// Vybe sends unsigned sizes and orients every trade from its authority, the base token
+    /// is the one it received and the quote token the one it paid.
+    pub fn bought(&self) -> (&str, f64) {
+        (&self.base_mint_address, self.base_size.abs())
+    }
+
+    /// The token the authority paid and how much of it, see [`Trade::bought`]
+    pub fn sold(&self) -> (&str, f64) {
+        (&self.quote_mint_address, self.quote_size.abs())
+    }
+}
+
+fn program_name(program_id: &str) -> Option<&'static str> {
+    [
+        TradingProgram::MeteoraDelMM,
+        TradingProgram::MeteoraPools,
+        TradingProgram::LifinitySwapV2,
+        TradingProgram::LifinitySwapV1,
+        TradingProgram::OpenbookV2,
+        TradingProgram::RaydiumV4,
+        TradingProgram::RaydiumCLMM,
+        TradingProgram::OrcaWhirlpool,
+        TradingProgram::Phoenix,
+        TradingProgram::PumpFun,
+    ]
+    .into_iter()
+    .find(|program| program.program_id() == program_id)
+    .map(|program| program.as_str())
+}
+
+/// Client side filter over the normalized trade stream, mirrors the `TradeFilter` fields
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TradeSubscription {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "tokenMintAddress")]
+    pub token_mint_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "programId")]
+    pub program_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "marketId")]
+    pub market_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "feePayer")]
+    pub fee_payer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "minQuoteSize")]
+    pub min_quote_size: Option<f64>,
+}
+
+impl TradeSubscription {
+    pub fn matches(&self, trade: &Trade) -> bool {
+        let eq = |expected: &Option<String>, actual: &str| {
+            expected
+                .as_deref()
+                .is_none_or(|expected| expected == actual)
+        };
+
+        let mint_matches = self
+            .token_mint_address
+            .as_deref()
+            .is_none_or(|mint| mint == trade.base_mint_address || mint == trade.quote_mint_address);
+
+        mint_matches
+            && eq(&self.program_id, &trade.program_id)
+            && eq(&self.market_id, &trade.market_id)
+            && eq(&self.fee_payer, &trade.fee_payer)
+            && self
+                .min_quote_size
+                .is_none_or(|min| trade.quote_size.abs() >= min)
+    }
+}
+
+/// Normalize a websocket message, update the price book and fan it out to every subscriber
+pub fn publish(message: VybeMessage) {
+    match Trade::from_message(message) {
+        // Sending only fails when nobody is subscribed, which is fine
+        Some(trade) => {
+            record_trade();
+            record_price(&trade);
+            let _ = TRADE_STREAM.send(trade);
+        }
+        None => tracing::warn!("Dropping trade with malformed numeric fields"),
+    }
+}
+
+/// Subscribe to every normalized trade
+pub fn subscribe() -> broadcast::Receiver<Trade> {
+    TRADE_STREAM.subscribe()
+}
+
+/// Subscribe to trades matching `subscription` through a queue of at most `buffer` trades.
+///
+/// The returned receiver is closed as soon as the consumer falls behind (its queue is full
+/// or the shared stream lagged), so slow clients get disconnected instead of stalling.
+pub fn subscribe_filtered(subscription: TradeSubscription, buffer: usize) -> mpsc::Receiver<Trade> {
+    let (tx, rx) = mpsc::channel(buffer);
+    let mut trades = subscribe();
+
+    tokio::spawn(async move {
+        loop {
+            let trade = tokio::select! {
+                trade = trades.recv() => trade,
+                _ = tx.closed() => break,
+            };
+
+            match trade {
+                Ok(trade) if subscription.matches(&trade) => match tx.try_send(trade) {
+                    Ok(()) => {}
+                    Err(TrySendError::Full(_)) => {
+                        tracing::debug!("Trade subscriber buffer full, disconnecting");
+                        break;
+                    }
+                    Err(TrySendError::Closed(_)) => break,
+                },
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(
+                        "Trade subscriber lagged by {} trades, disconnecting",
+                        skipped
+                    );
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::vybe_message;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    const SOL: &str = "So11111111111111111111111111111111111111112";
+
+    fn message() -> VybeMessage {
+        vybe_message(BONK, "1000000", SOL, "0.1234")
+    }
+
+    #[test]
+    fn test_trade_from_message() {
+        let trade = Trade::from_message(message()).unwrap();
+        assert_eq!(trade.program, Some("RAYDIUM_V4"));
+        assert_eq!(trade.base_size, 1000000.0);
+        assert_eq!(trade.quote_size, 0.1234);
+
+        // Buying BONK with SOL, the received token is the base
+        assert_eq!(
+            trade.bought(),
+            ("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263", 1000000.0)
+        );
+        assert_eq!(
+            trade.sold(),
+            ("So11111111111111111111111111111111111111112", 0.1234)
+        );
+
+        let mut malformed = message();
+        malformed.price = "n/a".to_string();
+        assert!(Trade::from_message(malformed).is_none());
+    }
+
+    #[test]
+    fn test_subscription_matches() {
+        let trade = Trade::from_message(message()).unwrap();
+
+        assert!(TradeSubscription::default().matches(&trade));
+
+        let by_quote_mint = TradeSubscription {
+            token_mint_address: Some("So11111111111111111111111111111111111111112".to_string()),
+            ..Default::default()
+        };
+        assert!(by_quote_mint.matches(&trade));
+
+        let other_payer = TradeSubscription {
+            fee_payer: Some("other".to_string()),
+            ..Default::default()
+        };
+        assert!(!other_payer.matches(&trade));
+
+        let too_small = TradeSubscription {
+            min_quote_size: Some(1.0),
+            ..Default::default()
+        };
+        assert!(!too_small.matches(&trade));
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_is_disconnected() {
+        let mut rx = subscribe_filtered(TradeSubscription::default(), 1);
+        let trade = Trade::from_message(message()).unwrap();
+
+        for _ in 0..3 {
+            TRADE_STREAM.send(trade.clone()).unwrap();
+        }
+
+        assert_eq!(rx.recv().await, Some(trade));
+        assert_eq!(rx.recv().await, None);
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::BTreeSet;
+use std::sync::{LazyLock, Mutex};
+use tokio::sync::watch;
+
+use crate::ws::{ConfigureMessage, TradeFilter, VybeWebSocketConfig};
+
+// Mints and wallets added to the default program filters
+#[derive(Debug, Default)]
+struct Watched {
+    mints: BTreeSet<String>,
+    wallets: BTreeSet<String>,
+}
+
+static WATCHED: LazyLock<Mutex<Watched>> = LazyLock::new(|| Mutex::new(Watched::default()));
+
+// Subscription sent to the Vybe websocket, the default program filters plus watched mints
+// and wallets
+static CONFIGURE_MESSAGE: LazyLock<watch::Sender<ConfigureMessage>> =
+    LazyLock::new(|| watch::channel(configure_message(&Watched::default())).0);
+
+fn configure_message(watched: &Watched) -> ConfigureMessage {
+    let mut configure_message = VybeWebSocketConfig::default().configure_message;
+    let trades = configure_message
+        .filters
+        .trades
+        .get_or_insert_with(Vec::new);
+    trades.extend(watched.mints.iter().map(|mint| TradeFilter {
+        token_mint_address: Some(mint.clone()),
+        ..Default::default()
+    }));
+    trades.extend(watched.wallets.iter().map(|wallet| TradeFilter {
+        fee_payer: Some(wallet.clone()),
+        ..Default::default()
+    }));
+    configure_message
+}
+
+fn update_watched(update: impl FnOnce(&mut Watched)) {
+    let mut watched = WATCHED.lock().unwrap();
+    update(&mut watched);
+    let next = configure_message(&watched);
+    CONFIGURE_MESSAGE.send_if_modified(|current| {
+        let changed = serde_json::to_value(&*current).ok() != serde_json::to_value(&next).ok();
+        *current = next;
+        changed
+    });
+}
+
+/// Make sure trades of `mints` are part of the live subscription, replacing the previous set
+pub fn set_watched_mints(mints: BTreeSet<String>) {
+    update_watched(|watched| watched.mints = mints);
+}
+
+/// Make sure trades paid for by `wallets` are part of the live subscription, replacing the
+/// previous set
+pub fn set_tracked_wallets(wallets: BTreeSet<String>) {
+    update_watched(|watched| watched.wallets = wallets);
+}
+
+pub(crate) fn configure_updates() -> watch::Receiver<ConfigureMessage> {
+    CONFIGURE_MESSAGE.subscribe()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watched_mints_extend_default_filters() {
+        let defaults = configure_message(&Watched::default())
+            .filters
+            .trades
+            .unwrap()
+            .len();
+
+        let watched = Watched {
+            mints: BTreeSet::from(["DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string()]),
+            ..Default::default()
+        };
+        let trades = configure_message(&watched).filters.trades.unwrap();
+
+        assert_eq!(trades.len(), defaults + 1);
+        assert_eq!(
+            trades.last().unwrap().token_mint_address.as_deref(),
+            Some("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263")
+        );
+    }
+
+    #[test]
+    fn test_tracked_wallets_filter_on_fee_payer() {
+        let watched = Watched {
+            mints: BTreeSet::from(["DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string()]),
+            wallets: BTreeSet::from(["9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM".to_string()]),
+        };
+        let trades = configure_message(&watched).filters.trades.unwrap();
+
+        let wallet = trades.last().unwrap();
+        assert_eq!(
+            wallet.fee_payer.as_deref(),
+            Some("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM")
+        );
+        assert_eq!(wallet.token_mint_address, None);
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{Trade, VybeMessage};
+
+/// Fee payer and authority of every built trade
+pub const TEST_WALLET: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+/// A message as the Vybe live feed sends it, [`TEST_WALLET`] buying `base_size` of `base`
+/// with `quote_size` of `quote`
+pub fn vybe_message(base: &str, base_size: &str, quote: &str, quote_size: &str) -> VybeMessage {
+    let price = quote_size.parse::<f64>().unwrap() / base_size.parse::<f64>().unwrap();
+    serde_json::from_value(serde_json::json!({
+        "authorityAddress": TEST_WALLET,
+        "blockTime": 1718000000,
+        "iixOrdinal": 0,
+        "baseMintAddress": base,
+        "interIxOrdinal": 0,
+        "ixOrdinal": 2,
+        "marketId": "5s8xC2BvZ1kS8Ff1mLrCYdZ3TSPhZbEyzbdmFxJYHMJY",
+        "quoteMintAddress": quote,
+        "price": price.to_string(),
+        "programId": "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8",
+        "signature": "5sig",
+        "slot": 270000000,
+        "txIndex": 10,
+        "fee": "0.000005",
+        "feePayer": TEST_WALLET,
+        "baseSize": base_size,
+        "quoteSize": quote_size
+    }))
+    .unwrap()
+}
+
+/// The [`Trade`] of [`vybe_message`]
+pub fn trade(base: &str, base_size: &str, quote: &str, quote_size: &str) -> Trade {
+    Trade::from_message(vybe_message(base, base_size, quote, quote_size)).unwrap()
+}
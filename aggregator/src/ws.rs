@@ -1,7 +1,9 @@
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::watch;
 use tokio::time::sleep;
 use tokio_tungstenite::{
     connect_async, tungstenite::client::IntoClientRequest, tungstenite::protocol::Message,
@@ -71,7 +73,7 @@ impl TradingProgram {
 }
 
 // Rust equivalents of TypeScript interfaces
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct TradeFilter {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "tokenMintAddress")]
@@ -188,11 +190,11 @@ pub struct VybeMessage {
     pub quote_size: String,
 }
 
-// Callback types for event handlers
-pub type MessageCallback = Box<dyn Fn(VybeMessage) + Send + Sync>;
-pub type ConnectCallback = Box<dyn Fn() + Send + Sync>;
-pub type DisconnectCallback = Box<dyn Fn() + Send + Sync>;
-pub type ErrorCallback = Box<dyn Fn(String) + Send + Sync>;
+// Callback types for event handlers, shared so they survive reconnects
+pub type MessageCallback = Arc<dyn Fn(VybeMessage) + Send + Sync>;
+pub type ConnectCallback = Arc<dyn Fn() + Send + Sync>;
+pub type DisconnectCallback = Arc<dyn Fn() + Send + Sync>;
+pub type ErrorCallback = Arc<dyn Fn(String) + Send + Sync>;
 
 pub struct VybeWebSocketConfig {
     pub websocket_uri: String,
@@ -200,6 +202,8 @@ pub struct VybeWebSocketConfig {
     pub base_reconnect_delay: u64,
     pub reconnect: bool,
     pub configure_message: ConfigureMessage,
+    /// Replaces the subscription filters on the live connection whenever a new value is sent
+    pub configure_updates: Option<watch::Receiver<ConfigureMessage>>,
     pub on_message: Option<MessageCallback>,
     pub on_connect: Option<ConnectCallback>,
     pub on_disconnect: Option<DisconnectCallback>,
@@ -242,6 +246,7 @@ impl Default for VybeWebSocketConfig {
                     oracle_prices: None,
                 },
             },
+            configure_updates: None,
             on_message: None,
             on_connect: None,
             on_disconnect: None,
@@ -271,8 +276,8 @@ impl VybeWebSocket {
         self.shutdown_tx = Some(shutdown_tx);
 
         // Default callbacks if not provided
-        let on_message = self.config.on_message.take().unwrap_or_else(|| {
-            Box::new(|message: VybeMessage| {
+        let on_message = self.config.on_message.clone().unwrap_or_else(|| {
+            Arc::new(|message: VybeMessage| {
                 println!(
                     "Trade: {} tokens for {} USDC at price {}, signature: {}",
                     message.base_size, message.quote_size, message.price, message.signature
@@ -280,20 +285,20 @@ impl VybeWebSocket {
             })
         });
 
-        let on_connect = self.config.on_connect.take().unwrap_or_else(|| {
-            Box::new(|| {
+        let on_connect = self.config.on_connect.clone().unwrap_or_else(|| {
+            Arc::new(|| {
                 println!("Connected to WebSocket");
             })
         });
 
-        let on_disconnect = self.config.on_disconnect.take().unwrap_or_else(|| {
-            Box::new(|| {
+        let on_disconnect = self.config.on_disconnect.clone().unwrap_or_else(|| {
+            Arc::new(|| {
                 println!("Disconnected from WebSocket");
             })
         });
 
-        let on_error = self.config.on_error.take().unwrap_or_else(|| {
-            Box::new(|error: String| {
+        let on_error = self.config.on_error.clone().unwrap_or_else(|| {
+            Arc::new(|error: String| {
                 println!("WebSocket error: {}", error);
             })
         });
@@ -343,6 +348,11 @@ impl VybeWebSocket {
 
         on_connect();
 
+        // Pick up filter changes made while we were disconnected
+        if let Some(updates) = &mut self.config.configure_updates {
+            self.config.configure_message = updates.borrow_and_update().clone();
+        }
+
         // Send configure message
         let (mut write, mut read) = ws_stream.split();
         match serde_json::to_string(&self.config.configure_message) {
@@ -360,44 +370,55 @@ impl VybeWebSocket {
             }
         }
 
-        // Create a future that completes when the shutdown channel receives a message
-        let ws_task = async {
-            // Handle incoming messages
-            while let Some(message_result) = read.next().await {
-                match message_result {
-                    Ok(message) => match message {
+        let mut configure_updates = self.config.configure_updates.take();
+
+        // Handle incoming messages and filter updates until the connection drops or shuts down
+        let reconnect = loop {
+            tokio::select! {
+                message_result = read.next() => match message_result {
+                    Some(Ok(message)) => match message {
                         Message::Text(text) => match serde_json::from_str::<VybeMessage>(&text) {
                             Ok(parsed_message) => on_message(parsed_message),
                             Err(e) => on_error(format!("Failed to parse message: {}", e)),
                         },
                         Message::Close(_) => {
                             on_disconnect();
-                            self.handle_reconnect(&on_disconnect, &on_error).await;
-                            break;
+                            break true;
                         }
                         _ => {} // Ignore other message types
                     },
-                    Err(e) => {
+                    Some(Err(e)) => {
                         on_error(format!("WebSocket error: {}", e));
-                        self.handle_reconnect(&on_disconnect, &on_error).await;
-                        break;
+                        break true;
+                    }
+                    // WebSocket stream completed naturally
+                    None => break false,
+                },
+                configure_message = next_configure_message(&mut configure_updates) => {
+                    match serde_json::to_string(&configure_message) {
+                        Ok(text) => {
+                            if let Err(e) = write.send(Message::Text(text)).await {
+                                on_error(format!("Failed to send configure message: {}", e));
+                            }
+                        }
+                        Err(e) => on_error(format!("Failed to serialize configure message: {}", e)),
+                    }
+                    self.config.configure_message = configure_message;
+                }
+                _ = shutdown_rx.recv() => {
+                    // Received shutdown signal, close the WebSocket connection gracefully
+                    if let Err(e) = write.send(Message::Close(None)).await {
+                        on_error(format!("Failed to close WebSocket: {}", e));
                     }
+                    on_disconnect();
+                    break false;
                 }
             }
         };
 
-        // Use tokio::select to race between the WebSocket task and the shutdown signal
-        tokio::select! {
-            _ = ws_task => {
-                // WebSocket task completed naturally
-            }
-            _ = shutdown_rx.recv() => {
-                // Received shutdown signal, close the WebSocket connection gracefully
-                if let Err(e) = write.send(Message::Close(None)).await {
-                    on_error(format!("Failed to close WebSocket: {}", e));
-                }
-                on_disconnect();
-            }
+        self.config.configure_updates = configure_updates;
+        if reconnect {
+            self.handle_reconnect(&on_disconnect, &on_error).await;
         }
     }
 
@@ -421,6 +442,11 @@ impl VybeWebSocket {
         let base_reconnect_delay = self.config.base_reconnect_delay;
         let reconnect = self.config.reconnect;
         let configure_message = self.config.configure_message.clone();
+        let configure_updates = self.config.configure_updates.clone();
+        let on_message = self.config.on_message.clone();
+        let on_connect = self.config.on_connect.clone();
+        let on_disconnect = self.config.on_disconnect.clone();
+        let on_error = self.config.on_error.clone();
 
         // Spawn a new task to handle reconnection
         tokio::spawn(async move {
@@ -430,10 +456,11 @@ impl VybeWebSocket {
                 base_reconnect_delay,
                 reconnect,
                 configure_message,
-                on_message: None,
-                on_connect: None,
-                on_disconnect: None,
-                on_error: None,
+                configure_updates,
+                on_message,
+                on_connect,
+                on_disconnect,
+                on_error,
             });
             new_ws.connect().await;
         });
@@ -451,3 +478,15 @@ impl VybeWebSocket {
         self.config.reconnect = false;
     }
 }
+
+// Wait for the next subscription change, never resolves without an update channel
+async fn next_configure_message(
+    updates: &mut Option<watch::Receiver<ConfigureMessage>>,
+) -> ConfigureMessage {
+    if let Some(updates) = updates {
+        if updates.changed().await.is_ok() {
+            return updates.borrow_and_update().clone();
+        }
+    }
+    std::future::pending().await
+}
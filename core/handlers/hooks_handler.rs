@@ -1,19 +1,34 @@
-use axum::{http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    body::Bytes,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
 use serde_json::json;
-// use telegram::telegram_bot_entrypoint;
-// use tokio;
+use telegram::webhook::{feed_update, WebhookError, SECRET_TOKEN_HEADER};
 
-pub async fn telegram_hook(Json(body): Json<serde_json::Value>) -> impl IntoResponse {
-    // tokio::spawn(async move {
-    // telegram_bot_entrypoint().await;
-    // });
+pub async fn telegram_hook(headers: HeaderMap, body: Bytes) -> impl IntoResponse {
+    let secret = headers
+        .get(SECRET_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok());
 
-    tracing::info!("Received telegram webhook request: {:?}", body);
+    let status = match feed_update(secret, &body) {
+        Ok(()) => StatusCode::OK,
+        Err(WebhookError::Disabled) => StatusCode::SERVICE_UNAVAILABLE,
+        Err(WebhookError::Unauthorized) => StatusCode::UNAUTHORIZED,
+        Err(err @ WebhookError::InvalidUpdate(_)) => {
+            // Telegram keeps redelivering rejected updates, so acknowledge the ones we can't read
+            tracing::warn!("Dropping telegram webhook update: {}", err);
+            StatusCode::OK
+        }
+        // Telegram retries later, by then the dispatcher has caught up
+        Err(WebhookError::Busy) => StatusCode::TOO_MANY_REQUESTS,
+    };
 
     (
-        StatusCode::OK,
+        status,
         Json(json!({
-            "message": "Telegram worker service spawned!"
+            "ok": status == StatusCode::OK
         })),
     )
 }
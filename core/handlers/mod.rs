@@ -1,3 +1,5 @@
 mod hooks_handler;
+mod trades_handler;
 
 pub use hooks_handler::*;
+pub use trades_handler::*;
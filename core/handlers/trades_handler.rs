@@ -0,0 +1,76 @@
+use aggregator::{subscribe_filtered, TradeSubscription};
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Query,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+};
+use futures_util::{stream, Stream};
+
+// Maximum number of trades queued per connection before the client is considered too slow
+const CLIENT_BUFFER: usize = 256;
+
+pub async fn trades_ws(
+    ws: WebSocketUpgrade,
+    Query(subscription): Query<TradeSubscription>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| stream_trades_ws(socket, subscription))
+}
+
+async fn stream_trades_ws(mut socket: WebSocket, subscription: TradeSubscription) {
+    let mut trades = subscribe_filtered(subscription, CLIENT_BUFFER);
+
+    loop {
+        tokio::select! {
+            trade = trades.recv() => {
+                let Some(trade) = trade else {
+                    // The feed dropped us, most likely because we could not keep up
+                    let _ = socket
+                        .send(Message::Close(Some(CloseFrame {
+                            code: close_code::AGAIN,
+                            reason: "Client too slow, trade stream closed".into(),
+                        })))
+                        .await;
+                    break;
+                };
+
+                let text = match serde_json::to_string(&trade) {
+                    Ok(text) => text,
+                    Err(err) => {
+                        tracing::warn!("Failed to serialize trade: {:?}", err);
+                        continue;
+                    }
+                };
+
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by axum, anything else from the client is ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    tracing::debug!("Trade websocket client disconnected");
+}
+
+pub async fn trades_sse(
+    Query(subscription): Query<TradeSubscription>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let trades = subscribe_filtered(subscription, CLIENT_BUFFER);
+
+    // The stream ends when the feed drops a slow client, which closes the response
+    let events = stream::unfold(trades, |mut trades| async move {
+        let trade = trades.recv().await?;
+        Some((Event::default().event("trade").json_data(trade), trades))
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
@@ -6,12 +6,16 @@ use axum::{
 };
 use serde_json::json;
 
-use crate::{handlers::telegram_hook, utils::route};
+use crate::{
+    handlers::{telegram_hook, trades_sse, trades_ws},
+    utils::route,
+};
 
 pub fn new_router() -> Router {
     Router::new()
         .route("/", get(hello_world))
         .merge(hook_routes())
+        .merge(trade_routes())
 }
 
 async fn hello_world() -> impl IntoResponse {
@@ -26,3 +30,7 @@ async fn hello_world() -> impl IntoResponse {
 fn hook_routes() -> Router {
     route("/hook", post(telegram_hook))
 }
+
+fn trade_routes() -> Router {
+    route("/ws/trades", get(trades_ws)).merge(route("/sse/trades", get(trades_sse)))
+}
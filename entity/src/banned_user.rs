@@ -0,0 +1,97 @@
+use sea_orm::{entity::prelude::*, sea_query::OnConflict, ActiveValue::Set, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+/// Users the bot ignores, set by admins with `/ban`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "banned_users")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub telegram_id: i64,
+    /// Telegram id of the admin who banned the user
+    pub banned_by: i64,
+    pub reason: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    /// Ban a user, banning them again replaces the reason
+    pub async fn ban<C>(
+        db: &C,
+        telegram_id: i64,
+        banned_by: i64,
+        reason: Option<String>,
+    ) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::insert(ActiveModel {
+            telegram_id: Set(telegram_id),
+            banned_by: Set(banned_by),
+            reason: Set(reason),
+            created_at: Set(chrono::Utc::now()),
+        })
+        .on_conflict(
+            OnConflict::column(Column::TelegramId)
+                .update_columns([Column::BannedBy, Column::Reason, Column::CreatedAt])
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await
+        .map(|_| ())
+    }
+
+    /// Lift a ban, returns whether the user was banned
+    pub async fn unban<C>(db: &C, telegram_id: i64) -> Result<bool, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let result = Entity::delete_by_id(telegram_id).exec(db).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    pub async fn find_ids<C>(db: &C) -> Result<Vec<i64>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .select_only()
+            .column(Column::TelegramId)
+            .into_tuple()
+            .all(db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+
+    #[tokio::test]
+    async fn test_ban_and_unban() {
+        let db = test_db().await;
+        let telegram_id = chrono::Utc::now().timestamp_micros();
+
+        Entity::ban(&db, telegram_id, 1, None).await.unwrap();
+        Entity::ban(&db, telegram_id, 2, Some("spam".to_string()))
+            .await
+            .unwrap();
+        let banned = Entity::find_by_id(telegram_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(banned.banned_by, 2);
+        assert_eq!(banned.reason.as_deref(), Some("spam"));
+        assert!(Entity::find_ids(&db).await.unwrap().contains(&telegram_id));
+
+        assert!(Entity::unban(&db, telegram_id).await.unwrap());
+        assert!(!Entity::unban(&db, telegram_id).await.unwrap());
+        assert!(!Entity::find_ids(&db).await.unwrap().contains(&telegram_id));
+    }
+}
@@ -0,0 +1,172 @@
+use sea_orm::{entity::prelude::*, sea_query::OnConflict, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+/// Per group chat settings, chats without a row use [`Model::new`]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "chat_settings")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub chat_id: i64,
+    /// Reply with a token card to any message containing a mint address
+    pub auto_cards: bool,
+    pub compact_cards: bool,
+    /// Auto cards are muted from this UTC hour...
+    pub mute_start_hour: Option<i16>,
+    /// ...until this UTC hour, wrapping around midnight
+    pub mute_end_hour: Option<i16>,
+    /// Names of commands the group turned off, as a JSON array
+    #[sea_orm(column_type = "JsonBinary")]
+    pub disabled_commands: Json,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub fn new(chat_id: i64) -> Self {
+        Self {
+            chat_id,
+            auto_cards: true,
+            compact_cards: false,
+            mute_start_hour: None,
+            mute_end_hour: None,
+            disabled_commands: Json::Array(Vec::new()),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Whether `hour` (UTC, 0-23) falls in the mute window
+    pub fn is_muted_at(&self, hour: i16) -> bool {
+        match (self.mute_start_hour, self.mute_end_hour) {
+            (Some(start), Some(end)) if start <= end => (start..end).contains(&hour),
+            (Some(start), Some(end)) => hour >= start || hour < end,
+            _ => false,
+        }
+    }
+
+    pub fn is_command_disabled(&self, command: &str) -> bool {
+        self.disabled_commands
+            .as_array()
+            .is_some_and(|commands| commands.iter().any(|c| c.as_str() == Some(command)))
+    }
+
+    /// Turn a command off when it is on and back on when it is off
+    pub fn toggle_command(&mut self, command: &str) {
+        let mut commands = self
+            .disabled_commands
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+
+        if self.is_command_disabled(command) {
+            commands.retain(|c| c.as_str() != Some(command));
+        } else {
+            commands.push(Json::from(command));
+        }
+        self.disabled_commands = Json::Array(commands);
+    }
+}
+
+impl Entity {
+    /// The settings of a chat, or the defaults when it never changed them
+    pub async fn find_or_default<C>(db: &C, chat_id: i64) -> Result<Model, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Ok(Entity::find_by_id(chat_id)
+            .one(db)
+            .await?
+            .unwrap_or_else(|| Model::new(chat_id)))
+    }
+
+    /// Insert or overwrite the settings of a chat
+    pub async fn save<C>(db: &C, settings: Model) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::insert(ActiveModel {
+            chat_id: Set(settings.chat_id),
+            auto_cards: Set(settings.auto_cards),
+            compact_cards: Set(settings.compact_cards),
+            mute_start_hour: Set(settings.mute_start_hour),
+            mute_end_hour: Set(settings.mute_end_hour),
+            disabled_commands: Set(settings.disabled_commands),
+            updated_at: Set(chrono::Utc::now()),
+        })
+        .on_conflict(
+            OnConflict::column(Column::ChatId)
+                .update_columns([
+                    Column::AutoCards,
+                    Column::CompactCards,
+                    Column::MuteStartHour,
+                    Column::MuteEndHour,
+                    Column::DisabledCommands,
+                    Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+
+    #[test]
+    fn test_is_muted_at() {
+        let mut settings = Model::new(1);
+        assert!(!settings.is_muted_at(3));
+
+        settings.mute_start_hour = Some(22);
+        settings.mute_end_hour = Some(6);
+        assert!(settings.is_muted_at(23));
+        assert!(settings.is_muted_at(0));
+        assert!(!settings.is_muted_at(6));
+        assert!(!settings.is_muted_at(12));
+
+        settings.mute_start_hour = Some(9);
+        settings.mute_end_hour = Some(17);
+        assert!(settings.is_muted_at(9));
+        assert!(!settings.is_muted_at(17));
+        assert!(!settings.is_muted_at(20));
+    }
+
+    #[test]
+    fn test_toggle_command() {
+        let mut settings = Model::new(1);
+        assert!(!settings.is_command_disabled("watch"));
+
+        settings.toggle_command("watch");
+        assert!(settings.is_command_disabled("watch"));
+        assert!(!settings.is_command_disabled("alert"));
+
+        settings.toggle_command("watch");
+        assert!(!settings.is_command_disabled("watch"));
+    }
+
+    #[tokio::test]
+    async fn test_save_and_find_or_default() {
+        let db = test_db().await;
+        let chat_id = -chrono::Utc::now().timestamp_micros();
+
+        let mut settings = Entity::find_or_default(&db, chat_id).await.unwrap();
+        assert!(settings.auto_cards);
+
+        settings.auto_cards = false;
+        settings.toggle_command("alert");
+        Entity::save(&db, settings).await.unwrap();
+
+        let stored = Entity::find_or_default(&db, chat_id).await.unwrap();
+        assert!(!stored.auto_cards);
+        assert!(stored.is_command_disabled("alert"));
+
+        Entity::delete_by_id(chat_id).exec(&db).await.unwrap();
+    }
+}
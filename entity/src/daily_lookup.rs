@@ -0,0 +1,81 @@
+use sea_orm::{
+    entity::prelude::*,
+    sea_query::{Alias, OnConflict},
+    ActiveValue::Set,
+    QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+
+/// Token lookups answered per UTC day, for `/stats`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "daily_lookups")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub day: Date,
+    pub lookups: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    /// Add `lookups` to the count of `day`
+    pub async fn add<C>(db: &C, day: Date, lookups: i64) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::insert(ActiveModel {
+            day: Set(day),
+            lookups: Set(lookups),
+        })
+        .on_conflict(
+            OnConflict::column(Column::Day)
+                .value(
+                    Column::Lookups,
+                    Expr::col((Entity, Column::Lookups))
+                        .add(Expr::col((Alias::new("excluded"), Column::Lookups))),
+                )
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await
+        .map(|_| ())
+    }
+
+    /// Counts from `day` on, newest first
+    pub async fn find_since<C>(db: &C, day: Date) -> Result<Vec<Model>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .filter(Column::Day.gte(day))
+            .order_by_desc(Column::Day)
+            .all(db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+
+    #[tokio::test]
+    async fn test_add_accumulates() {
+        let db = test_db().await;
+        // A day far in the past so concurrent runs of the bot don't interfere
+        let day = Date::from_ymd_opt(1971, 1, 1).unwrap()
+            + chrono::Days::new(chrono::Utc::now().timestamp_micros() as u64 % 10_000);
+
+        Entity::add(&db, day, 3).await.unwrap();
+        Entity::add(&db, day, 4).await.unwrap();
+
+        let counts = Entity::find_since(&db, day).await.unwrap();
+        let count = counts.iter().find(|count| count.day == day).unwrap();
+        assert_eq!(count.lookups, 7);
+
+        Entity::delete_by_id(day).exec(&db).await.unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+use sea_orm::{entity::prelude::*, sea_query::OnConflict, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "dialogue_states")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub chat_id: i64,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub state: Json,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Whether the dialogue was abandoned for longer than `ttl` at `now`, a `ttl` too large
+    /// to add to a date never expires
+    pub fn is_expired(&self, now: DateTimeUtc, ttl: chrono::Duration) -> bool {
+        self.updated_at
+            .checked_add_signed(ttl)
+            .is_some_and(|expires_at| expires_at < now)
+    }
+}
+
+impl Entity {
+    /// Store the dialogue state of a chat, replacing the previous one
+    pub async fn upsert<C>(db: &C, chat_id: i64, state: Json) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::insert(ActiveModel {
+            chat_id: Set(chat_id),
+            state: Set(state),
+            updated_at: Set(chrono::Utc::now()),
+        })
+        .on_conflict(
+            OnConflict::column(Column::ChatId)
+                .update_columns([Column::State, Column::UpdatedAt])
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await
+        .map(|_| ())
+    }
+
+    /// Remove every dialogue last touched before `before`, returning how many were removed
+    pub async fn delete_expired<C>(db: &C, before: DateTimeUtc) -> Result<u64, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::delete_many()
+            .filter(Column::UpdatedAt.lt(before))
+            .exec(db)
+            .await
+            .map(|result| result.rows_affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+
+    #[test]
+    fn test_is_expired() {
+        let now = chrono::Utc::now();
+        let model = Model {
+            chat_id: 1,
+            state: Json::from("Idle"),
+            updated_at: now - chrono::Duration::hours(2),
+        };
+
+        assert!(model.is_expired(now, chrono::Duration::hours(1)));
+        assert!(!model.is_expired(now, chrono::Duration::hours(3)));
+        assert!(!model.is_expired(now, chrono::Duration::MAX));
+        assert!(!model.is_expired(now, chrono::Duration::seconds(10_i64.pow(13))));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_and_delete_expired() {
+        let db = test_db().await;
+        let chat_id = chrono::Utc::now().timestamp_micros();
+
+        Entity::upsert(&db, chat_id, Json::from("Idle"))
+            .await
+            .unwrap();
+        Entity::upsert(&db, chat_id, Json::from("Start"))
+            .await
+            .unwrap();
+
+        let stored = Entity::find_by_id(chat_id).one(&db).await.unwrap().unwrap();
+        assert_eq!(stored.state, Json::from("Start"));
+
+        let removed = Entity::delete_expired(&db, stored.updated_at + chrono::Duration::seconds(1))
+            .await
+            .unwrap();
+        assert!(removed >= 1);
+        assert!(Entity::find_by_id(chat_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
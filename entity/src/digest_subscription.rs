@@ -0,0 +1,180 @@
+use sea_orm::{entity::prelude::*, sea_query::OnConflict, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// A user receiving the daily watchlist digest
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "digest_subscriptions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub telegram_id: i64,
+    /// Chat the digest is sent to
+    pub chat_id: i64,
+    /// Local time of day to send at, in minutes after midnight
+    pub local_minute: i16,
+    /// Offset of the user's local time from UTC
+    pub utc_offset_minutes: i16,
+    pub next_run_at: DateTimeUtc,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// First time strictly after `now` when the local clock shows `local_minute`
+pub fn next_run_after(now: DateTimeUtc, local_minute: i16, utc_offset_minutes: i16) -> DateTimeUtc {
+    let utc_minute = (local_minute as i64 - utc_offset_minutes as i64).rem_euclid(MINUTES_PER_DAY);
+    let midnight = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight exists")
+        .and_utc();
+
+    let today = midnight + chrono::Duration::minutes(utc_minute);
+    if today > now {
+        today
+    } else {
+        today + chrono::Duration::days(1)
+    }
+}
+
+impl Model {
+    pub fn next_run_after(&self, now: DateTimeUtc) -> DateTimeUtc {
+        next_run_after(now, self.local_minute, self.utc_offset_minutes)
+    }
+}
+
+impl Entity {
+    /// Subscribe a user or change their time, scheduling the next digest after `now`
+    pub async fn subscribe<C>(
+        db: &C,
+        telegram_id: i64,
+        chat_id: i64,
+        local_minute: i16,
+        utc_offset_minutes: i16,
+        now: DateTimeUtc,
+    ) -> Result<Model, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::insert(ActiveModel {
+            telegram_id: Set(telegram_id),
+            chat_id: Set(chat_id),
+            local_minute: Set(local_minute),
+            utc_offset_minutes: Set(utc_offset_minutes),
+            next_run_at: Set(next_run_after(now, local_minute, utc_offset_minutes)),
+            created_at: Set(now),
+        })
+        .on_conflict(
+            OnConflict::column(Column::TelegramId)
+                .update_columns([
+                    Column::ChatId,
+                    Column::LocalMinute,
+                    Column::UtcOffsetMinutes,
+                    Column::NextRunAt,
+                ])
+                .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await
+    }
+
+    /// Returns whether the user was subscribed
+    pub async fn unsubscribe<C>(db: &C, telegram_id: i64) -> Result<bool, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let result = Entity::delete_by_id(telegram_id).exec(db).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Subscriptions whose digest is due at `now`
+    pub async fn find_due<C>(db: &C, now: DateTimeUtc) -> Result<Vec<Model>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .filter(Column::NextRunAt.lte(now))
+            .all(db)
+            .await
+    }
+
+    /// Move a due subscription to its next run, returns false when another instance
+    /// already did so and will send the digest instead
+    pub async fn claim<C>(db: &C, subscription: &Model, now: DateTimeUtc) -> Result<bool, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let result = Entity::update_many()
+            .col_expr(
+                Column::NextRunAt,
+                Expr::value(subscription.next_run_after(now)),
+            )
+            .filter(Column::TelegramId.eq(subscription.telegram_id))
+            .filter(Column::NextRunAt.eq(subscription.next_run_at))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::test_support::test_db;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTimeUtc {
+        chrono::Utc
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_next_run_after() {
+        // 09:00 UTC
+        assert_eq!(next_run_after(at(19, 8, 0), 9 * 60, 0), at(19, 9, 0));
+        assert_eq!(next_run_after(at(19, 9, 0), 9 * 60, 0), at(20, 9, 0));
+        // 09:00 at UTC+2 is 07:00 UTC
+        assert_eq!(next_run_after(at(19, 8, 0), 9 * 60, 120), at(20, 7, 0));
+        // 20:00 at UTC-5 is 01:00 UTC the next day
+        assert_eq!(next_run_after(at(19, 23, 0), 20 * 60, -300), at(20, 1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_claim_once() {
+        let db = test_db().await;
+        let telegram_id = chrono::Utc::now().timestamp_micros();
+
+        let subscription =
+            Entity::subscribe(&db, telegram_id, telegram_id, 9 * 60, 0, at(19, 8, 0))
+                .await
+                .unwrap();
+        assert_eq!(subscription.next_run_at, at(19, 9, 0));
+
+        let due = |now| {
+            let db = &db;
+            async move {
+                Entity::find_due(db, now)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .any(|due| due.telegram_id == telegram_id)
+            }
+        };
+        assert!(!due(at(19, 8, 59)).await);
+        assert!(due(at(19, 9, 0)).await);
+
+        // Two instances see the same due row, only the first claim wins
+        let now = at(19, 9, 1);
+        assert!(Entity::claim(&db, &subscription, now).await.unwrap());
+        assert!(!Entity::claim(&db, &subscription, now).await.unwrap());
+        assert!(!due(now).await);
+        assert!(due(at(20, 9, 0)).await);
+
+        assert!(Entity::unsubscribe(&db, telegram_id).await.unwrap());
+    }
+}
@@ -4,7 +4,22 @@ use std::sync::OnceLock;
 use tokio::sync::OnceCell;
 use utils::ENV_CONFIG;
 
+pub mod banned_user;
+pub mod chat_setting;
+pub mod daily_lookup;
+pub mod dialogue_state;
+pub mod digest_subscription;
+pub mod price_alert;
+pub mod referral;
+pub mod referral_code;
 pub mod tg_user;
+pub mod token_registry;
+pub mod tracked_wallet;
+pub mod trade_follow;
+pub mod watchlist_entry;
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 
 static DB_CONN: OnceCell<DatabaseConnection> = OnceCell::const_new();
 
@@ -0,0 +1,161 @@
+use sea_orm::{entity::prelude::*, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+pub enum AlertCondition {
+    #[sea_orm(string_value = "above")]
+    Above,
+    #[sea_orm(string_value = "below")]
+    Below,
+    /// Move of `target` percent (signed) from `reference_price`
+    #[sea_orm(string_value = "percent")]
+    PercentMove,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "price_alerts")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub telegram_id: i64,
+    pub chat_id: i64,
+    pub mint_address: String,
+    pub symbol: Option<String>,
+    pub condition: AlertCondition,
+    pub target: f64,
+    pub reference_price: Option<f64>,
+    pub repeating: bool,
+    pub cooldown_secs: i64,
+    pub active: bool,
+    pub last_triggered_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Whether `price` (USD) satisfies the alert condition
+    pub fn is_triggered(&self, price: f64) -> bool {
+        match self.condition {
+            AlertCondition::Above => price >= self.target,
+            AlertCondition::Below => price <= self.target,
+            AlertCondition::PercentMove => match self.reference_price {
+                Some(reference) if reference > 0.0 => {
+                    let change = (price - reference) / reference * 100.0;
+                    if self.target >= 0.0 {
+                        change >= self.target
+                    } else {
+                        change <= self.target
+                    }
+                }
+                _ => false,
+            },
+        }
+    }
+
+    /// Whether a repeating alert fired too recently to fire again at `now`
+    pub fn in_cooldown(&self, now: DateTimeUtc) -> bool {
+        self.last_triggered_at.is_some_and(|triggered_at| {
+            now < triggered_at + chrono::Duration::seconds(self.cooldown_secs)
+        })
+    }
+}
+
+impl Entity {
+    /// Every alert that can still fire
+    pub async fn find_active<C>(db: &C) -> Result<Vec<Model>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find().filter(Column::Active.eq(true)).all(db).await
+    }
+
+    /// Active alerts owned by a Telegram user, oldest first
+    pub async fn find_active_by_user<C>(db: &C, telegram_id: i64) -> Result<Vec<Model>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .filter(Column::TelegramId.eq(telegram_id))
+            .filter(Column::Active.eq(true))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// Alerts of a Telegram user that fired since `since`, active or not
+    pub async fn find_triggered_since<C>(
+        db: &C,
+        telegram_id: i64,
+        since: DateTimeUtc,
+    ) -> Result<Vec<Model>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .filter(Column::TelegramId.eq(telegram_id))
+            .filter(Column::LastTriggeredAt.gte(since))
+            .order_by_desc(Column::LastTriggeredAt)
+            .all(db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(condition: AlertCondition, target: f64, reference_price: Option<f64>) -> Model {
+        Model {
+            id: 1,
+            telegram_id: 1,
+            chat_id: 1,
+            mint_address: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+            symbol: None,
+            condition,
+            target,
+            reference_price,
+            repeating: false,
+            cooldown_secs: 3600,
+            active: true,
+            last_triggered_at: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_is_triggered() {
+        assert!(alert(AlertCondition::Above, 2.0, None).is_triggered(2.5));
+        assert!(!alert(AlertCondition::Above, 2.0, None).is_triggered(1.5));
+        assert!(alert(AlertCondition::Below, 2.0, None).is_triggered(1.5));
+        assert!(!alert(AlertCondition::Below, 2.0, None).is_triggered(2.5));
+
+        let up = alert(AlertCondition::PercentMove, 20.0, Some(1.0));
+        assert!(up.is_triggered(1.25));
+        assert!(!up.is_triggered(1.1));
+        assert!(!up.is_triggered(0.5));
+
+        let down = alert(AlertCondition::PercentMove, -15.0, Some(1.0));
+        assert!(down.is_triggered(0.8));
+        assert!(!down.is_triggered(0.9));
+
+        assert!(!alert(AlertCondition::PercentMove, 20.0, None).is_triggered(100.0));
+    }
+
+    #[test]
+    fn test_in_cooldown() {
+        let now = chrono::Utc::now();
+        let mut model = alert(AlertCondition::Above, 1.0, None);
+        assert!(!model.in_cooldown(now));
+
+        model.last_triggered_at = Some(now - chrono::Duration::minutes(30));
+        assert!(model.in_cooldown(now));
+
+        model.last_triggered_at = Some(now - chrono::Duration::hours(2));
+        assert!(!model.in_cooldown(now));
+    }
+}
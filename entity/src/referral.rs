@@ -0,0 +1,113 @@
+use sea_orm::{entity::prelude::*, sea_query::OnConflict, ActiveValue::Set, PaginatorTrait};
+use serde::{Deserialize, Serialize};
+
+/// A user who started the bot from someone else's referral link
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "referrals")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub referrer_id: i64,
+    #[sea_orm(unique)]
+    pub referred_id: i64,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    /// Record who referred a user, returns false when the user was already referred
+    pub async fn record<C>(db: &C, referrer_id: i64, referred_id: i64) -> Result<bool, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let inserted = Entity::insert(ActiveModel {
+            referrer_id: Set(referrer_id),
+            referred_id: Set(referred_id),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::column(Column::ReferredId)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+        Ok(inserted > 0)
+    }
+
+    /// Users referred by someone, only those referred after `since` when given
+    pub async fn count_by_referrer<C>(
+        db: &C,
+        referrer_id: i64,
+        since: Option<DateTimeUtc>,
+    ) -> Result<u64, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let mut query = Entity::find().filter(Column::ReferrerId.eq(referrer_id));
+        if let Some(since) = since {
+            query = query.filter(Column::CreatedAt.gte(since));
+        }
+        query.count(db).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+
+    #[tokio::test]
+    async fn test_record_and_count() {
+        let db = test_db().await;
+        let referrer_id = chrono::Utc::now().timestamp_micros();
+        let started = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+        assert!(Entity::record(&db, referrer_id, referrer_id + 1)
+            .await
+            .unwrap());
+        assert!(Entity::record(&db, referrer_id, referrer_id + 2)
+            .await
+            .unwrap());
+        // The first referrer wins
+        assert!(!Entity::record(&db, referrer_id + 3, referrer_id + 1)
+            .await
+            .unwrap());
+
+        assert_eq!(
+            Entity::count_by_referrer(&db, referrer_id, None)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            Entity::count_by_referrer(&db, referrer_id, Some(started))
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            Entity::count_by_referrer(&db, referrer_id, Some(chrono::Utc::now()))
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            Entity::count_by_referrer(&db, referrer_id + 3, None)
+                .await
+                .unwrap(),
+            0
+        );
+
+        Entity::delete_many()
+            .filter(Column::ReferrerId.eq(referrer_id))
+            .exec(&db)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+use sea_orm::{entity::prelude::*, sea_query::OnConflict, ActiveValue::Set};
+use serde::{Deserialize, Serialize};
+
+/// The code a user shares in `/start` links to invite others, see `/referrals`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "referral_codes")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub telegram_id: i64,
+    #[sea_orm(unique)]
+    pub code: String,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    /// Give a user `code` unless they already have one, returns the code they end up with.
+    ///
+    /// Fails with a unique violation when another user holds `code`.
+    pub async fn get_or_create<C>(db: &C, telegram_id: i64, code: &str) -> Result<Model, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::insert(ActiveModel {
+            telegram_id: Set(telegram_id),
+            code: Set(code.to_string()),
+            created_at: Set(chrono::Utc::now()),
+        })
+        .on_conflict(
+            OnConflict::column(Column::TelegramId)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+        Entity::find_by_id(telegram_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("referral code of {}", telegram_id)))
+    }
+
+    pub async fn find_by_code<C>(db: &C, code: &str) -> Result<Option<Model>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find().filter(Column::Code.eq(code)).one(db).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+    use sea_orm::SqlErr;
+
+    #[tokio::test]
+    async fn test_code_is_kept_and_unique() {
+        let db = test_db().await;
+        let telegram_id = chrono::Utc::now().timestamp_micros();
+        let code = format!("t{}", telegram_id);
+
+        let created = Entity::get_or_create(&db, telegram_id, &code)
+            .await
+            .unwrap();
+        assert_eq!(created.code, code);
+
+        // Later calls keep the first code
+        let kept = Entity::get_or_create(&db, telegram_id, "other")
+            .await
+            .unwrap();
+        assert_eq!(kept.code, code);
+        assert_eq!(
+            Entity::find_by_code(&db, &code).await.unwrap(),
+            Some(created.clone())
+        );
+
+        // Nobody else can get the same code
+        let err = Entity::get_or_create(&db, telegram_id + 1, &code)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.sql_err(),
+            Some(SqlErr::UniqueConstraintViolation(_))
+        ));
+
+        Entity::delete_by_id(telegram_id).exec(&db).await.unwrap();
+    }
+}
@@ -0,0 +1,19 @@
+//! Fixtures for tests that need a database, shared with the crates built on `entity`
+
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{Database, DatabaseConnection};
+
+/// An SQLite database living in memory, for tests that can't count on a Postgres server
+pub const IN_MEMORY_DATABASE_URL: &str = "sqlite::memory:";
+
+/// Bring the schema of `db` up to date
+pub async fn migrate(db: &DatabaseConnection) {
+    Migrator::up(db, None).await.unwrap();
+}
+
+/// A fresh in-memory database with the schema up to date
+pub async fn test_db() -> DatabaseConnection {
+    let db = Database::connect(IN_MEMORY_DATABASE_URL).await.unwrap();
+    migrate(&db).await;
+    db
+}
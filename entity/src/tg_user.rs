@@ -1,4 +1,4 @@
-use sea_orm::entity::prelude::*;
+use sea_orm::{entity::prelude::*, sea_query::OnConflict, QueryOrder, QuerySelect};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
@@ -7,11 +7,166 @@ pub struct Model {
     #[sea_orm(primary_key)]
     // #[serde(skip_deserializing)]
     pub id: i32,
+    #[sea_orm(unique)]
     pub telegram_id: i64,
     pub username: Option<String>,
+    pub first_name: String,
+    pub last_name: Option<String>,
+    pub language_code: Option<String>,
+    /// Language picked with `/language`, overrides `language_code`
+    pub language: Option<String>,
+    pub created_at: DateTimeUtc,
+    pub last_seen_at: DateTimeUtc,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    /// Insert a user, or refresh the profile and `last_seen_at` of an existing one
+    pub async fn upsert<C>(db: &C, user: ActiveModel) -> Result<Model, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::insert(user)
+            .on_conflict(
+                OnConflict::column(Column::TelegramId)
+                    .update_columns([
+                        Column::Username,
+                        Column::FirstName,
+                        Column::LastName,
+                        Column::LanguageCode,
+                        Column::LastSeenAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_with_returning(db)
+            .await
+    }
+
+    pub async fn find_by_telegram_id<C>(db: &C, telegram_id: i64) -> Result<Option<Model>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .filter(Column::TelegramId.eq(telegram_id))
+            .one(db)
+            .await
+    }
+
+    /// Store the `/language` override of a user, `None` goes back to the Telegram language.
+    ///
+    /// Returns whether the user exists, users are created by `/start`.
+    pub async fn set_language<C>(
+        db: &C,
+        telegram_id: i64,
+        language: Option<String>,
+    ) -> Result<bool, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let result = Entity::update_many()
+            .col_expr(Column::Language, Expr::value(language))
+            .filter(Column::TelegramId.eq(telegram_id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Every user who started the bot, for broadcasts
+    pub async fn find_telegram_ids<C>(db: &C) -> Result<Vec<i64>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .select_only()
+            .column(Column::TelegramId)
+            .order_by_asc(Column::Id)
+            .into_tuple()
+            .all(db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+    use sea_orm::{ActiveValue::Set, DatabaseConnection};
+
+    fn user(telegram_id: i64, username: &str, seen_at: DateTimeUtc) -> ActiveModel {
+        ActiveModel {
+            telegram_id: Set(telegram_id),
+            username: Set(Some(username.to_string())),
+            first_name: Set("Pixa".to_string()),
+            last_name: Set(None),
+            language_code: Set(Some("en".to_string())),
+            created_at: Set(seen_at),
+            last_seen_at: Set(seen_at),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_inserts_then_updates() {
+        let db = test_db().await;
+        let telegram_id = chrono::Utc::now().timestamp_micros();
+        let first_seen = chrono::Utc::now();
+
+        let inserted = Entity::upsert(&db, user(telegram_id, "before", first_seen))
+            .await
+            .unwrap();
+        assert_eq!(inserted.username.as_deref(), Some("before"));
+
+        let later = first_seen + chrono::Duration::minutes(5);
+        let updated = Entity::upsert(&db, user(telegram_id, "after", later))
+            .await
+            .unwrap();
+
+        assert_eq!(updated.id, inserted.id);
+        assert_eq!(updated.username.as_deref(), Some("after"));
+        assert_eq!(updated.created_at, inserted.created_at);
+        assert!(updated.last_seen_at > inserted.last_seen_at);
+
+        Entity::delete_by_id(updated.id).exec(&db).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_language_survives_upsert() {
+        let db = test_db().await;
+        let telegram_id = chrono::Utc::now().timestamp_micros();
+
+        assert!(
+            !Entity::set_language(&db, telegram_id, Some("es".to_string()))
+                .await
+                .unwrap()
+        );
+
+        let inserted = Entity::upsert(&db, user(telegram_id, "pixa", chrono::Utc::now()))
+            .await
+            .unwrap();
+        assert!(
+            Entity::set_language(&db, telegram_id, Some("es".to_string()))
+                .await
+                .unwrap()
+        );
+
+        Entity::upsert(&db, user(telegram_id, "pixa", chrono::Utc::now()))
+            .await
+            .unwrap();
+        async fn language(db: &DatabaseConnection, telegram_id: i64) -> Option<String> {
+            Entity::find_by_telegram_id(db, telegram_id)
+                .await
+                .unwrap()
+                .and_then(|user| user.language)
+        }
+        assert_eq!(language(&db, telegram_id).await, Some("es".to_string()));
+
+        Entity::set_language(&db, telegram_id, None).await.unwrap();
+        assert_eq!(language(&db, telegram_id).await, None);
+
+        Entity::delete_by_id(inserted.id).exec(&db).await.unwrap();
+    }
+}
@@ -0,0 +1,88 @@
+use sea_orm::{entity::prelude::*, sea_query::OnConflict};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "token_registry")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub mint_address: String,
+    pub symbol: String,
+    pub name: Option<String>,
+    pub verified: bool,
+    pub volume_24h: f64,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    /// Insert tokens, refreshing the listing data of the ones already known
+    pub async fn upsert_many<C>(db: &C, tokens: Vec<ActiveModel>) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if tokens.is_empty() {
+            return Ok(());
+        }
+
+        Entity::insert_many(tokens)
+            .on_conflict(
+                OnConflict::column(Column::MintAddress)
+                    .update_columns([
+                        Column::Symbol,
+                        Column::Name,
+                        Column::Verified,
+                        Column::Volume24h,
+                        Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+    use sea_orm::ActiveValue::Set;
+
+    fn token(mint_address: &str, symbol: &str, volume_24h: f64) -> ActiveModel {
+        ActiveModel {
+            mint_address: Set(mint_address.to_string()),
+            symbol: Set(symbol.to_string()),
+            name: Set(None),
+            verified: Set(false),
+            volume_24h: Set(volume_24h),
+            updated_at: Set(chrono::Utc::now()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_many_refreshes_listing() {
+        let db = test_db().await;
+        let mint_address = format!("test-mint-{}", chrono::Utc::now().timestamp_micros());
+
+        Entity::upsert_many(&db, vec![token(&mint_address, "OLD", 1.0)])
+            .await
+            .unwrap();
+        Entity::upsert_many(&db, vec![token(&mint_address, "NEW", 2.0)])
+            .await
+            .unwrap();
+
+        let stored = Entity::find_by_id(mint_address.clone())
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.symbol, "NEW");
+        assert_eq!(stored.volume_24h, 2.0);
+
+        Entity::delete_by_id(mint_address).exec(&db).await.unwrap();
+    }
+}
@@ -0,0 +1,137 @@
+use sea_orm::{
+    entity::prelude::*, sea_query::OnConflict, ActiveValue::Set, QueryOrder, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+
+/// A wallet whose swaps a user is notified about, set with `/track`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "tracked_wallets")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub telegram_id: i64,
+    /// Chat the notifications go to, the one `/track` was sent in
+    pub chat_id: i64,
+    pub wallet_address: String,
+    /// Name the user gave the wallet, shown in notifications
+    pub label: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    /// Track a wallet for a user, tracking it again replaces the label and chat
+    pub async fn track<C>(
+        db: &C,
+        telegram_id: i64,
+        chat_id: i64,
+        wallet_address: &str,
+        label: Option<String>,
+    ) -> Result<Model, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::insert(ActiveModel {
+            telegram_id: Set(telegram_id),
+            chat_id: Set(chat_id),
+            wallet_address: Set(wallet_address.to_string()),
+            label: Set(label),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::columns([Column::TelegramId, Column::WalletAddress])
+                .update_columns([Column::ChatId, Column::Label])
+                .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await
+    }
+
+    /// Stop tracking a wallet, only its owner can, returns whether it was removed
+    pub async fn untrack<C>(db: &C, telegram_id: i64, id: i32) -> Result<bool, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let result = Entity::delete_many()
+            .filter(Column::Id.eq(id))
+            .filter(Column::TelegramId.eq(telegram_id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Wallets tracked by a user in the order they were added
+    pub async fn find_by_user<C>(db: &C, telegram_id: i64) -> Result<Vec<Model>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .filter(Column::TelegramId.eq(telegram_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// Every wallet tracked by at least one user
+    pub async fn find_tracked_wallets<C>(db: &C) -> Result<Vec<String>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .select_only()
+            .column(Column::WalletAddress)
+            .distinct()
+            .into_tuple()
+            .all(db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+
+    const WALLET: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    #[tokio::test]
+    async fn test_track_relabel_untrack() {
+        let db = test_db().await;
+        let telegram_id = chrono::Utc::now().timestamp_micros();
+
+        let tracked = Entity::track(&db, telegram_id, telegram_id, WALLET, None)
+            .await
+            .unwrap();
+
+        // Tracking again only relabels
+        let relabeled = Entity::track(&db, telegram_id, -1, WALLET, Some("whale".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(relabeled.id, tracked.id);
+        assert_eq!(relabeled.label.as_deref(), Some("whale"));
+        assert_eq!(relabeled.chat_id, -1);
+        assert_eq!(
+            Entity::find_by_user(&db, telegram_id).await.unwrap().len(),
+            1
+        );
+        assert!(Entity::find_tracked_wallets(&db)
+            .await
+            .unwrap()
+            .contains(&WALLET.to_string()));
+
+        // Someone else can't remove it
+        assert!(!Entity::untrack(&db, telegram_id + 1, tracked.id)
+            .await
+            .unwrap());
+        assert!(Entity::untrack(&db, telegram_id, tracked.id).await.unwrap());
+        assert!(Entity::find_by_user(&db, telegram_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
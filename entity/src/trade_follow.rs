@@ -0,0 +1,164 @@
+use sea_orm::{
+    entity::prelude::*, sea_query::OnConflict, ActiveValue::Set, QueryOrder, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+
+/// A token whose trades are streamed into a chat, set with `/follow`
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "trade_follows")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub chat_id: i64,
+    pub mint_address: String,
+    pub symbol: Option<String>,
+    /// Smaller trades are left out of the feed
+    pub min_usd: f64,
+    /// Telegram id of the user who followed the token
+    pub created_by: i64,
+    /// Set when the chat was flooded, the feed resumes on its own afterwards
+    pub suspended_until: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub fn is_suspended(&self, now: DateTimeUtc) -> bool {
+        self.suspended_until.is_some_and(|until| until > now)
+    }
+}
+
+impl Entity {
+    /// Follow a token in a chat, following it again replaces the minimum and resumes the feed
+    pub async fn follow<C>(
+        db: &C,
+        chat_id: i64,
+        mint_address: &str,
+        symbol: Option<String>,
+        min_usd: f64,
+        created_by: i64,
+    ) -> Result<Model, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::insert(ActiveModel {
+            chat_id: Set(chat_id),
+            mint_address: Set(mint_address.to_string()),
+            symbol: Set(symbol),
+            min_usd: Set(min_usd),
+            created_by: Set(created_by),
+            suspended_until: Set(None),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::columns([Column::ChatId, Column::MintAddress])
+                .update_columns([
+                    Column::Symbol,
+                    Column::MinUsd,
+                    Column::CreatedBy,
+                    Column::SuspendedUntil,
+                ])
+                .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await
+    }
+
+    /// Stop following one token in a chat, or every token with `None`, returns how many stopped
+    pub async fn unfollow<C>(db: &C, chat_id: i64, mint_address: Option<&str>) -> Result<u64, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let mut delete = Entity::delete_many().filter(Column::ChatId.eq(chat_id));
+        if let Some(mint_address) = mint_address {
+            delete = delete.filter(Column::MintAddress.eq(mint_address));
+        }
+        Ok(delete.exec(db).await?.rows_affected)
+    }
+
+    /// Tokens followed in a chat in the order they were followed
+    pub async fn find_by_chat<C>(db: &C, chat_id: i64) -> Result<Vec<Model>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .filter(Column::ChatId.eq(chat_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// Every mint followed in at least one chat
+    pub async fn find_followed_mints<C>(db: &C) -> Result<Vec<String>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .select_only()
+            .column(Column::MintAddress)
+            .distinct()
+            .into_tuple()
+            .all(db)
+            .await
+    }
+
+    /// Pause every feed of a chat until `until`
+    pub async fn suspend_chat<C>(db: &C, chat_id: i64, until: DateTimeUtc) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::update_many()
+            .col_expr(Column::SuspendedUntil, Expr::value(until))
+            .filter(Column::ChatId.eq(chat_id))
+            .exec(db)
+            .await
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_db;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    const WIF: &str = "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm";
+
+    #[tokio::test]
+    async fn test_follow_suspend_unfollow() {
+        let db = test_db().await;
+        let chat_id = -chrono::Utc::now().timestamp_micros();
+
+        Entity::follow(&db, chat_id, BONK, None, 0.0, 1)
+            .await
+            .unwrap();
+        Entity::follow(&db, chat_id, WIF, None, 0.0, 1)
+            .await
+            .unwrap();
+
+        let now = chrono::Utc::now();
+        Entity::suspend_chat(&db, chat_id, now + chrono::Duration::minutes(30))
+            .await
+            .unwrap();
+        let follows = Entity::find_by_chat(&db, chat_id).await.unwrap();
+        assert_eq!(follows.len(), 2);
+        assert!(follows.iter().all(|follow| follow.is_suspended(now)));
+
+        // Following again updates the minimum and resumes the feed
+        let bonk = Entity::follow(&db, chat_id, BONK, Some("Bonk".to_string()), 500.0, 2)
+            .await
+            .unwrap();
+        assert_eq!(bonk.id, follows[0].id);
+        assert_eq!(bonk.min_usd, 500.0);
+        assert!(!bonk.is_suspended(now));
+
+        assert_eq!(Entity::unfollow(&db, chat_id, Some(WIF)).await.unwrap(), 1);
+        assert_eq!(Entity::unfollow(&db, chat_id, Some(WIF)).await.unwrap(), 0);
+        assert_eq!(Entity::unfollow(&db, chat_id, None).await.unwrap(), 1);
+    }
+}
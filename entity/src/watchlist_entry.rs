@@ -0,0 +1,46 @@
+use sea_orm::{entity::prelude::*, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "watchlist_entries")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub telegram_id: i64,
+    pub mint_address: String,
+    pub symbol: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    /// Watchlist of a Telegram user in the order mints were added
+    pub async fn find_by_user<C>(db: &C, telegram_id: i64) -> Result<Vec<Model>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .filter(Column::TelegramId.eq(telegram_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// Every mint on at least one watchlist
+    pub async fn find_watched_mints<C>(db: &C) -> Result<Vec<String>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::find()
+            .select_only()
+            .column(Column::MintAddress)
+            .distinct()
+            .into_tuple()
+            .all(db)
+            .await
+    }
+}
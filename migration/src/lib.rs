@@ -1,12 +1,40 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20261019_000001_extend_tg_users;
+mod m20261019_000002_create_price_alerts;
+mod m20261019_000003_create_watchlist_entries;
+mod m20261019_000004_create_dialogue_states;
+mod m20261019_000005_create_token_registry;
+mod m20261019_000006_create_chat_settings;
+mod m20261019_000007_add_tg_users_language;
+mod m20261019_000008_create_banned_users;
+mod m20261019_000009_create_daily_lookups;
+mod m20261019_000010_create_digest_subscriptions;
+mod m20261019_000011_create_trade_follows;
+mod m20261019_000012_create_tracked_wallets;
+mod m20261019_000013_create_referrals;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20261019_000001_extend_tg_users::Migration),
+            Box::new(m20261019_000002_create_price_alerts::Migration),
+            Box::new(m20261019_000003_create_watchlist_entries::Migration),
+            Box::new(m20261019_000004_create_dialogue_states::Migration),
+            Box::new(m20261019_000005_create_token_registry::Migration),
+            Box::new(m20261019_000006_create_chat_settings::Migration),
+            Box::new(m20261019_000007_add_tg_users_language::Migration),
+            Box::new(m20261019_000008_create_banned_users::Migration),
+            Box::new(m20261019_000009_create_daily_lookups::Migration),
+            Box::new(m20261019_000010_create_digest_subscriptions::Migration),
+            Box::new(m20261019_000011_create_trade_follows::Migration),
+            Box::new(m20261019_000012_create_tracked_wallets::Migration),
+            Box::new(m20261019_000013_create_referrals::Migration),
+        ]
     }
 }
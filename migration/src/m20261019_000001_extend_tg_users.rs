@@ -0,0 +1,130 @@
+use sea_orm_migration::{prelude::*, schema::*, sea_orm::DatabaseBackend};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const TELEGRAM_ID_INDEX: &str = "idx_tg_users_telegram_id";
+
+// SQLite, used by the tests, alters one column per statement and can't default a new column
+// to the current time, so rows that existed before get the epoch
+fn sqlite_columns() -> [ColumnDef; 5] {
+    let epoch = "1970-01-01 00:00:00+00:00";
+    [
+        string(TgUsers::FirstName).default("").to_owned(),
+        string_null(TgUsers::LastName),
+        string_null(TgUsers::LanguageCode),
+        timestamp_with_time_zone(TgUsers::CreatedAt)
+            .default(epoch)
+            .to_owned(),
+        timestamp_with_time_zone(TgUsers::LastSeenAt)
+            .default(epoch)
+            .to_owned(),
+    ]
+}
+
+fn is_sqlite(manager: &SchemaManager) -> bool {
+    manager.get_database_backend() == DatabaseBackend::Sqlite
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if is_sqlite(manager) {
+            for mut column in sqlite_columns() {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(TgUsers::Table)
+                            .add_column(&mut column)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        } else {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TgUsers::Table)
+                        .add_column(string(TgUsers::FirstName).default(""))
+                        .add_column(string_null(TgUsers::LastName))
+                        .add_column(string_null(TgUsers::LanguageCode))
+                        .add_column(
+                            timestamp_with_time_zone(TgUsers::CreatedAt)
+                                .default(Expr::current_timestamp()),
+                        )
+                        .add_column(
+                            timestamp_with_time_zone(TgUsers::LastSeenAt)
+                                .default(Expr::current_timestamp()),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(TELEGRAM_ID_INDEX)
+                    .table(TgUsers::Table)
+                    .col(TgUsers::TelegramId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(TELEGRAM_ID_INDEX)
+                    .table(TgUsers::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        if is_sqlite(manager) {
+            for column in [
+                TgUsers::FirstName,
+                TgUsers::LastName,
+                TgUsers::LanguageCode,
+                TgUsers::CreatedAt,
+                TgUsers::LastSeenAt,
+            ] {
+                manager
+                    .alter_table(
+                        Table::alter()
+                            .table(TgUsers::Table)
+                            .drop_column(column)
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+        } else {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TgUsers::Table)
+                        .drop_column(TgUsers::FirstName)
+                        .drop_column(TgUsers::LastName)
+                        .drop_column(TgUsers::LanguageCode)
+                        .drop_column(TgUsers::CreatedAt)
+                        .drop_column(TgUsers::LastSeenAt)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum TgUsers {
+    Table,
+    TelegramId,
+    FirstName,
+    LastName,
+    LanguageCode,
+    CreatedAt,
+    LastSeenAt,
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PriceAlerts::Table)
+                    .if_not_exists()
+                    .col(pk_auto(PriceAlerts::Id))
+                    .col(big_integer(PriceAlerts::TelegramId))
+                    .col(big_integer(PriceAlerts::ChatId))
+                    .col(string(PriceAlerts::MintAddress))
+                    .col(string_null(PriceAlerts::Symbol))
+                    .col(string_len(PriceAlerts::Condition, 16))
+                    .col(double(PriceAlerts::Target))
+                    .col(double_null(PriceAlerts::ReferencePrice))
+                    .col(boolean(PriceAlerts::Repeating).default(false))
+                    .col(big_integer(PriceAlerts::CooldownSecs).default(3600))
+                    .col(boolean(PriceAlerts::Active).default(true))
+                    .col(timestamp_with_time_zone_null(PriceAlerts::LastTriggeredAt))
+                    .col(
+                        timestamp_with_time_zone(PriceAlerts::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_price_alerts_telegram_id")
+                    .table(PriceAlerts::Table)
+                    .col(PriceAlerts::TelegramId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_price_alerts_mint_address")
+                    .table(PriceAlerts::Table)
+                    .col(PriceAlerts::MintAddress)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PriceAlerts::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PriceAlerts {
+    Table,
+    Id,
+    TelegramId,
+    ChatId,
+    MintAddress,
+    Symbol,
+    Condition,
+    Target,
+    ReferencePrice,
+    Repeating,
+    CooldownSecs,
+    Active,
+    LastTriggeredAt,
+    CreatedAt,
+}
@@ -0,0 +1,54 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WatchlistEntries::Table)
+                    .if_not_exists()
+                    .col(pk_auto(WatchlistEntries::Id))
+                    .col(big_integer(WatchlistEntries::TelegramId))
+                    .col(string(WatchlistEntries::MintAddress))
+                    .col(string_null(WatchlistEntries::Symbol))
+                    .col(
+                        timestamp_with_time_zone(WatchlistEntries::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_watchlist_entries_telegram_id_mint_address")
+                    .table(WatchlistEntries::Table)
+                    .col(WatchlistEntries::TelegramId)
+                    .col(WatchlistEntries::MintAddress)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WatchlistEntries::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WatchlistEntries {
+    Table,
+    Id,
+    TelegramId,
+    MintAddress,
+    Symbol,
+    CreatedAt,
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DialogueStates::Table)
+                    .if_not_exists()
+                    .col(big_integer(DialogueStates::ChatId).primary_key())
+                    .col(json_binary(DialogueStates::State))
+                    .col(
+                        timestamp_with_time_zone(DialogueStates::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Expired dialogues are pruned by age
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_dialogue_states_updated_at")
+                    .table(DialogueStates::Table)
+                    .col(DialogueStates::UpdatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DialogueStates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DialogueStates {
+    Table,
+    ChatId,
+    State,
+    UpdatedAt,
+}
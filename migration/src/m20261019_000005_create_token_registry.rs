@@ -0,0 +1,55 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TokenRegistry::Table)
+                    .if_not_exists()
+                    .col(string(TokenRegistry::MintAddress).primary_key())
+                    .col(string(TokenRegistry::Symbol))
+                    .col(string_null(TokenRegistry::Name))
+                    .col(boolean(TokenRegistry::Verified).default(false))
+                    .col(double(TokenRegistry::Volume24h).default(0.0))
+                    .col(
+                        timestamp_with_time_zone(TokenRegistry::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_token_registry_symbol")
+                    .table(TokenRegistry::Table)
+                    .col(TokenRegistry::Symbol)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TokenRegistry::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TokenRegistry {
+    Table,
+    MintAddress,
+    Symbol,
+    Name,
+    Verified,
+    #[sea_orm(iden = "volume_24h")]
+    Volume24h,
+    UpdatedAt,
+}
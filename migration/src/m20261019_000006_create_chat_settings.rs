@@ -0,0 +1,46 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatSettings::Table)
+                    .if_not_exists()
+                    .col(big_integer(ChatSettings::ChatId).primary_key())
+                    .col(boolean(ChatSettings::AutoCards).default(true))
+                    .col(boolean(ChatSettings::CompactCards).default(false))
+                    .col(small_integer_null(ChatSettings::MuteStartHour))
+                    .col(small_integer_null(ChatSettings::MuteEndHour))
+                    .col(json_binary(ChatSettings::DisabledCommands).default("[]"))
+                    .col(
+                        timestamp_with_time_zone(ChatSettings::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChatSettings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChatSettings {
+    Table,
+    ChatId,
+    AutoCards,
+    CompactCards,
+    MuteStartHour,
+    MuteEndHour,
+    DisabledCommands,
+    UpdatedAt,
+}
@@ -0,0 +1,40 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BannedUsers::Table)
+                    .if_not_exists()
+                    .col(big_integer(BannedUsers::TelegramId).primary_key())
+                    .col(big_integer(BannedUsers::BannedBy))
+                    .col(string_null(BannedUsers::Reason))
+                    .col(
+                        timestamp_with_time_zone(BannedUsers::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BannedUsers::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BannedUsers {
+    Table,
+    TelegramId,
+    BannedBy,
+    Reason,
+    CreatedAt,
+}
@@ -0,0 +1,54 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DigestSubscriptions::Table)
+                    .if_not_exists()
+                    .col(big_integer(DigestSubscriptions::TelegramId).primary_key())
+                    .col(big_integer(DigestSubscriptions::ChatId))
+                    .col(small_integer(DigestSubscriptions::LocalMinute))
+                    .col(small_integer(DigestSubscriptions::UtcOffsetMinutes).default(0))
+                    .col(timestamp_with_time_zone(DigestSubscriptions::NextRunAt))
+                    .col(
+                        timestamp_with_time_zone(DigestSubscriptions::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_digest_subscriptions_next_run_at")
+                    .table(DigestSubscriptions::Table)
+                    .col(DigestSubscriptions::NextRunAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DigestSubscriptions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DigestSubscriptions {
+    Table,
+    TelegramId,
+    ChatId,
+    LocalMinute,
+    UtcOffsetMinutes,
+    NextRunAt,
+    CreatedAt,
+}
@@ -0,0 +1,60 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TradeFollows::Table)
+                    .if_not_exists()
+                    .col(pk_auto(TradeFollows::Id))
+                    .col(big_integer(TradeFollows::ChatId))
+                    .col(string(TradeFollows::MintAddress))
+                    .col(string_null(TradeFollows::Symbol))
+                    .col(double(TradeFollows::MinUsd).default(0.0))
+                    .col(big_integer(TradeFollows::CreatedBy))
+                    .col(timestamp_with_time_zone_null(TradeFollows::SuspendedUntil))
+                    .col(
+                        timestamp_with_time_zone(TradeFollows::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_trade_follows_chat_id_mint_address")
+                    .table(TradeFollows::Table)
+                    .col(TradeFollows::ChatId)
+                    .col(TradeFollows::MintAddress)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TradeFollows::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TradeFollows {
+    Table,
+    Id,
+    ChatId,
+    MintAddress,
+    Symbol,
+    MinUsd,
+    CreatedBy,
+    SuspendedUntil,
+    CreatedAt,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TrackedWallets::Table)
+                    .if_not_exists()
+                    .col(pk_auto(TrackedWallets::Id))
+                    .col(big_integer(TrackedWallets::TelegramId))
+                    .col(big_integer(TrackedWallets::ChatId))
+                    .col(string(TrackedWallets::WalletAddress))
+                    .col(string_null(TrackedWallets::Label))
+                    .col(
+                        timestamp_with_time_zone(TrackedWallets::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tracked_wallets_telegram_id_wallet_address")
+                    .table(TrackedWallets::Table)
+                    .col(TrackedWallets::TelegramId)
+                    .col(TrackedWallets::WalletAddress)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TrackedWallets::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TrackedWallets {
+    Table,
+    Id,
+    TelegramId,
+    ChatId,
+    WalletAddress,
+    Label,
+    CreatedAt,
+}
@@ -0,0 +1,77 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReferralCodes::Table)
+                    .if_not_exists()
+                    .col(big_integer(ReferralCodes::TelegramId).primary_key())
+                    .col(string_uniq(ReferralCodes::Code))
+                    .col(
+                        timestamp_with_time_zone(ReferralCodes::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // A user can only be referred once, by whoever brought them to /start first
+        manager
+            .create_table(
+                Table::create()
+                    .table(Referrals::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Referrals::Id))
+                    .col(big_integer(Referrals::ReferrerId))
+                    .col(big_integer_uniq(Referrals::ReferredId))
+                    .col(
+                        timestamp_with_time_zone(Referrals::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_referrals_referrer_id")
+                    .table(Referrals::Table)
+                    .col(Referrals::ReferrerId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Referrals::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ReferralCodes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReferralCodes {
+    Table,
+    TelegramId,
+    Code,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Referrals {
+    Table,
+    Id,
+    ReferrerId,
+    ReferredId,
+    CreatedAt,
+}
@@ -0,0 +1,229 @@
+# Messages of the bot in English, every other locale has the same ids
+
+## Shared
+
+token-not-found = Could not find that token.
+table-token = TOKEN
+table-price = PRICE $
+table-change-1d = 1D
+table-volume = VOL
+table-amount = AMOUNT
+table-value = VALUE $
+
+## /start and /language
+
+start-welcome = 👋 Welcome to Pixa! Paste a token mint address to get its card, or use one of these commands:
+language-choose = Your language is { $language }. Pick another one:
+language-telegram = 🌐 Use my Telegram language
+language-set = ✅ I'll talk to you in { $language } from now on.
+language-reset = ✅ Back to your Telegram language.
+language-unknown = Unknown language, pick one of: { $languages }
+language-start-first = Send /start first so I can remember your language.
+
+## Price alerts
+
+alert-usage =
+    Usage:
+    /alert <mint> above <price>
+    /alert <mint> below <price>
+    /alert <mint> +20% (or -15%)
+    Add "repeat" at the end to keep the alert after it fires.
+alert-limit = You already have { $count } alerts, remove some with /alerts first.
+alert-set =
+    🔔 Alert set: { $alert }
+    Current price: ${ $price }
+alert-above = { $token } above ${ $target }
+alert-below = { $token } below ${ $target }
+alert-move = { $token } { $target }% move
+alert-repeating = { $alert } (repeating)
+alerts-empty = You have no active alerts.
+alerts-list = Your active alerts, tap one to remove it:
+alert-triggered =
+    🔔 Price alert: { $alert }
+    Price is now ${ $price }
+    { $mint }
+alert-prompt = Send the alert condition: "above 0.5", "below 0.1" or "+20%", add "repeat" to keep it after it fires, or "cancel".
+alert-cancelled = Alert cancelled.
+alert-group-hint = Use /alert { $mint } above|below <price>
+
+## Watchlist
+
+watch-usage = Usage: /watch <mint>
+watch-already = Already on your watchlist.
+watch-full = Your watchlist is full ({ $count } tokens), /unwatch something first.
+watch-added = 👀 Added { $symbol } to your watchlist.
+unwatch-removed = Removed from your watchlist.
+unwatch-missing = That token is not on your watchlist.
+watchlist-empty = Your watchlist is empty, add tokens with /watch <mint>.
+watchlist-title = Your watchlist
+
+## Token lookups
+
+cashtag-not-found = No token found for ${ $symbol }
+cashtag-failed = Could not load ${ $symbol }
+error-message = Error: { $message }
+lookup-none-found = None of those addresses is a token I know.
+
+## /chart
+
+chart-usage = Usage: /chart <mint> [1h|24h|7d]
+chart-load-failed = Could not load the price history of that token.
+chart-no-trades = No trades in the last { $period }.
+chart-caption =
+    📈 { $symbol } · { $period }
+    Close ${ $close } · High ${ $high } · Low ${ $low }
+
+## /holders
+
+holders-usage = Usage: /holders <mint>
+holders-none = No holders found for that token.
+holders-load-failed = Could not load the holders of that token.
+holders-title = Top holders of { $symbol }
+holders-share = Top 10 hold { $share }% of the supply
+holders-concentrated = ⚠️ Top 10 hold { $share }% of the supply, highly concentrated
+holders-no-regular = No regular holders among the largest accounts
+holders-legend = 🏦 exchange · 🔥 burn · 💧 liquidity, burn and liquidity accounts don't count towards the top 10
+
+## /wallet
+
+wallet-usage = Usage: /wallet <address>
+wallet-not-found = No token balances found for that wallet.
+wallet-title = Wallet
+wallet-value = Value:
+wallet-no-balances = No token balances worth ${ $amount } or more.
+wallet-dust = { $count } tokens under ${ $amount } hidden (${ $total } total)
+wallet-prev = ◀️ Prev
+wallet-next = Next ▶️
+
+## Group /settings
+
+settings-private = Add me to a group and use /settings there.
+settings-admins-only = Only group admins can change settings.
+settings-title = ⚙️ Group settings
+settings-on = on
+settings-off = off
+settings-token-cards = Token cards: { $state }
+settings-card-style = Card style: { $style }
+settings-style-compact = compact
+settings-style-full = full
+settings-mute = Mute: { $window }
+settings-done = Done
+
+## Rate limits
+
+rate-limited-user = 🐢 Slow down a little, you can look up more tokens in a few seconds.
+rate-limited-chat = 🐢 Lots of lookups in this chat, token cards are paused for a moment.
+lookup-busy = ⏳ The bot is busy right now, try again in { $seconds }s.
+
+## /digest
+
+digest-usage = Usage: /digest on [HH:MM] [UTC+2] for a daily digest of your watchlist, /digest off to stop it.
+digest-on = 🗞 Daily digest on, it arrives every day at { $time } ({ $offset }).
+digest-off = Daily digest off.
+digest-not-subscribed = You don't get the daily digest, turn it on with /digest on [HH:MM] [UTC+2].
+digest-status = 🗞 Your daily digest arrives every day at { $time } ({ $offset }), turn it off with /digest off.
+digest-title = 🗞 Your daily digest
+digest-movers = Top movers (24h)
+digest-alerts = Alerts triggered in the last 24h
+digest-no-alerts = No alerts triggered in the last 24h.
+
+## /follow
+
+follow-usage = Usage: /follow <mint> [min_usd] streams the trades of a token into this chat, smaller trades than min_usd are left out.
+follow-set = 📡 Following { $symbol } trades of at least ${ $min } here, stop with /unfollow.
+follow-limit = This chat already follows { $count } tokens, /unfollow one first.
+follow-admins-only = Only group admins can change trade feeds.
+follow-list = Trade feeds in this chat, stop one with /unfollow <mint> or all with /unfollow all:
+follow-suspended = ⏸ Too many trades, the trade feeds of this chat are paused for { $minutes } minutes.
+follow-skipped = +{ $count } more trades
+unfollow-done = Stopped { $count } trade feeds.
+unfollow-none = No trade feeds in this chat.
+
+## /track
+
+track-usage = Usage: /track <wallet> [label] to get notified whenever the wallet buys or sells.
+track-set = 👛 Tracking { $wallet }, its swaps will show up here. See your wallets with /tracked.
+track-limit = You can track up to { $count } wallets, remove one with /tracked first.
+tracked-list = 👛 Your tracked wallets, tap one to stop tracking it:
+tracked-empty = You don't track any wallet, add one with /track <wallet> [label].
+tracked-not-yours = Only the owner of this list can remove its wallets.
+track-bought = bought { $bought_amount } { $bought_token } for { $sold_amount } { $sold_token }
+track-sold = sold { $sold_amount } { $sold_token } for { $bought_amount } { $bought_token }
+track-swapped = swapped { $sold_amount } { $sold_token } for { $bought_amount } { $bought_token }
+
+## /referrals
+
+referrals-text =
+    🔗 Invite friends to Pixa with your link:
+    { $link }
+
+    Joined with it: { $total } in total, { $recent } in the last { $days } days.
+referral-joined = 🎉 { $name } joined Pixa with your link, that makes { $count } so far.
+
+## Token cards
+
+card-token-details = Token details
+card-price = Price
+card-market-cap = MC
+card-supply = Supply
+card-volume = Vol (24h)
+card-top-holders = Top 10 holders
+card-verified = Verified
+card-open-vybe = Open with Vybe
+card-unknown-name = Unknown
+card-change-unknown = n/a
+card-refresh = 🔄 Refresh
+card-watch = 👀 Watch
+card-alert = 🔔 Alert
+card-chart = 📈 Chart
+
+## Command list
+
+help-header = These commands are supported:
+help-start = start bot.
+help-alert = set a price alert: /alert <mint> above|below <price> or <mint> +20%
+help-alerts = list and remove your price alerts.
+help-watch = add a token to your watchlist: /watch <mint>
+help-unwatch = remove a token from your watchlist: /unwatch <mint>
+help-watchlist = show prices of your watched tokens.
+help-chart = price chart of a token: /chart <mint> [1h|24h|7d]
+help-wallet = token balances of a wallet: /wallet <address>
+help-holders = largest holders of a token: /holders <mint>
+help-digest = daily digest of your watchlist: /digest on [HH:MM] [UTC+2] or /digest off
+help-follow = stream trades of a token into this chat: /follow <mint> [min_usd]
+help-unfollow = stop a trade feed: /unfollow <mint|all>
+help-track = get notified when a wallet swaps: /track <wallet> [label]
+help-tracked = list and remove the wallets you track.
+help-referrals = your referral link and how many users joined with it.
+help-language = choose the language of the bot: /language [en|es|auto]
+help-settings = configure the bot in a group (admins only).
+help-test = testing
+help-help = display this text.
+
+## Admin commands
+
+stats =
+    📊 Stats
+    Users: { $users } (+{ $new_users } in 24h), { $banned } banned
+    Lookups: { $today } today, { $yesterday } yesterday, { $week } in 7d
+    Active alerts: { $alerts }
+    Watchlist entries: { $watchlist }
+    Live feed: { $stream }
+stats-stream-connected = connected, { $trades } trades, last { $seconds }s ago
+stats-stream-disconnected = disconnected, { $trades } trades, last { $seconds }s ago
+stats-stream-idle = connected, no trades yet
+stats-stream-down = disconnected
+broadcast-usage = Usage: /broadcast <text>, or reply /broadcast to the message to send
+broadcast-already-running = A broadcast is already running.
+broadcast-running = 📣 Broadcast running: { $sent }/{ $total } sent, { $failed } failed
+broadcast-cancelled = 📣 Broadcast cancelled: { $sent }/{ $total } sent, { $failed } failed
+broadcast-finished = 📣 Broadcast finished: { $sent }/{ $total } sent, { $failed } failed
+broadcast-cancel = Cancel
+broadcast-cancelling = Cancelling the broadcast
+broadcast-not-running = No broadcast is running
+ban-usage = Usage: /ban <telegram id> [reason], or reply /ban [reason] to a message
+ban-admin = Admins can't be banned.
+ban-done = 🚫 Banned { $id }.
+unban-usage = Usage: /unban <telegram id>
+unban-done = ✅ Unbanned { $id }.
+unban-not-banned = { $id } is not banned.
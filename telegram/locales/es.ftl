@@ -0,0 +1,229 @@
+# Mensajes del bot en español, con los mismos ids que en.ftl
+
+## Shared
+
+token-not-found = No encontré ese token.
+table-token = TOKEN
+table-price = PRECIO $
+table-change-1d = 1D
+table-volume = VOL
+table-amount = CANTIDAD
+table-value = VALOR $
+
+## /start and /language
+
+start-welcome = 👋 ¡Bienvenido a Pixa! Pega la dirección mint de un token para ver su ficha, o usa uno de estos comandos:
+language-choose = Tu idioma es { $language }. Elige otro:
+language-telegram = 🌐 Usar el idioma de Telegram
+language-set = ✅ A partir de ahora te hablaré en { $language }.
+language-reset = ✅ Volviste al idioma de Telegram.
+language-unknown = Idioma desconocido, elige uno de: { $languages }
+language-start-first = Envía /start primero para que pueda recordar tu idioma.
+
+## Price alerts
+
+alert-usage =
+    Uso:
+    /alert <mint> above <precio>
+    /alert <mint> below <precio>
+    /alert <mint> +20% (o -15%)
+    Agrega "repeat" al final para mantener la alerta después de que se dispare.
+alert-limit = Ya tienes { $count } alertas, elimina algunas con /alerts primero.
+alert-set =
+    🔔 Alerta creada: { $alert }
+    Precio actual: ${ $price }
+alert-above = { $token } por encima de ${ $target }
+alert-below = { $token } por debajo de ${ $target }
+alert-move = { $token } se mueve { $target }%
+alert-repeating = { $alert } (repetida)
+alerts-empty = No tienes alertas activas.
+alerts-list = Tus alertas activas, toca una para eliminarla:
+alert-triggered =
+    🔔 Alerta de precio: { $alert }
+    El precio ahora es ${ $price }
+    { $mint }
+alert-prompt = Envía la condición de la alerta: "above 0.5", "below 0.1" o "+20%", agrega "repeat" para mantenerla después de que se dispare, o "cancel".
+alert-cancelled = Alerta cancelada.
+alert-group-hint = Usa /alert { $mint } above|below <precio>
+
+## Watchlist
+
+watch-usage = Uso: /watch <mint>
+watch-already = Ya está en tu lista.
+watch-full = Tu lista está llena ({ $count } tokens), usa /unwatch con alguno primero.
+watch-added = 👀 { $symbol } añadido a tu lista.
+unwatch-removed = Eliminado de tu lista.
+unwatch-missing = Ese token no está en tu lista.
+watchlist-empty = Tu lista está vacía, agrega tokens con /watch <mint>.
+watchlist-title = Tu lista
+
+## Token lookups
+
+cashtag-not-found = No se encontró ningún token para ${ $symbol }
+cashtag-failed = No se pudo cargar ${ $symbol }
+error-message = Error: { $message }
+lookup-none-found = Ninguna de esas direcciones es un token conocido.
+
+## /chart
+
+chart-usage = Uso: /chart <mint> [1h|24h|7d]
+chart-load-failed = No se pudo cargar el historial de precios de ese token.
+chart-no-trades = Sin operaciones en las últimas { $period }.
+chart-caption =
+    📈 { $symbol } · { $period }
+    Cierre ${ $close } · Máximo ${ $high } · Mínimo ${ $low }
+
+## /holders
+
+holders-usage = Uso: /holders <mint>
+holders-none = No se encontraron holders para ese token.
+holders-load-failed = No se pudieron cargar los holders de ese token.
+holders-title = Mayores holders de { $symbol }
+holders-share = El top 10 tiene el { $share }% del suministro
+holders-concentrated = ⚠️ El top 10 tiene el { $share }% del suministro, muy concentrado
+holders-no-regular = No hay holders normales entre las cuentas más grandes
+holders-legend = 🏦 exchange · 🔥 quemado · 💧 liquidez, las cuentas quemadas y de liquidez no cuentan para el top 10
+
+## /wallet
+
+wallet-usage = Uso: /wallet <dirección>
+wallet-not-found = No se encontraron saldos de tokens para esa wallet.
+wallet-title = Wallet
+wallet-value = Valor:
+wallet-no-balances = No hay saldos de tokens de ${ $amount } o más.
+wallet-dust = { $count } tokens por debajo de ${ $amount } ocultos (${ $total } en total)
+wallet-prev = ◀️ Anterior
+wallet-next = Siguiente ▶️
+
+## Group /settings
+
+settings-private = Agrégame a un grupo y usa /settings allí.
+settings-admins-only = Solo los administradores del grupo pueden cambiar la configuración.
+settings-title = ⚙️ Configuración del grupo
+settings-on = sí
+settings-off = no
+settings-token-cards = Fichas de tokens: { $state }
+settings-card-style = Estilo de ficha: { $style }
+settings-style-compact = compacto
+settings-style-full = completo
+settings-mute = Silencio: { $window }
+settings-done = Listo
+
+## Rate limits
+
+rate-limited-user = 🐢 Ve más despacio, podrás buscar más tokens en unos segundos.
+rate-limited-chat = 🐢 Muchas búsquedas en este chat, las fichas de tokens se pausan un momento.
+lookup-busy = ⏳ El bot está ocupado ahora, inténtalo de nuevo en { $seconds }s.
+
+## /digest
+
+digest-usage = Uso: /digest on [HH:MM] [UTC+2] para un resumen diario de tu lista, /digest off para desactivarlo.
+digest-on = 🗞 Resumen diario activado, llega cada día a las { $time } ({ $offset }).
+digest-off = Resumen diario desactivado.
+digest-not-subscribed = No recibes el resumen diario, actívalo con /digest on [HH:MM] [UTC+2].
+digest-status = 🗞 Tu resumen diario llega cada día a las { $time } ({ $offset }), desactívalo con /digest off.
+digest-title = 🗞 Tu resumen diario
+digest-movers = Mayores movimientos (24h)
+digest-alerts = Alertas disparadas en las últimas 24h
+digest-no-alerts = Ninguna alerta disparada en las últimas 24h.
+
+## /follow
+
+follow-usage = Uso: /follow <mint> [min_usd] transmite las operaciones de un token a este chat, las menores que min_usd se omiten.
+follow-set = 📡 Siguiendo aquí las operaciones de { $symbol } de al menos ${ $min }, detenlo con /unfollow.
+follow-limit = Este chat ya sigue { $count } tokens, usa /unfollow con uno primero.
+follow-admins-only = Solo los administradores del grupo pueden cambiar los feeds de operaciones.
+follow-list = Feeds de operaciones en este chat, detén uno con /unfollow <mint> o todos con /unfollow all:
+follow-suspended = ⏸ Demasiadas operaciones, los feeds de este chat se pausan durante { $minutes } minutos.
+follow-skipped = +{ $count } operaciones más
+unfollow-done = Se detuvieron { $count } feeds de operaciones.
+unfollow-none = No hay feeds de operaciones en este chat.
+
+## /track
+
+track-usage = Uso: /track <wallet> [etiqueta] para recibir un aviso cada vez que la billetera compre o venda.
+track-set = 👛 Siguiendo { $wallet }, sus swaps aparecerán aquí. Consulta tus billeteras con /tracked.
+track-limit = Puedes seguir hasta { $count } billeteras, elimina una con /tracked primero.
+tracked-list = 👛 Tus billeteras seguidas, toca una para dejar de seguirla:
+tracked-empty = No sigues ninguna billetera, añade una con /track <wallet> [etiqueta].
+tracked-not-yours = Solo el dueño de esta lista puede eliminar sus billeteras.
+track-bought = compró { $bought_amount } { $bought_token } por { $sold_amount } { $sold_token }
+track-sold = vendió { $sold_amount } { $sold_token } por { $bought_amount } { $bought_token }
+track-swapped = cambió { $sold_amount } { $sold_token } por { $bought_amount } { $bought_token }
+
+## /referrals
+
+referrals-text =
+    🔗 Invita a tus amigos a Pixa con tu enlace:
+    { $link }
+
+    Se unieron con él: { $total } en total, { $recent } en los últimos { $days } días.
+referral-joined = 🎉 { $name } se unió a Pixa con tu enlace, ya van { $count }.
+
+## Token cards
+
+card-token-details = Datos del token
+card-price = Precio
+card-market-cap = Capitalización
+card-supply = Suministro
+card-volume = Vol. (24h)
+card-top-holders = Top 10 holders
+card-verified = Verificado
+card-open-vybe = Abrir en Vybe
+card-unknown-name = Desconocido
+card-change-unknown = n/d
+card-refresh = 🔄 Actualizar
+card-watch = 👀 Seguir
+card-alert = 🔔 Alerta
+card-chart = 📈 Gráfico
+
+## Command list
+
+help-header = Estos son los comandos disponibles:
+help-start = iniciar el bot.
+help-alert = crear una alerta de precio: /alert <mint> above|below <precio> o <mint> +20%
+help-alerts = ver y eliminar tus alertas de precio.
+help-watch = añadir un token a tu lista: /watch <mint>
+help-unwatch = quitar un token de tu lista: /unwatch <mint>
+help-watchlist = ver los precios de los tokens de tu lista.
+help-chart = gráfico de precio de un token: /chart <mint> [1h|24h|7d]
+help-wallet = saldos de tokens de una billetera: /wallet <dirección>
+help-holders = mayores holders de un token: /holders <mint>
+help-digest = resumen diario de tu lista: /digest on [HH:MM] [UTC+2] o /digest off
+help-follow = transmitir las operaciones de un token en este chat: /follow <mint> [min_usd]
+help-unfollow = detener un feed de operaciones: /unfollow <mint|all>
+help-track = recibir avisos cuando una billetera haga swaps: /track <wallet> [etiqueta]
+help-tracked = ver y eliminar las billeteras que sigues.
+help-referrals = tu enlace de invitación y cuántos usuarios se unieron con él.
+help-language = elegir el idioma del bot: /language [en|es|auto]
+help-settings = configurar el bot en un grupo (solo admins).
+help-test = pruebas
+help-help = mostrar este texto.
+
+## Admin commands
+
+stats =
+    📊 Estadísticas
+    Usuarios: { $users } (+{ $new_users } en 24h), { $banned } bloqueados
+    Búsquedas: { $today } hoy, { $yesterday } ayer, { $week } en 7d
+    Alertas activas: { $alerts }
+    Tokens en listas: { $watchlist }
+    Feed en vivo: { $stream }
+stats-stream-connected = conectado, { $trades } trades, último hace { $seconds }s
+stats-stream-disconnected = desconectado, { $trades } trades, último hace { $seconds }s
+stats-stream-idle = conectado, sin trades todavía
+stats-stream-down = desconectado
+broadcast-usage = Uso: /broadcast <texto>, o responde /broadcast al mensaje que quieras enviar
+broadcast-already-running = Ya hay una difusión en curso.
+broadcast-running = 📣 Difusión en curso: { $sent }/{ $total } enviados, { $failed } fallidos
+broadcast-cancelled = 📣 Difusión cancelada: { $sent }/{ $total } enviados, { $failed } fallidos
+broadcast-finished = 📣 Difusión terminada: { $sent }/{ $total } enviados, { $failed } fallidos
+broadcast-cancel = Cancelar
+broadcast-cancelling = Cancelando la difusión
+broadcast-not-running = No hay ninguna difusión en curso
+ban-usage = Uso: /ban <id de telegram> [motivo], o responde /ban [motivo] a un mensaje
+ban-admin = Los admins no pueden ser bloqueados.
+ban-done = 🚫 { $id } bloqueado.
+unban-usage = Uso: /unban <id de telegram>
+unban-done = ✅ { $id } desbloqueado.
+unban-not-banned = { $id } no está bloqueado.
@@ -0,0 +1,171 @@
+use url::Url;
+
+use crate::commands::message::is_valid_solana_mint_address;
+
+/// Most tokens looked up from a single message
+pub const MAX_ADDRESSES_PER_MESSAGE: usize = 5;
+
+// Characters wrapped around pasted links, like `(https://…)` or `<https://…>`
+const LINK_DELIMITERS: &[char] = &['(', ')', '<', '>', '[', ']', '"', '\'', ',', '.', '!', '?'];
+
+fn valid(address: &str) -> Option<String> {
+    is_valid_solana_mint_address(address).then(|| address.to_string())
+}
+
+/// The token a link of a known explorer or DEX points at.
+///
+/// `None` for unknown links, `Some(None)` for known links that point at no token, like a
+/// DexScreener page of another chain, so the addresses in them are not picked up either.
+fn link_address(url: &Url) -> Option<Option<String>> {
+    let host = url.host_str()?;
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let segments = url
+        .path_segments()
+        .map(|segments| {
+            segments
+                .filter(|segment| !segment.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    let address = match host {
+        // Pair pages share the URL scheme with token pages, the lookup tells them apart
+        "dexscreener.com" => match segments.as_slice() {
+            ["solana", address, ..] => valid(address),
+            _ => None,
+        },
+        "birdeye.so" => match (segments.as_slice(), query("chain")) {
+            (_, Some(chain)) if chain != "solana" => None,
+            (["token", address, ..], _) => valid(address),
+            _ => None,
+        },
+        "pump.fun" => match segments.as_slice() {
+            ["coin", address, ..] | [address] => valid(address),
+            _ => None,
+        },
+        "solscan.io" => match segments.as_slice() {
+            ["token" | "account", address, ..] => valid(address),
+            _ => None,
+        },
+        // Swaps name both sides, the token bought is the interesting one
+        "jup.ag" => match (
+            segments.as_slice(),
+            query("buy").or_else(|| query("outputMint")),
+        ) {
+            (_, Some(bought)) => valid(&bought),
+            (["swap", pair, ..], None) => pair.rsplit('-').next().and_then(valid),
+            (["tokens", address, ..], None) => valid(address),
+            _ => None,
+        },
+        _ => return None,
+    };
+    Some(address)
+}
+
+/// Every address in a message in order of appearance, deduplicated and capped at
+/// [`MAX_ADDRESSES_PER_MESSAGE`], links of known sites contribute the token they point at
+pub fn extract_addresses(text: &str) -> Vec<String> {
+    let mut addresses = Vec::new();
+    for word in text.split_whitespace() {
+        let link = Url::parse(word.trim_matches(LINK_DELIMITERS))
+            .ok()
+            .and_then(|url| link_address(&url));
+        let found = match link {
+            Some(address) => address.into_iter().collect(),
+            None => word
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .filter_map(valid)
+                .collect::<Vec<_>>(),
+        };
+
+        for address in found {
+            if !addresses.contains(&address) {
+                addresses.push(address);
+            }
+            if addresses.len() == MAX_ADDRESSES_PER_MESSAGE {
+                return addresses;
+            }
+        }
+    }
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    const WIF: &str = "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm";
+    const WSOL: &str = "So11111111111111111111111111111111111111112";
+    const PUMP: &str = "9BB6NFEcjBCtnNLFko2FqVQBq8HHM13kCyYcdQbgpump";
+
+    #[test]
+    fn test_extract_plain_addresses() {
+        assert_eq!(
+            extract_addresses(&format!("ape into {} now!", BONK)),
+            [BONK]
+        );
+        assert_eq!(extract_addresses(&format!("({})", BONK)), [BONK]);
+        assert_eq!(
+            extract_addresses(&format!("{}\n{}, {} again", BONK, WIF, BONK)),
+            [BONK, WIF]
+        );
+        assert!(extract_addresses("gm everyone").is_empty());
+    }
+
+    #[test]
+    fn test_extract_from_links() {
+        let links = [
+            (
+                format!("https://dexscreener.com/solana/{}", BONK),
+                Some(BONK),
+            ),
+            (format!("https://dexscreener.com/ethereum/{}", BONK), None),
+            (
+                format!("https://birdeye.so/token/{}?chain=solana", WIF),
+                Some(WIF),
+            ),
+            (format!("https://birdeye.so/token/{}?chain=base", WIF), None),
+            (format!("https://pump.fun/coin/{}", PUMP), Some(PUMP)),
+            (format!("https://pump.fun/{}", PUMP), Some(PUMP)),
+            (
+                format!("https://solscan.io/token/{}#holders", BONK),
+                Some(BONK),
+            ),
+            (format!("https://jup.ag/swap/SOL-{}", WIF), Some(WIF)),
+            (
+                format!("https://jup.ag/swap?sell={}&buy={}", WSOL, WIF),
+                Some(WIF),
+            ),
+            (format!("https://jup.ag/tokens/{}", BONK), Some(BONK)),
+        ];
+        for (link, expected) in links {
+            assert_eq!(
+                extract_addresses(&format!("look <{}>", link)),
+                expected.into_iter().collect::<Vec<_>>(),
+                "{}",
+                link
+            );
+        }
+
+        // Unknown sites are scanned like plain text
+        assert_eq!(
+            extract_addresses(&format!("https://example.com/{}", BONK)),
+            [BONK]
+        );
+    }
+
+    #[test]
+    fn test_extract_caps_addresses() {
+        let addresses = (1..=9)
+            .map(|n| format!("{}{}", &BONK[..43], n))
+            .collect::<Vec<_>>();
+        let extracted = extract_addresses(&addresses.join(" "));
+        assert_eq!(extracted, addresses[..MAX_ADDRESSES_PER_MESSAGE]);
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use entity::price_alert::{self, Entity as PriceAlert};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, IntoActiveModel};
+use teloxide::prelude::*;
+use tokio::sync::{broadcast::error::RecvError, Notify};
+use utils::endpoints::vybe::util::VYBE_TOKEN_API;
+
+use crate::{
+    commands::alert::describe_alert,
+    i18n::{stored_user_lang, t},
+};
+
+// How often alerts are reloaded and tokens missing from the stream are priced over REST
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+// A stream price older than this is considered missing
+const STALE_PRICE_SECS: u64 = 120;
+
+static ALERTS_CHANGED: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+type AlertsByMint = HashMap<String, Vec<price_alert::Model>>;
+
+/// Ask the evaluator to reload alerts after they were created or removed
+pub fn alerts_changed() {
+    ALERTS_CHANGED.notify_one();
+}
+
+async fn load_alerts() -> AlertsByMint {
+    let db = entity::get_db().await;
+    let mut alerts = AlertsByMint::new();
+
+    match PriceAlert::find_active(db).await {
+        Ok(models) => {
+            for alert in models {
+                alerts
+                    .entry(alert.mint_address.clone())
+                    .or_default()
+                    .push(alert);
+            }
+        }
+        Err(err) => tracing::error!("Failed to load price alerts: {:?}", err),
+    }
+
+    alerts
+}
+
+/// Evaluate price alerts against the aggregator trade stream, falling back to Vybe REST
+/// prices for tokens that are not trading on the stream.
+pub async fn run(bot: Bot) {
+    let mut trades = aggregator::subscribe();
+    let mut refresh = tokio::time::interval(REFRESH_INTERVAL);
+    let mut alerts = load_alerts().await;
+
+    loop {
+        tokio::select! {
+            trade = trades.recv() => match trade {
+                Ok(trade) => {
+                    if let Some((mint, price)) = aggregator::usd_price(&trade) {
+                        if let Some(mint_alerts) = alerts.get_mut(mint) {
+                            evaluate(&bot, mint_alerts, price).await;
+                        }
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Alert evaluator lagged, skipped {} trades", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            _ = refresh.tick() => {
+                alerts = load_alerts().await;
+                evaluate_stale(&bot, &mut alerts).await;
+            }
+            _ = ALERTS_CHANGED.notified() => {
+                alerts = load_alerts().await;
+            }
+        }
+
+        alerts.retain(|_, mint_alerts| !mint_alerts.is_empty());
+    }
+}
+
+// Price alerted tokens that had no recent trade on the stream through the REST API
+async fn evaluate_stale(bot: &Bot, alerts: &mut AlertsByMint) {
+    let now = chrono::Utc::now().timestamp() as u64;
+
+    for (mint, mint_alerts) in alerts.iter_mut() {
+        let fresh = aggregator::latest_price(mint)
+            .is_some_and(|price| price.block_time + STALE_PRICE_SECS >= now);
+        if fresh {
+            continue;
+        }
+
+        match VYBE_TOKEN_API.get_token_details(mint.clone()).await {
+            Ok(details) => evaluate(bot, mint_alerts, details.price).await,
+            Err(err) => tracing::warn!("Failed to price {} for alerts: {:?}", mint, err),
+        }
+    }
+}
+
+// Fire every alert of one token satisfied by `price`, dropping the one-shot ones
+async fn evaluate(bot: &Bot, alerts: &mut Vec<price_alert::Model>, price: f64) {
+    let now = chrono::Utc::now();
+    let mut index = 0;
+
+    while index < alerts.len() {
+        let alert = &alerts[index];
+        if alert.in_cooldown(now) || !alert.is_triggered(price) {
+            index += 1;
+            continue;
+        }
+
+        let lang = stored_user_lang(alert.telegram_id).await;
+        let text = t!(
+            lang,
+            "alert-triggered",
+            alert = describe_alert(lang, alert),
+            price = lang.number(&price.to_string()),
+            mint = alert.mint_address.as_str()
+        );
+        if let Err(err) = bot.send_message(ChatId(alert.chat_id), text).await {
+            tracing::warn!("Failed to send price alert {}: {:?}", alert.id, err);
+        }
+
+        let mut model = alert.clone().into_active_model();
+        if alert.repeating {
+            model.last_triggered_at = Set(Some(now));
+            // Repeating percentage alerts measure the next move from here
+            model.reference_price = Set(Some(price));
+        } else {
+            model.active = Set(false);
+            model.last_triggered_at = Set(Some(now));
+        }
+
+        match model.update(entity::get_db().await).await {
+            Ok(updated) if updated.active => {
+                alerts[index] = updated;
+                index += 1;
+            }
+            Ok(_) => {
+                alerts.remove(index);
+            }
+            Err(err) => {
+                // Keep it out of the way until the next reload rather than spamming the chat
+                tracing::error!(
+                    "Failed to update price alert {}: {:?}",
+                    alerts[index].id,
+                    err
+                );
+                alerts.remove(index);
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::{
+    collections::HashSet,
+    sync::{LazyLock, RwLock},
+};
+
+use entity::banned_user::Entity as BannedUser;
+use sea_orm::DbErr;
+use teloxide::types::{Update, UserId};
+use utils::ENV_CONFIG;
+
+use crate::HandlerResult;
+
+// Banned Telegram ids, mirrored from the `banned_users` table
+static BANNED: LazyLock<RwLock<HashSet<i64>>> = LazyLock::new(|| RwLock::new(HashSet::new()));
+
+pub fn is_admin(user_id: UserId) -> bool {
+    ENV_CONFIG.admin_ids.contains(&(user_id.0 as i64))
+}
+
+/// Load the bans stored by earlier runs
+pub async fn load_bans() {
+    let db = entity::get_db().await;
+    match BannedUser::find_ids(db).await {
+        Ok(ids) => *BANNED.write().unwrap() = ids.into_iter().collect(),
+        Err(err) => tracing::error!("Failed to load banned users: {:?}", err),
+    }
+}
+
+pub fn is_banned_id(telegram_id: i64) -> bool {
+    BANNED.read().unwrap().contains(&telegram_id)
+}
+
+/// Whether an update comes from a banned user, admins can't be banned
+pub fn is_banned(update: &Update) -> bool {
+    update
+        .from()
+        .is_some_and(|user| !is_admin(user.id) && is_banned_id(user.id.0 as i64))
+}
+
+/// Swallows updates of banned users so no other handler sees them
+pub async fn ignore_banned() -> HandlerResult {
+    Ok(())
+}
+
+pub async fn ban(telegram_id: i64, banned_by: i64, reason: Option<String>) -> Result<(), DbErr> {
+    let db = entity::get_db().await;
+    BannedUser::ban(db, telegram_id, banned_by, reason).await?;
+    BANNED.write().unwrap().insert(telegram_id);
+    Ok(())
+}
+
+/// Lift a ban, returns whether the user was banned
+pub async fn unban(telegram_id: i64) -> Result<bool, DbErr> {
+    let db = entity::get_db().await;
+    let was_banned = BannedUser::unban(db, telegram_id).await?;
+    BANNED.write().unwrap().remove(&telegram_id);
+    Ok(was_banned)
+}
+
+pub fn banned_count() -> usize {
+    BANNED.read().unwrap().len()
+}
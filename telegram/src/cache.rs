@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use utils::{
+    endpoints::vybe::{
+        types::{VybeTokenDetails, VybeTopHolder, VybeWalletTokenBalances},
+        util::{VYBE_TOKEN_API, VYBE_WALLET_API},
+    },
+    http::HttpError,
+};
+
+// Inline queries fire on every keystroke, so identical lookups are served from memory
+const TOKEN_DETAILS_TTL: Duration = Duration::from_secs(60);
+
+// Past this many tokens expired entries are dropped on insert
+const MAX_CACHED_TOKENS: usize = 2048;
+
+// Paging through a portfolio re-renders it, so keep the balances for a couple of minutes
+const WALLET_BALANCES_TTL: Duration = Duration::from_secs(120);
+const MAX_CACHED_WALLETS: usize = 256;
+
+// Balances fetched per request, and at most this many requests per wallet
+const WALLET_PAGE_SIZE: u32 = 100;
+const MAX_WALLET_PAGES: u32 = 10;
+
+// Holder distributions move slowly and the request is heavy for Vybe
+const TOP_HOLDERS_TTL: Duration = Duration::from_secs(10 * 60);
+pub const TOP_HOLDERS_LIMIT: u32 = 20;
+
+struct CachedDetails {
+    fetched_at: Instant,
+    details: VybeTokenDetails,
+}
+
+static TOKEN_DETAILS: LazyLock<Mutex<HashMap<String, CachedDetails>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Responses keyed by address with the time they were fetched
+type FetchedAt<T> = Mutex<HashMap<String, (Instant, T)>>;
+
+static TOP_HOLDERS: LazyLock<FetchedAt<Vec<VybeTopHolder>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static WALLET_BALANCES: LazyLock<FetchedAt<VybeWalletTokenBalances>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Token details from Vybe, cached for a minute per mint
+pub async fn token_details(mint_address: &str) -> Result<VybeTokenDetails, HttpError> {
+    if let Some(cached) = TOKEN_DETAILS.lock().unwrap().get(mint_address) {
+        if cached.fetched_at.elapsed() < TOKEN_DETAILS_TTL {
+            return Ok(cached.details.clone());
+        }
+    }
+
+    refresh_token_details(mint_address).await
+}
+
+/// Token details only if they are cached and fresh, without calling Vybe
+pub fn cached_token_details(mint_address: &str) -> Option<VybeTokenDetails> {
+    TOKEN_DETAILS
+        .lock()
+        .unwrap()
+        .get(mint_address)
+        .filter(|cached| cached.fetched_at.elapsed() < TOKEN_DETAILS_TTL)
+        .map(|cached| cached.details.clone())
+}
+
+/// Fetch token details from Vybe regardless of the cache, storing them for later lookups
+pub async fn refresh_token_details(mint_address: &str) -> Result<VybeTokenDetails, HttpError> {
+    let details = VYBE_TOKEN_API
+        .get_token_details(mint_address.to_string())
+        .await?;
+
+    let mut cache = TOKEN_DETAILS.lock().unwrap();
+    if cache.len() >= MAX_CACHED_TOKENS {
+        cache.retain(|_, cached| cached.fetched_at.elapsed() < TOKEN_DETAILS_TTL);
+    }
+    cache.insert(
+        mint_address.to_string(),
+        CachedDetails {
+            fetched_at: Instant::now(),
+            details: details.clone(),
+        },
+    );
+
+    Ok(details)
+}
+
+/// Every token balance of a wallet, most valuable first, cached for two minutes per wallet
+pub async fn wallet_balances(owner_address: &str) -> Result<VybeWalletTokenBalances, HttpError> {
+    if let Some((fetched_at, balances)) = WALLET_BALANCES.lock().unwrap().get(owner_address) {
+        if fetched_at.elapsed() < WALLET_BALANCES_TTL {
+            return Ok(balances.clone());
+        }
+    }
+
+    let mut balances = VYBE_WALLET_API
+        .get_token_balances(owner_address, WALLET_PAGE_SIZE, 0)
+        .await?;
+    for page in 1..MAX_WALLET_PAGES {
+        if balances.data.len() as u32 >= balances.total_token_count
+            || balances.data.len() as u32 != page * WALLET_PAGE_SIZE
+        {
+            break;
+        }
+        let next = VYBE_WALLET_API
+            .get_token_balances(owner_address, WALLET_PAGE_SIZE, page)
+            .await?;
+        balances.data.extend(next.data);
+    }
+
+    let mut cache = WALLET_BALANCES.lock().unwrap();
+    if cache.len() >= MAX_CACHED_WALLETS {
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < WALLET_BALANCES_TTL);
+    }
+    cache.insert(
+        owner_address.to_string(),
+        (Instant::now(), balances.clone()),
+    );
+
+    Ok(balances)
+}
+
+/// The largest holders of a token, cached for ten minutes per mint
+pub async fn top_holders(mint_address: &str) -> Result<Vec<VybeTopHolder>, HttpError> {
+    if let Some((fetched_at, holders)) = TOP_HOLDERS.lock().unwrap().get(mint_address) {
+        if fetched_at.elapsed() < TOP_HOLDERS_TTL {
+            return Ok(holders.clone());
+        }
+    }
+
+    let holders = VYBE_TOKEN_API
+        .get_top_holders(mint_address, TOP_HOLDERS_LIMIT)
+        .await?
+        .data;
+
+    let mut cache = TOP_HOLDERS.lock().unwrap();
+    if cache.len() >= MAX_CACHED_TOKENS {
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < TOP_HOLDERS_TTL);
+    }
+    cache.insert(mint_address.to_string(), (Instant::now(), holders.clone()));
+
+    Ok(holders)
+}
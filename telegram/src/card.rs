@@ -0,0 +1,434 @@
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode};
+use url::Url;
+use utils::{
+    endpoints::vybe::types::VybeTokenDetails,
+    math::calculate_price_change,
+    number::{format_decimal_price, format_long_number},
+};
+
+use crate::{
+    i18n::{t, Lang},
+    rich_text::{RichText, Style},
+};
+
+/// Markup of [`TokenCard::caption`] and [`TokenCard::compact`]
+pub const CARD_STYLE: Style = Style::MarkdownV2;
+
+/// Parse mode to send [`TokenCard::caption`] and [`TokenCard::compact`] with
+pub const CARD_PARSE_MODE: ParseMode = CARD_STYLE.parse_mode();
+
+/// Prefix of the callback data of the buttons below a token card
+pub const CARD_ACTION_PREFIX: &str = "tc:";
+
+/// A token card rendered independently of where it is sent, a chat message or an inline result
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenCard {
+    pub title: String,
+    pub description: String,
+    pub caption: String,
+    /// One line summary for chats that asked for compact cards
+    pub compact: String,
+    pub logo_url: Option<Url>,
+}
+
+pub fn vybe_token_url(token_mint_address: &str) -> String {
+    format!(
+        "https://alpha.vybenetwork.com/tokens/{}",
+        token_mint_address
+    )
+}
+
+/// Window of the price change shown on a card
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CardPeriod {
+    Hour,
+    #[default]
+    Day,
+    Week,
+}
+
+impl CardPeriod {
+    pub const ALL: [CardPeriod; 3] = [CardPeriod::Hour, CardPeriod::Day, CardPeriod::Week];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CardPeriod::Hour => "1h",
+            CardPeriod::Day => "24h",
+            CardPeriod::Week => "7d",
+        }
+    }
+
+    /// Single character used in callback data
+    pub fn code(self) -> char {
+        match self {
+            CardPeriod::Hour => 'h',
+            CardPeriod::Day => 'd',
+            CardPeriod::Week => 'w',
+        }
+    }
+
+    pub fn from_code(code: char) -> Option<Self> {
+        CardPeriod::ALL
+            .into_iter()
+            .find(|period| period.code() == code)
+    }
+
+    /// Parse a period typed by a user, like `1h`, `24h` or `7d`
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.to_ascii_lowercase().as_str() {
+            "1h" => Some(CardPeriod::Hour),
+            "24h" | "1d" => Some(CardPeriod::Day),
+            "7d" | "1w" => Some(CardPeriod::Week),
+            _ => None,
+        }
+    }
+}
+
+/// What a button below a token card does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardAction {
+    /// Re-render the card with fresh data, `Period` does the same with another period
+    Refresh,
+    Period,
+    Watch,
+    Alert,
+    Chart,
+}
+
+impl CardAction {
+    const ALL: [CardAction; 5] = [
+        CardAction::Refresh,
+        CardAction::Period,
+        CardAction::Watch,
+        CardAction::Alert,
+        CardAction::Chart,
+    ];
+
+    fn code(self) -> char {
+        match self {
+            CardAction::Refresh => 'r',
+            CardAction::Period => 'p',
+            CardAction::Watch => 'w',
+            CardAction::Alert => 'a',
+            CardAction::Chart => 'c',
+        }
+    }
+}
+
+/// Callback data of a card button, `tc:<action><period>:<mint>` stays well under Telegram's 64 bytes
+pub fn encode_card_action(action: CardAction, period: CardPeriod, mint_address: &str) -> String {
+    format!(
+        "{}{}{}:{}",
+        CARD_ACTION_PREFIX,
+        action.code(),
+        period.code(),
+        mint_address
+    )
+}
+
+pub fn decode_card_action(data: &str) -> Option<(CardAction, CardPeriod, &str)> {
+    let (codes, mint_address) = data.strip_prefix(CARD_ACTION_PREFIX)?.split_once(':')?;
+    let mut codes = codes.chars();
+    let action = codes.next()?;
+    let action = CardAction::ALL
+        .into_iter()
+        .find(|candidate| candidate.code() == action)?;
+    let period = CardPeriod::from_code(codes.next()?)?;
+
+    (codes.next().is_none() && !mint_address.is_empty()).then_some((action, period, mint_address))
+}
+
+/// Buttons below a full token card showing the change over `period`
+pub fn card_markup(lang: Lang, mint_address: &str, period: CardPeriod) -> InlineKeyboardMarkup {
+    let button = |text: &str, action: CardAction, period: CardPeriod| {
+        InlineKeyboardButton::callback(text, encode_card_action(action, period, mint_address))
+    };
+
+    InlineKeyboardMarkup::new([
+        vec![button(
+            &t!(lang, "card-refresh"),
+            CardAction::Refresh,
+            period,
+        )],
+        CardPeriod::ALL
+            .into_iter()
+            .map(|option| {
+                let label = if option == period {
+                    format!("• {} •", option.label())
+                } else {
+                    option.label().to_string()
+                };
+                button(&label, CardAction::Period, option)
+            })
+            .collect(),
+        vec![
+            button(&t!(lang, "card-watch"), CardAction::Watch, period),
+            button(&t!(lang, "card-alert"), CardAction::Alert, period),
+            button(&t!(lang, "card-chart"), CardAction::Chart, period),
+        ],
+    ])
+}
+
+/// Data shown on a card besides the token details
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CardContext {
+    /// Window of the price change
+    pub period: CardPeriod,
+    /// Price an hour ago, Vybe details only carry the 24h and 7d reference prices
+    pub hour_reference: Option<f64>,
+    /// Percent of the supply held by the top 10 holders
+    pub top10_share: Option<f64>,
+}
+
+pub fn token_card(lang: Lang, token_details: &VybeTokenDetails) -> TokenCard {
+    token_card_with(lang, token_details, &CardContext::default())
+}
+
+/// Render a card in `lang` with the price change over `context.period` and the optional extra lines
+pub fn token_card_with(
+    lang: Lang,
+    token_details: &VybeTokenDetails,
+    context: &CardContext,
+) -> TokenCard {
+    let period = context.period;
+    let name = token_details
+        .name
+        .clone()
+        .unwrap_or_else(|| t!(lang, "card-unknown-name"));
+    let price = lang.number(&format_decimal_price(token_details.price, None));
+    let reference = match period {
+        CardPeriod::Hour => context.hour_reference,
+        CardPeriod::Day => Some(token_details.price_1d),
+        CardPeriod::Week => Some(token_details.price_7d),
+    };
+    let price_change = match reference {
+        Some(reference) => format!(
+            "{}% {}",
+            lang.number(&format!(
+                "{:.2}",
+                calculate_price_change(token_details.price, reference)
+            )),
+            period.label()
+        ),
+        None => format!("{} {}", t!(lang, "card-change-unknown"), period.label()),
+    };
+
+    let market_cap = lang.number(&format_long_number(token_details.market_cap));
+    let market_cap_label = t!(lang, "card-market-cap");
+    let mut caption = RichText::new();
+    caption
+        .plain("🟣")
+        .bold(&name)
+        .plain(format!(" ({})\n\n", token_details.symbol))
+        .bold(t!(lang, "card-token-details"))
+        .plain(format!(" 📊\n├ {}: ", t!(lang, "card-price")))
+        .bold(format!("${}", price))
+        .plain(format!(" ({})\n├ {}: ", price_change, market_cap_label))
+        .bold(&market_cap)
+        .plain(format!("\n├ {}: ", t!(lang, "card-supply")))
+        .bold(lang.number(&format_long_number(token_details.current_supply)))
+        .plain(format!("\n├ {}: ", t!(lang, "card-volume")))
+        .bold(format!(
+            "${}",
+            lang.number(&format_long_number(
+                token_details.usd_value_volume_24h.unwrap_or(0.0)
+            ))
+        ));
+    if let Some(share) = context.top10_share {
+        caption
+            .plain(format!("\n├ {}: ", t!(lang, "card-top-holders")))
+            .bold(format!("{}%", lang.number(&format!("{:.1}", share))));
+    }
+    caption
+        .plain(format!(
+            "\n└ {}: {}\n\n",
+            t!(lang, "card-verified"),
+            if token_details.verified {
+                "🟢"
+            } else {
+                "🔴"
+            }
+        ))
+        .code(&token_details.mint_address)
+        .plain("\n└ ")
+        .link(
+            t!(lang, "card-open-vybe"),
+            vybe_token_url(&token_details.mint_address),
+        );
+
+    let mut compact = RichText::new();
+    compact
+        .plain("🟣")
+        .bold(&name)
+        .plain(format!(" ({}) · ", token_details.symbol))
+        .bold(format!("${}", price))
+        .plain(format!(" ({}) · {} ", price_change, market_cap_label))
+        .bold(&market_cap)
+        .plain("\n")
+        .code(&token_details.mint_address);
+
+    TokenCard {
+        title: format!("{} ({})", name, token_details.symbol),
+        description: format!(
+            "${} ({}) · {} {}",
+            price, price_change, market_cap_label, market_cap
+        ),
+        caption: caption.caption(CARD_STYLE),
+        compact: compact.message(CARD_STYLE),
+        logo_url: token_details
+            .logo_url
+            .as_deref()
+            .and_then(|logo_url| Url::parse(logo_url).ok()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bonk() -> VybeTokenDetails {
+        VybeTokenDetails {
+            symbol: "Bonk".to_string(),
+            mint_address: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+            price: 0.00002,
+            price_1d: 0.00001,
+            price_7d: 0.00001,
+            decimal: 5,
+            verified: true,
+            update_time: 0,
+            current_supply: 88_000_000_000_000.0,
+            market_cap: 1_760_000_000.0,
+            category: None,
+            logo_url: Some("https://arweave.net/bonk.png".to_string()),
+            name: Some("Bonk".to_string()),
+            subcategory: None,
+            token_amount_volume_24h: None,
+            usd_value_volume_24h: Some(12_500_000.0),
+        }
+    }
+
+    #[test]
+    fn test_token_card() {
+        let card = token_card(Lang::En, &bonk());
+
+        assert_eq!(card.title, "Bonk (Bonk)");
+        assert!(card.caption.starts_with("🟣*Bonk* \\(Bonk\\)"));
+        assert!(card.caption.contains("\\(100\\.00% 24h\\)"));
+        assert!(card.caption.contains("└ Verified: 🟢"));
+        assert!(card.compact.starts_with("🟣*Bonk* \\(Bonk\\) · *$"));
+        assert!(!card.compact.contains("Token details"));
+        assert!(card.caption.contains(
+            "https://alpha.vybenetwork.com/tokens/DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263"
+        ));
+        assert_eq!(
+            card.logo_url.as_ref().map(Url::as_str),
+            Some("https://arweave.net/bonk.png")
+        );
+
+        assert!(!card.caption.contains("Top 10"));
+
+        let context = |period, hour_reference| CardContext {
+            period,
+            hour_reference,
+            top10_share: None,
+        };
+        let week = token_card_with(Lang::En, &bonk(), &context(CardPeriod::Week, None));
+        assert!(week.caption.contains("\\(100\\.00% 7d\\)"));
+        let hour = token_card_with(Lang::En, &bonk(), &context(CardPeriod::Hour, None));
+        assert!(hour.caption.contains("\\(n/a 1h\\)"));
+        let hour = token_card_with(
+            Lang::En,
+            &bonk(),
+            &context(CardPeriod::Hour, Some(0.000016)),
+        );
+        assert!(hour.caption.contains("\\(25\\.00% 1h\\)"));
+
+        let held = token_card_with(
+            Lang::En,
+            &bonk(),
+            &CardContext {
+                top10_share: Some(42.34),
+                ..CardContext::default()
+            },
+        );
+        assert!(held
+            .caption
+            .contains("├ Top 10 holders: *42\\.3%*\n└ Verified: 🟢"));
+
+        let spanish = token_card_with(Lang::Es, &bonk(), &context(CardPeriod::Week, None));
+        assert!(spanish.caption.contains("├ Precio: *$"));
+        assert!(spanish.caption.contains("\\(100,00% 7d\\)"));
+        assert!(spanish.caption.contains("Capitalización: *1,7B*"));
+
+        let mut no_logo = bonk();
+        no_logo.logo_url = Some("not a url".to_string());
+        assert_eq!(token_card(Lang::En, &no_logo).logo_url, None);
+    }
+
+    #[test]
+    fn test_token_card_snapshot() {
+        let mut token = bonk();
+        token.name = Some("Dog_Wif*Hat [v2]".to_string());
+        token.symbol = "WIF.2".to_string();
+        token.price = 1.5;
+        token.price_1d = 1.2;
+        let card = token_card_with(
+            Lang::En,
+            &token,
+            &CardContext {
+                top10_share: Some(42.34),
+                ..CardContext::default()
+            },
+        );
+
+        insta::assert_snapshot!(card.caption, @r"
+        🟣*Dog\_Wif\*Hat \[v2\]* \(WIF\.2\)
+
+        *Token details* 📊
+        ├ Price: *$1\.5000* \(25\.00% 24h\)
+        ├ MC: *1\.7B*
+        ├ Supply: *88000B*
+        ├ Vol \(24h\): *$12\.5M*
+        ├ Top 10 holders: *42\.3%*
+        └ Verified: 🟢
+
+        `DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263`
+        └ [Open with Vybe](https://alpha.vybenetwork.com/tokens/DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263)
+        ");
+        insta::assert_snapshot!(card.compact, @r"
+        🟣*Dog\_Wif\*Hat \[v2\]* \(WIF\.2\) · *$1\.5000* \(25\.00% 24h\) · MC *1\.7B*
+        `DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263`
+        ");
+    }
+
+    #[test]
+    fn test_card_markup_language() {
+        let label = |lang| {
+            card_markup(lang, "mint", CardPeriod::Day).inline_keyboard[0][0]
+                .text
+                .clone()
+        };
+        assert_eq!(label(Lang::En), "🔄 Refresh");
+        assert_eq!(label(Lang::Es), "🔄 Actualizar");
+    }
+
+    #[test]
+    fn test_card_action_round_trip() {
+        let mint_address = bonk().mint_address;
+        for action in CardAction::ALL {
+            for period in CardPeriod::ALL {
+                let data = encode_card_action(action, period, &mint_address);
+                assert!(data.len() <= 64);
+                assert_eq!(
+                    decode_card_action(&data),
+                    Some((action, period, mint_address.as_str()))
+                );
+            }
+        }
+
+        assert_eq!(decode_card_action("tc:xd:mint"), None);
+        assert_eq!(decode_card_action("tc:rd:"), None);
+        assert_eq!(decode_card_action("tc:rdd:mint"), None);
+        assert_eq!(decode_card_action("token:mint"), None);
+    }
+}
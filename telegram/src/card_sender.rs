@@ -0,0 +1,234 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+};
+
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardMarkup, InputFile, LinkPreviewOptions, PhotoSize},
+    ApiError, RequestError,
+};
+use url::Url;
+
+use crate::card::{TokenCard, CARD_PARSE_MODE};
+
+// Past this many mints an arbitrary uploaded logo is forgotten on insert
+const MAX_CACHED_LOGOS: usize = 4096;
+
+/// Text cards skip the preview of the Vybe link, it would only repeat the card
+pub const NO_LINK_PREVIEW: LinkPreviewOptions = LinkPreviewOptions {
+    is_disabled: true,
+    url: None,
+    prefer_small_media: false,
+    prefer_large_media: false,
+    show_above_text: false,
+};
+
+// Logos Telegram already has, sending them again by file id skips fetching the URL
+static LOGO_FILES: LazyLock<Mutex<HashMap<String, PhotoSize>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// How a token card is sent
+#[derive(Debug, Clone, PartialEq)]
+pub enum CardMedia {
+    /// The logo uploaded for an earlier card of the same mint
+    Uploaded(PhotoSize),
+    /// The logo for Telegram to fetch
+    Logo(Url),
+    /// No usable logo, the caption goes out as a text message
+    Text,
+}
+
+/// Prefer a logo Telegram already has, then the logo URL, then text
+pub fn card_media(card: &TokenCard, uploaded: Option<PhotoSize>) -> CardMedia {
+    match (uploaded, &card.logo_url) {
+        (Some(uploaded), _) => CardMedia::Uploaded(uploaded),
+        (None, Some(logo_url)) => CardMedia::Logo(logo_url.clone()),
+        (None, None) => CardMedia::Text,
+    }
+}
+
+/// What to try after `failed` was rejected, an uploaded logo falls back to the logo URL
+pub fn fallback_media(card: &TokenCard, failed: &CardMedia) -> CardMedia {
+    match failed {
+        CardMedia::Uploaded(_) => card_media(card, None),
+        CardMedia::Logo(_) | CardMedia::Text => CardMedia::Text,
+    }
+}
+
+// Photo errors teloxide has no variant for, the details after them vary
+const BAD_PHOTO_DESCRIPTIONS: &[&str] = &[
+    // URLs serving HTML instead of an image
+    "Bad Request: wrong type of the web page content",
+    // File ids of another bot or malformed ones
+    "Bad Request: wrong remote file identifier specified",
+    "Bad Request: PHOTO_INVALID_DIMENSIONS",
+    "Bad Request: PHOTO_SAVE_FILE_INVALID",
+];
+
+/// Whether a photo send failed because of the image, so the card can still go out as text
+pub fn is_bad_photo(err: &RequestError) -> bool {
+    match err {
+        RequestError::Api(
+            ApiError::WrongFileId
+            | ApiError::WrongFileIdOrUrl
+            | ApiError::FileIdInvalid
+            | ApiError::FailedToGetUrlContent
+            | ApiError::ImageProcessFailed
+            | ApiError::PhotoAsInputFileRequired
+            | ApiError::WrongHttpUrl,
+        ) => true,
+        RequestError::Api(ApiError::Unknown(description)) => BAD_PHOTO_DESCRIPTIONS
+            .iter()
+            .any(|known| description.starts_with(known)),
+        _ => false,
+    }
+}
+
+fn uploaded_logo(mint_address: &str) -> Option<PhotoSize> {
+    LOGO_FILES.lock().unwrap().get(mint_address).cloned()
+}
+
+fn store_uploaded_logo(mint_address: &str, message: &Message) {
+    // Sizes are ordered from smallest to largest
+    let Some(largest) = message.photo().and_then(|sizes| sizes.last()) else {
+        return;
+    };
+
+    let mut files = LOGO_FILES.lock().unwrap();
+    if files.len() >= MAX_CACHED_LOGOS && !files.contains_key(mint_address) {
+        if let Some(evicted) = files.keys().next().cloned() {
+            files.remove(&evicted);
+        }
+    }
+    files.insert(mint_address.to_string(), largest.clone());
+}
+
+fn forget_uploaded_logo(mint_address: &str) {
+    LOGO_FILES.lock().unwrap().remove(mint_address);
+}
+
+async fn send_text_card(
+    bot: &Bot,
+    chat_id: ChatId,
+    card: &TokenCard,
+    markup: InlineKeyboardMarkup,
+) -> Result<Message, RequestError> {
+    bot.send_message(chat_id, card.caption.clone())
+        .parse_mode(CARD_PARSE_MODE)
+        .link_preview_options(NO_LINK_PREVIEW)
+        .reply_markup(markup)
+        .await
+}
+
+/// Send a token card with its logo when there is a working one and as text otherwise
+pub async fn send_card(
+    bot: &Bot,
+    chat_id: ChatId,
+    mint_address: &str,
+    card: &TokenCard,
+    markup: InlineKeyboardMarkup,
+) -> Result<Message, RequestError> {
+    let mut media = card_media(card, uploaded_logo(mint_address));
+    loop {
+        let photo = match &media {
+            CardMedia::Uploaded(uploaded) => InputFile::file_id(uploaded.file.id.clone()),
+            CardMedia::Logo(logo_url) => InputFile::url(logo_url.clone()),
+            CardMedia::Text => return send_text_card(bot, chat_id, card, markup).await,
+        };
+
+        let result = bot
+            .send_photo(chat_id, photo)
+            .caption(card.caption.clone())
+            .parse_mode(CARD_PARSE_MODE)
+            .reply_markup(markup.clone())
+            .await;
+
+        match result {
+            Ok(message) => {
+                store_uploaded_logo(mint_address, &message);
+                return Ok(message);
+            }
+            Err(err) if is_bad_photo(&err) => {
+                tracing::warn!(
+                    "The logo of {} failed in {:?}, trying without it: {:?}",
+                    mint_address,
+                    media,
+                    err
+                );
+                if let CardMedia::Uploaded(_) = media {
+                    forget_uploaded_logo(mint_address);
+                }
+                media = fallback_media(card, &media);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(logo_url: Option<&str>) -> TokenCard {
+        TokenCard {
+            title: String::new(),
+            description: String::new(),
+            caption: String::new(),
+            compact: String::new(),
+            logo_url: logo_url.map(|logo_url| Url::parse(logo_url).unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_card_media() {
+        let logo_url = "https://arweave.net/bonk.png";
+        assert_eq!(card_media(&card(None), None), CardMedia::Text);
+        assert_eq!(
+            card_media(&card(Some(logo_url)), None),
+            CardMedia::Logo(Url::parse(logo_url).unwrap())
+        );
+
+        let uploaded: PhotoSize = serde_json::from_value(serde_json::json!({
+            "file_id": "photo-1",
+            "file_unique_id": "unique-1",
+            "width": 320,
+            "height": 320,
+        }))
+        .unwrap();
+        let media = card_media(&card(Some(logo_url)), Some(uploaded.clone()));
+        assert_eq!(media, CardMedia::Uploaded(uploaded.clone()));
+
+        // A stale upload retries the logo URL before giving up on the photo
+        let media = fallback_media(&card(Some(logo_url)), &media);
+        assert_eq!(media, CardMedia::Logo(Url::parse(logo_url).unwrap()));
+        assert_eq!(
+            fallback_media(&card(Some(logo_url)), &media),
+            CardMedia::Text
+        );
+        assert_eq!(
+            fallback_media(&card(None), &CardMedia::Uploaded(uploaded)),
+            CardMedia::Text
+        );
+    }
+
+    #[test]
+    fn test_is_bad_photo() {
+        assert!(is_bad_photo(&RequestError::Api(
+            ApiError::FailedToGetUrlContent
+        )));
+        assert!(is_bad_photo(&RequestError::Api(ApiError::Unknown(
+            "Bad Request: wrong type of the web page content".to_string()
+        ))));
+        assert!(!is_bad_photo(&RequestError::Api(ApiError::BotBlocked)));
+        assert!(is_bad_photo(&RequestError::Api(ApiError::Unknown(
+            "Bad Request: wrong remote file identifier specified: Wrong string length".to_string()
+        ))));
+        assert!(!is_bad_photo(&RequestError::Api(ApiError::Unknown(
+            "Bad Request: can't parse entities".to_string()
+        ))));
+        assert!(!is_bad_photo(&RequestError::Api(ApiError::Unknown(
+            "Bad Request: file must be non-empty".to_string()
+        ))));
+    }
+}
@@ -0,0 +1,197 @@
+use plotters::prelude::*;
+use utils::endpoints::vybe::types::VybeOhlcv;
+
+use crate::card::CardPeriod;
+
+pub const CHART_WIDTH: u32 = 800;
+pub const CHART_HEIGHT: u32 = 500;
+
+const BACKGROUND: RGBColor = RGBColor(19, 23, 34);
+const GRID: RGBColor = RGBColor(42, 46, 57);
+const UP: RGBColor = RGBColor(38, 166, 154);
+const DOWN: RGBColor = RGBColor(239, 83, 80);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChartError {
+    #[error("no candles to draw")]
+    Empty,
+    #[error("failed to draw chart: {0}")]
+    Draw(String),
+    #[error("failed to encode chart: {0}")]
+    Encode(#[from] png::EncodingError),
+}
+
+fn draw_error<E: std::error::Error + Send + Sync>(err: DrawingAreaErrorKind<E>) -> ChartError {
+    ChartError::Draw(err.to_string())
+}
+
+/// Candle resolution and time span in seconds of a chart covering `period`
+pub fn chart_window(period: CardPeriod) -> (&'static str, i64) {
+    match period {
+        CardPeriod::Hour => ("1m", 60 * 60),
+        CardPeriod::Day => ("15m", 24 * 60 * 60),
+        CardPeriod::Week => ("2h", 7 * 24 * 60 * 60),
+    }
+}
+
+/// Render candles sorted by time as a PNG candlestick chart with volume bars below.
+///
+/// The chart has no text so it doesn't depend on system fonts, the caption carries the numbers.
+pub fn render_candles(candles: &[VybeOhlcv]) -> Result<Vec<u8>, ChartError> {
+    let (Some(first), Some(last)) = (candles.first(), candles.last()) else {
+        return Err(ChartError::Empty);
+    };
+
+    let mut buffer = vec![0u8; (CHART_WIDTH * CHART_HEIGHT * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (CHART_WIDTH, CHART_HEIGHT))
+            .into_drawing_area();
+        root.fill(&BACKGROUND).map_err(draw_error)?;
+        let (price_area, volume_area) = root.split_vertically(CHART_HEIGHT * 3 / 4);
+
+        let step = ((last.time - first.time) / (candles.len() as i64 - 1).max(1)).max(1);
+        let x_range = (first.time - step)..(last.time + step);
+
+        let low = candles.iter().map(|c| c.low).fold(f64::INFINITY, f64::min);
+        let high = candles
+            .iter()
+            .map(|c| c.high)
+            .fold(f64::NEG_INFINITY, f64::max);
+        // Flat charts still get some room above and below the line
+        let padding = match (high - low) * 0.05 {
+            padding if padding > 0.0 => padding,
+            _ => high.abs().max(f64::MIN_POSITIVE) * 0.01,
+        };
+
+        let mut price_chart = ChartBuilder::on(&price_area)
+            .margin(10)
+            .build_cartesian_2d(x_range.clone(), (low - padding)..(high + padding))
+            .map_err(draw_error)?;
+
+        price_chart
+            .draw_series((1..4).map(|i| {
+                let y = low + (high - low) * i as f64 / 4.0;
+                PathElement::new([(x_range.start, y), (x_range.end, y)], GRID)
+            }))
+            .map_err(draw_error)?;
+
+        let slot_width = (CHART_WIDTH - 20) as f64 / (candles.len() + 2) as f64;
+        let candle_width = ((slot_width * 0.7) as u32).max(1);
+        price_chart
+            .draw_series(candles.iter().map(|candle| {
+                CandleStick::new(
+                    candle.time,
+                    candle.open,
+                    candle.high,
+                    candle.low,
+                    candle.close,
+                    UP.filled(),
+                    DOWN.filled(),
+                    candle_width,
+                )
+            }))
+            .map_err(draw_error)?;
+
+        let max_volume = candles
+            .iter()
+            .map(|c| c.volume_usd)
+            .fold(0.0, f64::max)
+            .max(f64::MIN_POSITIVE);
+        let mut volume_chart = ChartBuilder::on(&volume_area)
+            .margin(10)
+            .build_cartesian_2d(x_range, 0.0..max_volume * 1.1)
+            .map_err(draw_error)?;
+
+        let half_bar = (step * 35 / 100).max(1);
+        volume_chart
+            .draw_series(candles.iter().map(|candle| {
+                let color = if candle.close >= candle.open {
+                    UP
+                } else {
+                    DOWN
+                };
+                Rectangle::new(
+                    [
+                        (candle.time - half_bar, 0.0),
+                        (candle.time + half_bar, candle.volume_usd),
+                    ],
+                    color.mix(0.6).filled(),
+                )
+            }))
+            .map_err(draw_error)?;
+
+        root.present().map_err(draw_error)?;
+    }
+
+    let mut png_bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png_bytes, CHART_WIDTH, CHART_HEIGHT);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&buffer)?;
+
+    Ok(png_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // Deterministic wave so the snapshot only changes when rendering does
+    fn fixture() -> Vec<VybeOhlcv> {
+        (0..48)
+            .map(|i| {
+                let open = 1.0 + (i as f64 / 5.0).sin() * 0.2;
+                let close = 1.0 + ((i + 1) as f64 / 5.0).sin() * 0.2;
+                VybeOhlcv {
+                    time: 1_700_000_000 + i * 900,
+                    open,
+                    high: open.max(close) + 0.03,
+                    low: open.min(close) - 0.03,
+                    close,
+                    volume: 1000.0 + (i % 7) as f64 * 250.0,
+                    volume_usd: 1000.0 + (i % 7) as f64 * 250.0,
+                    count: None,
+                }
+            })
+            .collect()
+    }
+
+    fn decode(png_bytes: &[u8]) -> (png::OutputInfo, Vec<u8>) {
+        let mut reader = png::Decoder::new(png_bytes).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        pixels.truncate(info.buffer_size());
+        (info, pixels)
+    }
+
+    #[test]
+    fn test_render_candles_matches_snapshot() {
+        let png_bytes = render_candles(&fixture()).unwrap();
+        let snapshot = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/snapshots/chart.png");
+
+        // UPDATE_SNAPSHOTS=1 cargo test -p telegram rewrites the snapshot after intended changes
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(&snapshot, &png_bytes).unwrap();
+        }
+
+        let (info, pixels) = decode(&png_bytes);
+        assert_eq!((info.width, info.height), (CHART_WIDTH, CHART_HEIGHT));
+        let (_, expected) = decode(&std::fs::read(&snapshot).unwrap());
+        assert!(
+            pixels == expected,
+            "chart differs from {}",
+            snapshot.display()
+        );
+    }
+
+    #[test]
+    fn test_render_candles_edge_cases() {
+        assert!(matches!(render_candles(&[]), Err(ChartError::Empty)));
+
+        // A single flat candle has no price range and no time step
+        let mut flat = fixture()[0].clone();
+        (flat.open, flat.high, flat.low, flat.close) = (1.0, 1.0, 1.0, 1.0);
+        assert!(render_candles(&[flat]).is_ok());
+    }
+}
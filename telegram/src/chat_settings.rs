@@ -0,0 +1,94 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, RwLock},
+};
+
+use chrono::Timelike;
+use entity::chat_setting::{self, Entity as ChatSetting};
+use sea_orm::DbErr;
+use teloxide::{
+    types::{Chat, ChatId},
+    utils::command::BotCommands,
+};
+
+use crate::GlobalCommand;
+
+/// Commands a group can turn off from `/settings`, in `/help` order
+pub static GROUP_COMMANDS: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
+    // Every command parses without arguments, which is enough to ask for its toggle name
+    GlobalCommand::bot_commands()
+        .iter()
+        .filter_map(|command| GlobalCommand::parse(&command.command, "").ok())
+        .filter_map(|command| command.group_toggle_name())
+        .collect()
+});
+
+// Settings are read on every group message, so keep them in memory once loaded
+static SETTINGS: LazyLock<RwLock<HashMap<ChatId, chat_setting::Model>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+pub fn is_group_chat(chat: &Chat) -> bool {
+    chat.is_group() || chat.is_supergroup()
+}
+
+/// The settings of a chat, defaults when they can't be loaded
+pub async fn chat_settings(chat_id: ChatId) -> chat_setting::Model {
+    if let Some(settings) = SETTINGS.read().unwrap().get(&chat_id) {
+        return settings.clone();
+    }
+
+    let db = entity::get_db().await;
+    match ChatSetting::find_or_default(db, chat_id.0).await {
+        Ok(settings) => {
+            SETTINGS.write().unwrap().insert(chat_id, settings.clone());
+            settings
+        }
+        Err(err) => {
+            tracing::error!("Failed to load settings of chat {}: {:?}", chat_id, err);
+            chat_setting::Model::new(chat_id.0)
+        }
+    }
+}
+
+pub async fn save_chat_settings(settings: chat_setting::Model) -> Result<(), DbErr> {
+    let db = entity::get_db().await;
+    ChatSetting::save(db, settings.clone()).await?;
+    SETTINGS
+        .write()
+        .unwrap()
+        .insert(ChatId(settings.chat_id), settings);
+    Ok(())
+}
+
+/// Whether the chat wants token cards for mints posted in it right now
+pub async fn auto_cards_enabled(chat: &Chat) -> bool {
+    if !is_group_chat(chat) {
+        return true;
+    }
+
+    let settings = chat_settings(chat.id).await;
+    settings.auto_cards && !settings.is_muted_at(chrono::Utc::now().hour() as i16)
+}
+
+/// Whether a command may run in this chat, private chats allow everything
+pub async fn command_allowed(chat: &Chat, command: Option<&str>) -> bool {
+    match command {
+        Some(command) if is_group_chat(chat) => {
+            !chat_settings(chat.id).await.is_command_disabled(command)
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_commands() {
+        assert_eq!(&GROUP_COMMANDS[..2], ["alert", "alerts"]);
+        assert!(GROUP_COMMANDS.contains(&"referrals"));
+        assert!(!GROUP_COMMANDS.contains(&"start"));
+        assert!(!GROUP_COMMANDS.contains(&"settings"));
+    }
+}
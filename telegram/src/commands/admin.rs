@@ -0,0 +1,516 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use entity::{
+    daily_lookup::{self, Entity as DailyLookup},
+    price_alert::{self, Entity as PriceAlert},
+    tg_user::{self, Entity as TgUser},
+    watchlist_entry::Entity as WatchlistEntry,
+};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId},
+    RequestError,
+};
+
+use crate::{
+    bans::{self, is_admin},
+    i18n::{t, user_lang, Lang},
+    stats, HandlerResult,
+};
+
+pub const BROADCAST_PREFIX: &str = "bc:";
+
+// Telegram allows about 30 messages per second to different chats
+const SEND_INTERVAL: Duration = Duration::from_millis(40);
+
+// The progress message is edited at most this often
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(3);
+
+// Flood waits honoured for one recipient before giving up on them
+const MAX_FLOOD_RETRIES: u32 = 3;
+
+// Cancel flag of the broadcast in progress, only one runs at a time
+static BROADCAST: LazyLock<Mutex<Option<Arc<AtomicBool>>>> = LazyLock::new(|| Mutex::new(None));
+
+struct Stats {
+    users: u64,
+    new_users: u64,
+    banned: usize,
+    active_alerts: u64,
+    watchlist_entries: u64,
+    /// Stored daily counts, newest first
+    lookups: Vec<daily_lookup::Model>,
+    pending_lookups: i64,
+    stream: aggregator::StreamHealth,
+}
+
+fn render_stats(lang: Lang, stats: &Stats, now: chrono::DateTime<chrono::Utc>) -> String {
+    let today = now.date_naive();
+    let lookups_on = |day: chrono::NaiveDate| {
+        stats
+            .lookups
+            .iter()
+            .find(|count| count.day == day)
+            .map_or(0, |count| count.lookups)
+    };
+    let week = stats.lookups.iter().map(|count| count.lookups).sum::<i64>() + stats.pending_lookups;
+
+    let seconds = stats
+        .stream
+        .last_trade_at
+        .map(|last_trade_at| (now.timestamp() - last_trade_at).max(0));
+    let trades = stats.stream.trades;
+    let stream = match (stats.stream.connected, seconds) {
+        (true, Some(seconds)) => t!(
+            lang,
+            "stats-stream-connected",
+            trades = trades,
+            seconds = seconds
+        ),
+        (false, Some(seconds)) => t!(
+            lang,
+            "stats-stream-disconnected",
+            trades = trades,
+            seconds = seconds
+        ),
+        (true, None) => t!(lang, "stats-stream-idle"),
+        (false, None) => t!(lang, "stats-stream-down"),
+    };
+
+    t!(
+        lang,
+        "stats",
+        users = stats.users,
+        new_users = stats.new_users,
+        banned = stats.banned,
+        today = lookups_on(today) + stats.pending_lookups,
+        yesterday = lookups_on(today - chrono::Days::new(1)),
+        week = week,
+        alerts = stats.active_alerts,
+        watchlist = stats.watchlist_entries,
+        stream = stream
+    )
+}
+
+pub async fn stats(bot: Bot, message: Message) -> HandlerResult {
+    let lang = user_lang(message.from.as_ref()).await;
+    let db = entity::get_db().await;
+    let now = chrono::Utc::now();
+
+    let stats = Stats {
+        users: TgUser::find().count(db).await?,
+        new_users: TgUser::find()
+            .filter(tg_user::Column::CreatedAt.gte(now - chrono::Duration::hours(24)))
+            .count(db)
+            .await?,
+        banned: bans::banned_count(),
+        active_alerts: PriceAlert::find()
+            .filter(price_alert::Column::Active.eq(true))
+            .count(db)
+            .await?,
+        watchlist_entries: WatchlistEntry::find().count(db).await?,
+        lookups: DailyLookup::find_since(db, now.date_naive() - chrono::Days::new(6)).await?,
+        pending_lookups: stats::pending_lookups(),
+        stream: aggregator::stream_health(),
+    };
+
+    bot.send_message(message.chat.id, render_stats(lang, &stats, now))
+        .await?;
+    Ok(())
+}
+
+/// What a broadcast sends, text or a copy of the message the command replied to
+#[derive(Clone)]
+enum Content {
+    Text(String),
+    Copy(ChatId, MessageId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Running,
+    Cancelled,
+    Finished,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Progress {
+    total: usize,
+    sent: usize,
+    failed: usize,
+}
+
+impl Progress {
+    fn text(&self, lang: Lang, phase: Phase) -> String {
+        let (sent, total, failed) = (self.sent, self.total, self.failed);
+        match phase {
+            Phase::Running => t!(
+                lang,
+                "broadcast-running",
+                sent = sent,
+                total = total,
+                failed = failed
+            ),
+            Phase::Cancelled => t!(
+                lang,
+                "broadcast-cancelled",
+                sent = sent,
+                total = total,
+                failed = failed
+            ),
+            Phase::Finished => t!(
+                lang,
+                "broadcast-finished",
+                sent = sent,
+                total = total,
+                failed = failed
+            ),
+        }
+    }
+}
+
+fn cancel_markup(lang: Lang) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new([[InlineKeyboardButton::callback(
+        t!(lang, "broadcast-cancel"),
+        format!("{}cancel", BROADCAST_PREFIX),
+    )]])
+}
+
+// Send to one user, waiting out flood limits
+async fn deliver(bot: &Bot, chat_id: ChatId, content: &Content) -> Result<(), RequestError> {
+    let mut retries = 0;
+    loop {
+        let result = match content {
+            Content::Text(text) => bot.send_message(chat_id, text).await.map(|_| ()),
+            Content::Copy(from_chat_id, message_id) => bot
+                .copy_message(chat_id, *from_chat_id, *message_id)
+                .await
+                .map(|_| ()),
+        };
+        match result {
+            Err(RequestError::RetryAfter(wait)) if retries < MAX_FLOOD_RETRIES => {
+                tracing::warn!("Broadcast flood wait of {:?}", wait.duration());
+                tokio::time::sleep(wait.duration()).await;
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+async fn run_broadcast(
+    bot: Bot,
+    lang: Lang,
+    recipients: Vec<i64>,
+    content: Content,
+    progress_chat_id: ChatId,
+    progress_message_id: MessageId,
+    cancelled: Arc<AtomicBool>,
+) {
+    let mut progress = Progress {
+        total: recipients.len(),
+        ..Default::default()
+    };
+    let mut reported_at = Instant::now();
+
+    for telegram_id in recipients {
+        if cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+
+        match deliver(&bot, ChatId(telegram_id), &content).await {
+            Ok(()) => progress.sent += 1,
+            Err(err) => {
+                // Mostly users who blocked the bot
+                tracing::debug!("Broadcast to {} failed: {:?}", telegram_id, err);
+                progress.failed += 1;
+            }
+        }
+
+        if reported_at.elapsed() >= PROGRESS_INTERVAL {
+            reported_at = Instant::now();
+            let _ = bot
+                .edit_message_text(
+                    progress_chat_id,
+                    progress_message_id,
+                    progress.text(lang, Phase::Running),
+                )
+                .reply_markup(cancel_markup(lang))
+                .await;
+        }
+        tokio::time::sleep(SEND_INTERVAL).await;
+    }
+
+    let phase = if cancelled.load(Ordering::Relaxed) {
+        Phase::Cancelled
+    } else {
+        Phase::Finished
+    };
+    if let Err(err) = bot
+        .edit_message_text(
+            progress_chat_id,
+            progress_message_id,
+            progress.text(lang, phase),
+        )
+        .await
+    {
+        tracing::warn!("Failed to report broadcast progress: {:?}", err);
+    }
+    *BROADCAST.lock().unwrap() = None;
+}
+
+/// Send a message to every user, `/broadcast <text>` or in reply to the message to copy
+pub async fn broadcast(bot: Bot, message: Message, text: String) -> HandlerResult {
+    let lang = user_lang(message.from.as_ref()).await;
+    let content = match (text.trim(), message.reply_to_message()) {
+        ("", Some(reply)) => Content::Copy(reply.chat.id, reply.id),
+        ("", None) => {
+            bot.send_message(message.chat.id, t!(lang, "broadcast-usage"))
+                .await?;
+            return Ok(());
+        }
+        (text, _) => Content::Text(text.to_string()),
+    };
+
+    let started = {
+        let mut running = BROADCAST.lock().unwrap();
+        match *running {
+            Some(_) => None,
+            None => Some(running.insert(Arc::new(AtomicBool::new(false))).clone()),
+        }
+    };
+    let Some(cancelled) = started else {
+        bot.send_message(message.chat.id, t!(lang, "broadcast-already-running"))
+            .await?;
+        return Ok(());
+    };
+
+    let db = entity::get_db().await;
+    let recipients = match TgUser::find_telegram_ids(db).await {
+        Ok(ids) => ids
+            .into_iter()
+            .filter(|id| !bans::is_banned_id(*id))
+            .collect::<Vec<_>>(),
+        Err(err) => {
+            *BROADCAST.lock().unwrap() = None;
+            return Err(err.into());
+        }
+    };
+
+    let progress = Progress {
+        total: recipients.len(),
+        ..Default::default()
+    };
+    let progress_message = bot
+        .send_message(message.chat.id, progress.text(lang, Phase::Running))
+        .reply_markup(cancel_markup(lang))
+        .await;
+    let progress_message = match progress_message {
+        Ok(progress_message) => progress_message,
+        Err(err) => {
+            *BROADCAST.lock().unwrap() = None;
+            return Err(err.into());
+        }
+    };
+
+    // Runs in the background so the cancel button can be handled meanwhile
+    tokio::spawn(run_broadcast(
+        bot,
+        lang,
+        recipients,
+        content,
+        progress_message.chat.id,
+        progress_message.id,
+        cancelled,
+    ));
+    Ok(())
+}
+
+/// The cancel button of a running broadcast
+pub async fn broadcast_callback(bot: Bot, query: CallbackQuery) -> HandlerResult {
+    if !is_admin(query.from.id) {
+        bot.answer_callback_query(query.id.clone()).await?;
+        return Ok(());
+    }
+
+    let lang = user_lang(Some(&query.from)).await;
+    let cancelling = match BROADCAST.lock().unwrap().as_ref() {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    };
+    let text = if cancelling {
+        t!(lang, "broadcast-cancelling")
+    } else {
+        t!(lang, "broadcast-not-running")
+    };
+    bot.answer_callback_query(query.id.clone())
+        .text(text)
+        .await?;
+    Ok(())
+}
+
+/// The user id and reason of `/ban`, from the arguments or the message replied to
+fn parse_ban(args: &str, replied_user: Option<i64>) -> Option<(i64, Option<String>)> {
+    let args = args.trim();
+    match replied_user {
+        // Replying, everything after /ban is the reason, even when it starts with a number
+        Some(replied_user) => Some((replied_user, Some(args))),
+        None => {
+            let (telegram_id, reason) = match args.split_once(char::is_whitespace) {
+                Some((id, reason)) => (id, Some(reason.trim())),
+                None => (args, None),
+            };
+            telegram_id
+                .parse::<i64>()
+                .ok()
+                .map(|telegram_id| (telegram_id, reason))
+        }
+    }
+    .map(|(telegram_id, reason)| {
+        (
+            telegram_id,
+            reason
+                .filter(|reason| !reason.is_empty())
+                .map(str::to_string),
+        )
+    })
+}
+
+pub async fn ban(bot: Bot, message: Message, args: String) -> HandlerResult {
+    let Some(admin) = message.from.as_ref() else {
+        return Ok(());
+    };
+    let replied_user = message
+        .reply_to_message()
+        .and_then(|reply| reply.from.as_ref())
+        .map(|user| user.id.0 as i64);
+
+    let lang = user_lang(Some(admin)).await;
+    let Some((telegram_id, reason)) = parse_ban(&args, replied_user) else {
+        bot.send_message(message.chat.id, t!(lang, "ban-usage"))
+            .await?;
+        return Ok(());
+    };
+    if is_admin(UserId(telegram_id as u64)) {
+        bot.send_message(message.chat.id, t!(lang, "ban-admin"))
+            .await?;
+        return Ok(());
+    }
+
+    bans::ban(telegram_id, admin.id.0 as i64, reason).await?;
+    bot.send_message(message.chat.id, t!(lang, "ban-done", id = telegram_id))
+        .await?;
+    Ok(())
+}
+
+pub async fn unban(bot: Bot, message: Message, args: String) -> HandlerResult {
+    let lang = user_lang(message.from.as_ref()).await;
+    let Ok(telegram_id) = args.trim().parse::<i64>() else {
+        bot.send_message(message.chat.id, t!(lang, "unban-usage"))
+            .await?;
+        return Ok(());
+    };
+
+    let text = if bans::unban(telegram_id).await? {
+        t!(lang, "unban-done", id = telegram_id)
+    } else {
+        t!(lang, "unban-not-banned", id = telegram_id)
+    };
+    bot.send_message(message.chat.id, text).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_ban() {
+        assert_eq!(parse_ban("42", None), Some((42, None)));
+        assert_eq!(
+            parse_ban("42  spam links ", None),
+            Some((42, Some("spam links".to_string())))
+        );
+        assert_eq!(
+            parse_ban("spam links", Some(7)),
+            Some((7, Some("spam links".to_string())))
+        );
+        assert_eq!(parse_ban("", Some(7)), Some((7, None)));
+        assert_eq!(
+            parse_ban("3 strikes", Some(7)),
+            Some((7, Some("3 strikes".to_string())))
+        );
+        assert_eq!(parse_ban("spam", None), None);
+    }
+
+    #[test]
+    fn test_render_stats() {
+        let now = chrono::Utc
+            .with_ymd_and_hms(2026, 10, 19, 12, 0, 0)
+            .unwrap();
+        let day = |days_ago: u64, lookups: i64| daily_lookup::Model {
+            day: now.date_naive() - chrono::Days::new(days_ago),
+            lookups,
+        };
+        let mut stats = Stats {
+            users: 120,
+            new_users: 4,
+            banned: 2,
+            active_alerts: 31,
+            watchlist_entries: 57,
+            lookups: vec![day(0, 10), day(1, 25), day(3, 5)],
+            pending_lookups: 3,
+            stream: aggregator::StreamHealth {
+                connected: true,
+                trades: 9000,
+                last_trade_at: Some(now.timestamp() - 2),
+            },
+        };
+
+        let text = render_stats(Lang::En, &stats, now);
+        assert!(text.contains("Users: 120 (+4 in 24h), 2 banned"));
+        assert!(text.contains("Lookups: 13 today, 25 yesterday, 43 in 7d"));
+        assert!(text.contains("Active alerts: 31"));
+        assert!(text.contains("Live feed: connected, 9000 trades, last 2s ago"));
+
+        let text = render_stats(Lang::Es, &stats, now);
+        assert!(text.contains("Usuarios: 120 (+4 en 24h), 2 bloqueados"));
+        assert!(text.contains("Feed en vivo: conectado, 9000 trades, último hace 2s"));
+
+        stats.stream = aggregator::StreamHealth {
+            connected: false,
+            trades: 0,
+            last_trade_at: None,
+        };
+        let text = render_stats(Lang::En, &stats, now);
+        assert!(text.contains("Live feed: disconnected"));
+    }
+
+    #[test]
+    fn test_progress_text() {
+        let progress = Progress {
+            total: 10,
+            sent: 7,
+            failed: 1,
+        };
+        assert_eq!(
+            progress.text(Lang::En, Phase::Running),
+            "📣 Broadcast running: 7/10 sent, 1 failed"
+        );
+        assert_eq!(
+            progress.text(Lang::Es, Phase::Finished),
+            "📣 Difusión terminada: 7/10 enviados, 1 fallidos"
+        );
+    }
+}
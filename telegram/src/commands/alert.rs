@@ -0,0 +1,283 @@
+use entity::price_alert::{ActiveModel, AlertCondition, Entity as PriceAlert, Model};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+};
+use utils::endpoints::vybe::util::VYBE_TOKEN_API;
+
+use super::message::is_valid_solana_mint_address;
+use crate::{
+    alerts::alerts_changed,
+    i18n::{t, user_lang, Lang},
+    HandlerResult,
+};
+
+pub const DELETE_ALERT_PREFIX: &str = "alert_del:";
+
+const MAX_ALERTS_PER_USER: usize = 20;
+const DEFAULT_COOLDOWN_SECS: i64 = 60 * 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRequest {
+    pub mint_address: String,
+    pub condition: AlertCondition,
+    pub target: f64,
+    pub repeating: bool,
+}
+
+/// Parse the arguments of `/alert`
+pub fn parse_alert(args: &str) -> Option<AlertRequest> {
+    let mut parts = args.split_whitespace().collect::<Vec<_>>();
+
+    let repeating = parts
+        .last()
+        .is_some_and(|last| last.eq_ignore_ascii_case("repeat"));
+    if repeating {
+        parts.pop();
+    }
+
+    let (mint_address, condition, target) = match parts.as_slice() {
+        [mint, direction, price] => {
+            let condition = match direction.to_ascii_lowercase().as_str() {
+                "above" => AlertCondition::Above,
+                "below" => AlertCondition::Below,
+                _ => return None,
+            };
+            let price = price.trim_start_matches('$').parse::<f64>().ok()?;
+            (mint, condition, price)
+        }
+        [mint, percent] => {
+            let percent = percent.strip_suffix('%')?.parse::<f64>().ok()?;
+            (mint, AlertCondition::PercentMove, percent)
+        }
+        _ => return None,
+    };
+
+    if !is_valid_solana_mint_address(mint_address) || !target.is_finite() || target == 0.0 {
+        return None;
+    }
+    if condition != AlertCondition::PercentMove && target < 0.0 {
+        return None;
+    }
+
+    Some(AlertRequest {
+        mint_address: mint_address.to_string(),
+        condition,
+        target,
+        repeating,
+    })
+}
+
+pub fn describe_alert(lang: Lang, alert: &Model) -> String {
+    let token = alert.symbol.as_deref().unwrap_or(&alert.mint_address);
+    let description = match alert.condition {
+        AlertCondition::Above => t!(
+            lang,
+            "alert-above",
+            token = token,
+            target = lang.number(&alert.target.to_string())
+        ),
+        AlertCondition::Below => t!(
+            lang,
+            "alert-below",
+            token = token,
+            target = lang.number(&alert.target.to_string())
+        ),
+        AlertCondition::PercentMove => t!(
+            lang,
+            "alert-move",
+            token = token,
+            target = lang.number(&format!("{:+}", alert.target))
+        ),
+    };
+
+    if alert.repeating {
+        t!(lang, "alert-repeating", alert = description)
+    } else {
+        description
+    }
+}
+
+pub async fn alert(bot: Bot, message: Message, args: String) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let lang = user_lang(Some(from)).await;
+    let Some(request) = parse_alert(&args) else {
+        bot.send_message(message.chat.id, t!(lang, "alert-usage"))
+            .await?;
+        return Ok(());
+    };
+
+    let db = entity::get_db().await;
+    let telegram_id = from.id.0 as i64;
+    if PriceAlert::find_active_by_user(db, telegram_id)
+        .await?
+        .len()
+        >= MAX_ALERTS_PER_USER
+    {
+        bot.send_message(
+            message.chat.id,
+            t!(lang, "alert-limit", count = MAX_ALERTS_PER_USER),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let token_details = match VYBE_TOKEN_API
+        .get_token_details(request.mint_address.clone())
+        .await
+    {
+        Ok(details) => details,
+        Err(err) => {
+            tracing::warn!("Failed to get token details for alert: {:?}", err);
+            bot.send_message(message.chat.id, t!(lang, "token-not-found"))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // Percentage moves are measured from the freshest price we have
+    let reference_price = aggregator::latest_price(&request.mint_address)
+        .map(|price| price.usd)
+        .unwrap_or(token_details.price);
+
+    let alert = PriceAlert::insert(ActiveModel {
+        telegram_id: Set(telegram_id),
+        chat_id: Set(message.chat.id.0),
+        mint_address: Set(request.mint_address),
+        symbol: Set(Some(token_details.symbol)),
+        condition: Set(request.condition),
+        target: Set(request.target),
+        reference_price: Set(Some(reference_price)),
+        repeating: Set(request.repeating),
+        cooldown_secs: Set(DEFAULT_COOLDOWN_SECS),
+        active: Set(true),
+        last_triggered_at: Set(None),
+        created_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    })
+    .exec_with_returning(db)
+    .await?;
+
+    alerts_changed();
+
+    bot.send_message(
+        message.chat.id,
+        t!(
+            lang,
+            "alert-set",
+            alert = describe_alert(lang, &alert),
+            price = lang.number(&reference_price.to_string())
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+fn alerts_markup(lang: Lang, alerts: &[Model]) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(alerts.iter().map(|alert| {
+        [InlineKeyboardButton::callback(
+            format!("❌ {}", describe_alert(lang, alert)),
+            format!("{}{}", DELETE_ALERT_PREFIX, alert.id),
+        )]
+    }))
+}
+
+fn alerts_text(lang: Lang, alerts: &[Model]) -> String {
+    if alerts.is_empty() {
+        t!(lang, "alerts-empty")
+    } else {
+        t!(lang, "alerts-list")
+    }
+}
+
+pub async fn alerts(bot: Bot, message: Message) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let db = entity::get_db().await;
+    let alerts = PriceAlert::find_active_by_user(db, from.id.0 as i64).await?;
+    let lang = user_lang(Some(from)).await;
+
+    bot.send_message(message.chat.id, alerts_text(lang, &alerts))
+        .reply_markup(alerts_markup(lang, &alerts))
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_alert(bot: Bot, query: CallbackQuery) -> HandlerResult {
+    let alert_id = query
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(DELETE_ALERT_PREFIX))
+        .and_then(|id| id.parse::<i32>().ok());
+
+    let db = entity::get_db().await;
+    let telegram_id = query.from.id.0 as i64;
+
+    if let Some(alert) = match alert_id {
+        Some(id) => PriceAlert::find_by_id(id).one(db).await?,
+        None => None,
+    } {
+        // Only the owner can remove an alert, buttons can be pressed by anyone in a group
+        if alert.telegram_id == telegram_id {
+            PriceAlert::delete_by_id(alert.id).exec(db).await?;
+            alerts_changed();
+        }
+    }
+
+    bot.answer_callback_query(query.id.clone()).await?;
+
+    if let Some(message) = &query.message {
+        let alerts = PriceAlert::find_active_by_user(db, telegram_id).await?;
+        let lang = user_lang(Some(&query.from)).await;
+        bot.edit_message_text(message.chat().id, message.id(), alerts_text(lang, &alerts))
+            .reply_markup(alerts_markup(lang, &alerts))
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    #[test]
+    fn test_parse_alert() {
+        assert_eq!(
+            parse_alert(&format!("{} above $0.5", MINT)),
+            Some(AlertRequest {
+                mint_address: MINT.to_string(),
+                condition: AlertCondition::Above,
+                target: 0.5,
+                repeating: false,
+            })
+        );
+        assert_eq!(
+            parse_alert(&format!("{} BELOW 0.00002 repeat", MINT))
+                .map(|r| (r.condition, r.repeating)),
+            Some((AlertCondition::Below, true))
+        );
+        assert_eq!(
+            parse_alert(&format!("{} -15%", MINT)).map(|r| (r.condition, r.target)),
+            Some((AlertCondition::PercentMove, -15.0))
+        );
+        assert_eq!(
+            parse_alert(&format!("{} 20%", MINT)).map(|r| r.target),
+            Some(20.0)
+        );
+
+        assert_eq!(parse_alert(""), None);
+        assert_eq!(parse_alert("notamint above 1"), None);
+        assert_eq!(parse_alert(&format!("{} sideways 1", MINT)), None);
+        assert_eq!(parse_alert(&format!("{} above -1", MINT)), None);
+        assert_eq!(parse_alert(&format!("{} 0%", MINT)), None);
+    }
+}
@@ -0,0 +1,246 @@
+use teloxide::{
+    prelude::*,
+    types::{
+        InlineKeyboardButton, InlineKeyboardButtonKind, InlineKeyboardMarkup,
+        MaybeInaccessibleMessage,
+    },
+    ApiError, RequestError,
+};
+use utils::endpoints::vybe::util::VYBE_TOKEN_API;
+
+use super::{
+    alert::{alert, parse_alert},
+    chart::send_chart,
+    message::handle_message,
+    watchlist::add_to_watchlist,
+};
+use crate::{
+    addresses::extract_addresses,
+    cache,
+    card::{
+        card_markup, decode_card_action, token_card_with, CardAction, CardContext, CardPeriod,
+        CARD_ACTION_PREFIX, CARD_PARSE_MODE,
+    },
+    card_sender::NO_LINK_PREVIEW,
+    chat_settings::is_group_chat,
+    holders::token_top10_share,
+    i18n::{t, user_lang, Lang},
+    registry::parse_cashtag,
+    GlobalDialogue, GlobalState, HandlerResult,
+};
+
+// Candles fetched to find the price an hour ago
+const HOUR_REFERENCE_RESOLUTION: &str = "5m";
+
+/// Price of a token an hour ago, from the first 5 minute candle of the last hour
+async fn hour_reference_price(mint_address: &str) -> Option<f64> {
+    let now = chrono::Utc::now().timestamp();
+    let candles = VYBE_TOKEN_API
+        .get_token_ohlcv(mint_address, HOUR_REFERENCE_RESOLUTION, now - 60 * 60, now)
+        .await
+        .map_err(|err| tracing::warn!("Failed to get candles of {}: {:?}", mint_address, err))
+        .ok()?;
+
+    candles
+        .data
+        .into_iter()
+        .min_by_key(|candle| candle.time)
+        .map(|candle| candle.open)
+}
+
+// The card buttons followed by any other rows the message had, like cashtag alternatives
+fn refreshed_markup(
+    lang: Lang,
+    existing: Option<&InlineKeyboardMarkup>,
+    mint_address: &str,
+    period: CardPeriod,
+) -> InlineKeyboardMarkup {
+    let mut markup = card_markup(lang, mint_address, period);
+    let is_card_row = |row: &Vec<InlineKeyboardButton>| {
+        row.iter().any(|button| {
+            matches!(&button.kind, InlineKeyboardButtonKind::CallbackData(data)
+                if data.starts_with(CARD_ACTION_PREFIX))
+        })
+    };
+
+    if let Some(existing) = existing {
+        markup.inline_keyboard.extend(
+            existing
+                .inline_keyboard
+                .iter()
+                .filter(|row| !is_card_row(row))
+                .cloned(),
+        );
+    }
+    markup
+}
+
+// Re-render the card in place with fresh details
+async fn refresh_card(
+    bot: &Bot,
+    lang: Lang,
+    message: &MaybeInaccessibleMessage,
+    mint_address: &str,
+    period: CardPeriod,
+) -> Result<(), RequestError> {
+    let token_details = match cache::refresh_token_details(mint_address).await {
+        Ok(token_details) => token_details,
+        Err(err) => {
+            tracing::warn!("Failed to refresh token card: {:?}", err);
+            return Ok(());
+        }
+    };
+    let hour_reference = match period {
+        CardPeriod::Hour => hour_reference_price(mint_address).await,
+        CardPeriod::Day | CardPeriod::Week => None,
+    };
+    let context = CardContext {
+        period,
+        hour_reference,
+        top10_share: token_top10_share(mint_address).await,
+    };
+    let card = token_card_with(lang, &token_details, &context);
+    let existing = message
+        .regular_message()
+        .and_then(|message| message.reply_markup());
+    let markup = refreshed_markup(lang, existing, mint_address, period);
+
+    // Cards without a working logo were sent as text
+    let result = match message.regular_message().and_then(Message::photo) {
+        Some(_) => bot
+            .edit_message_caption(message.chat().id, message.id())
+            .caption(card.caption)
+            .parse_mode(CARD_PARSE_MODE)
+            .reply_markup(markup)
+            .await
+            .map(drop),
+        None => bot
+            .edit_message_text(message.chat().id, message.id(), card.caption)
+            .parse_mode(CARD_PARSE_MODE)
+            .link_preview_options(NO_LINK_PREVIEW)
+            .reply_markup(markup)
+            .await
+            .map(drop),
+    };
+
+    match result {
+        // Pressing refresh twice within a price update changes nothing
+        Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Handle the buttons below a token card
+pub async fn card_callback(
+    bot: Bot,
+    query: CallbackQuery,
+    dialogue: GlobalDialogue,
+) -> HandlerResult {
+    let (Some((action, period, mint_address)), Some(message)) = (
+        query.data.as_deref().and_then(decode_card_action),
+        query.message.as_ref(),
+    ) else {
+        bot.answer_callback_query(query.id.clone()).await?;
+        return Ok(());
+    };
+
+    let lang = user_lang(Some(&query.from)).await;
+    match action {
+        CardAction::Refresh | CardAction::Period => {
+            bot.answer_callback_query(query.id.clone()).await?;
+            refresh_card(&bot, lang, message, mint_address, period).await?;
+        }
+        CardAction::Watch => {
+            let reply =
+                add_to_watchlist(lang, query.from.id.0 as i64, mint_address.to_string()).await?;
+            bot.answer_callback_query(query.id.clone())
+                .text(reply)
+                .await?;
+        }
+        // Anyone could answer the prompt in a group, so point them at the command instead
+        CardAction::Alert if is_group_chat(message.chat()) => {
+            bot.answer_callback_query(query.id.clone())
+                .text(t!(lang, "alert-group-hint", mint = mint_address))
+                .show_alert(true)
+                .await?;
+        }
+        CardAction::Alert => {
+            bot.answer_callback_query(query.id.clone()).await?;
+            dialogue
+                .update(GlobalState::AwaitingAlert {
+                    mint_address: mint_address.to_string(),
+                })
+                .await?;
+            bot.send_message(message.chat().id, t!(lang, "alert-prompt"))
+                .await?;
+        }
+        CardAction::Chart => {
+            bot.answer_callback_query(query.id.clone()).await?;
+            send_chart(&bot, message.chat().id, lang, mint_address, period).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The condition of an alert started from a token card
+pub async fn alert_condition(
+    bot: Bot,
+    message: Message,
+    dialogue: GlobalDialogue,
+    mint_address: String,
+) -> HandlerResult {
+    let lang = user_lang(message.from.as_ref()).await;
+    let text = message.text().unwrap_or_default().trim();
+    if text.eq_ignore_ascii_case("cancel") {
+        dialogue.update(GlobalState::Idle).await?;
+        bot.send_message(message.chat.id, t!(lang, "alert-cancelled"))
+            .await?;
+        return Ok(());
+    }
+
+    let args = format!("{} {}", mint_address, text);
+    if parse_alert(&args).is_none() {
+        // A pasted token moves on from the prompt to its card
+        if !extract_addresses(text).is_empty() || parse_cashtag(text).is_some() {
+            dialogue.update(GlobalState::Idle).await?;
+            handle_message(bot, message).await?;
+            return Ok(());
+        }
+        bot.send_message(message.chat.id, t!(lang, "alert-prompt"))
+            .await?;
+        return Ok(());
+    }
+
+    dialogue.update(GlobalState::Idle).await?;
+    alert(bot, message, args).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::encode_card_action;
+
+    const MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    #[test]
+    fn test_refreshed_markup_keeps_other_rows() {
+        let mut existing = card_markup(Lang::En, MINT, CardPeriod::Day);
+        existing
+            .inline_keyboard
+            .push(vec![InlineKeyboardButton::callback("other", "token:other")]);
+
+        let markup = refreshed_markup(Lang::En, Some(&existing), MINT, CardPeriod::Week);
+        let card_rows = card_markup(Lang::En, MINT, CardPeriod::Week)
+            .inline_keyboard
+            .len();
+
+        assert_eq!(markup.inline_keyboard.len(), card_rows + 1);
+        assert!(matches!(
+            &markup.inline_keyboard[0][0].kind,
+            InlineKeyboardButtonKind::CallbackData(data)
+                if *data == encode_card_action(CardAction::Refresh, CardPeriod::Week, MINT)
+        ));
+        assert_eq!(markup.inline_keyboard[card_rows][0].text, "other");
+    }
+}
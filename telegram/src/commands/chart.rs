@@ -0,0 +1,121 @@
+use teloxide::{prelude::*, types::InputFile};
+use utils::{endpoints::vybe::util::VYBE_TOKEN_API, number::format_compact_price};
+
+use super::message::is_valid_solana_mint_address;
+use crate::{
+    cache,
+    card::CardPeriod,
+    chart::{chart_window, render_candles, ChartError},
+    i18n::{t, user_lang, Lang},
+    HandlerResult,
+};
+
+/// Parse the arguments of `/chart`, the period defaults to 24h
+fn parse_chart(args: &str) -> Option<(&str, CardPeriod)> {
+    match args.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [mint_address] => Some((*mint_address, CardPeriod::Day)),
+        [mint_address, period] => Some((*mint_address, CardPeriod::from_label(period)?)),
+        _ => None,
+    }
+    .filter(|(mint_address, _)| is_valid_solana_mint_address(mint_address))
+}
+
+/// Render and send the candlestick chart of a token
+pub async fn send_chart(
+    bot: &Bot,
+    chat_id: ChatId,
+    lang: Lang,
+    mint_address: &str,
+    period: CardPeriod,
+) -> HandlerResult {
+    let (resolution, span) = chart_window(period);
+    let now = chrono::Utc::now().timestamp();
+    let mut candles = match VYBE_TOKEN_API
+        .get_token_ohlcv(mint_address, resolution, now - span, now)
+        .await
+    {
+        Ok(candles) => candles.data,
+        Err(err) => {
+            tracing::warn!("Failed to get candles of {}: {:?}", mint_address, err);
+            bot.send_message(chat_id, t!(lang, "chart-load-failed"))
+                .await?;
+            return Ok(());
+        }
+    };
+    candles.sort_by_key(|candle| candle.time);
+
+    let chart = {
+        let candles = candles.clone();
+        tokio::task::spawn_blocking(move || render_candles(&candles)).await?
+    };
+    let png_bytes = match chart {
+        Ok(png_bytes) => png_bytes,
+        Err(ChartError::Empty) => {
+            bot.send_message(
+                chat_id,
+                t!(lang, "chart-no-trades", period = period.label()),
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let symbol = cache::token_details(mint_address)
+        .await
+        .map_or_else(|_| mint_address.to_string(), |details| details.symbol);
+    let high = candles
+        .iter()
+        .map(|c| c.high)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let low = candles.iter().map(|c| c.low).fold(f64::INFINITY, f64::min);
+    let close = candles.last().map_or(0.0, |candle| candle.close);
+    let caption = t!(
+        lang,
+        "chart-caption",
+        symbol = symbol,
+        period = period.label(),
+        close = lang.number(&format_compact_price(close)),
+        high = lang.number(&format_compact_price(high)),
+        low = lang.number(&format_compact_price(low))
+    );
+
+    bot.send_photo(chat_id, InputFile::memory(png_bytes).file_name("chart.png"))
+        .caption(caption)
+        .await?;
+    Ok(())
+}
+
+pub async fn chart(bot: Bot, message: Message, args: String) -> HandlerResult {
+    let lang = user_lang(message.from.as_ref()).await;
+    let Some((mint_address, period)) = parse_chart(&args) else {
+        bot.send_message(message.chat.id, t!(lang, "chart-usage"))
+            .await?;
+        return Ok(());
+    };
+
+    send_chart(&bot, message.chat.id, lang, mint_address, period).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    #[test]
+    fn test_parse_chart() {
+        assert_eq!(parse_chart(MINT), Some((MINT, CardPeriod::Day)));
+        assert_eq!(
+            parse_chart(&format!("{} 1h", MINT)),
+            Some((MINT, CardPeriod::Hour))
+        );
+        assert_eq!(
+            parse_chart(&format!("{} 7D", MINT)),
+            Some((MINT, CardPeriod::Week))
+        );
+        assert_eq!(parse_chart(&format!("{} 3h", MINT)), None);
+        assert_eq!(parse_chart("notamint 1h"), None);
+        assert_eq!(parse_chart(""), None);
+    }
+}
@@ -0,0 +1,195 @@
+use entity::digest_subscription::Entity as DigestSubscription;
+use sea_orm::EntityTrait;
+use teloxide::prelude::*;
+
+use crate::{
+    digest::{format_local_time, format_utc_offset},
+    i18n::{t, user_lang},
+    HandlerResult,
+};
+
+// Sent at 09:00 UTC unless told otherwise
+const DEFAULT_LOCAL_MINUTE: i16 = 9 * 60;
+
+// UTC-12 to UTC+14 covers every time zone
+const MIN_UTC_OFFSET_MINUTES: i16 = -12 * 60;
+const MAX_UTC_OFFSET_MINUTES: i16 = 14 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestRequest {
+    Status,
+    On {
+        local_minute: i16,
+        utc_offset_minutes: i16,
+    },
+    Off,
+}
+
+// `9`, `09:30` to minutes after midnight, a sign makes it an offset instead
+fn parse_time(arg: &str) -> Option<i16> {
+    let (hours, minutes) = arg.split_once(':').unwrap_or((arg, "0"));
+    let is_number = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    if !is_number(hours) || !is_number(minutes) {
+        return None;
+    }
+    let hours = hours.parse::<i16>().ok()?;
+    let minutes = minutes.parse::<i16>().ok()?;
+    ((0..24).contains(&hours) && (0..60).contains(&minutes)).then_some(hours * 60 + minutes)
+}
+
+// `UTC`, `UTC+2`, `+5:30`, `GMT-3` to minutes
+fn parse_utc_offset(arg: &str) -> Option<i16> {
+    let upper = arg.to_ascii_uppercase();
+    let offset = upper
+        .strip_prefix("UTC")
+        .or_else(|| upper.strip_prefix("GMT"))
+        .unwrap_or(&upper);
+    if offset.is_empty() {
+        return Some(0);
+    }
+
+    let (sign, offset) = if let Some(rest) = offset.strip_prefix('+') {
+        (1, rest)
+    } else {
+        (-1, offset.strip_prefix('-')?)
+    };
+    let minutes = sign * parse_time(offset)?;
+    (MIN_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES)
+        .contains(&minutes)
+        .then_some(minutes)
+}
+
+/// Parse the arguments of `/digest`: nothing, `off` or `on [HH:MM] [UTC±H[:MM]]`
+pub fn parse_digest(args: &str) -> Option<DigestRequest> {
+    let mut args = args.split_whitespace();
+    let request = match args.next().map(str::to_ascii_lowercase).as_deref() {
+        None => DigestRequest::Status,
+        Some("off") => DigestRequest::Off,
+        Some("on") => {
+            let mut local_minute = DEFAULT_LOCAL_MINUTE;
+            let mut utc_offset_minutes = 0;
+            let mut arg = args.next();
+            if let Some(time) = arg.and_then(parse_time) {
+                local_minute = time;
+                arg = args.next();
+            }
+            if let Some(offset) = arg {
+                utc_offset_minutes = parse_utc_offset(offset)?;
+            }
+            DigestRequest::On {
+                local_minute,
+                utc_offset_minutes,
+            }
+        }
+        Some(_) => return None,
+    };
+
+    args.next().is_none().then_some(request)
+}
+
+pub async fn digest(bot: Bot, message: Message, args: String) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let lang = user_lang(Some(from)).await;
+    let Some(request) = parse_digest(&args) else {
+        bot.send_message(message.chat.id, t!(lang, "digest-usage"))
+            .await?;
+        return Ok(());
+    };
+
+    let db = entity::get_db().await;
+    let telegram_id = from.id.0 as i64;
+    let reply = match request {
+        DigestRequest::Status => match DigestSubscription::find_by_id(telegram_id).one(db).await? {
+            Some(subscription) => t!(
+                lang,
+                "digest-status",
+                time = format_local_time(subscription.local_minute),
+                offset = format_utc_offset(subscription.utc_offset_minutes)
+            ),
+            None => t!(lang, "digest-not-subscribed"),
+        },
+        DigestRequest::On {
+            local_minute,
+            utc_offset_minutes,
+        } => {
+            DigestSubscription::subscribe(
+                db,
+                telegram_id,
+                message.chat.id.0,
+                local_minute,
+                utc_offset_minutes,
+                chrono::Utc::now(),
+            )
+            .await?;
+            t!(
+                lang,
+                "digest-on",
+                time = format_local_time(local_minute),
+                offset = format_utc_offset(utc_offset_minutes)
+            )
+        }
+        DigestRequest::Off => {
+            if DigestSubscription::unsubscribe(db, telegram_id).await? {
+                t!(lang, "digest-off")
+            } else {
+                t!(lang, "digest-not-subscribed")
+            }
+        }
+    };
+
+    bot.send_message(message.chat.id, reply).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_digest() {
+        assert_eq!(parse_digest(""), Some(DigestRequest::Status));
+        assert_eq!(parse_digest(" OFF "), Some(DigestRequest::Off));
+        assert_eq!(
+            parse_digest("on"),
+            Some(DigestRequest::On {
+                local_minute: 9 * 60,
+                utc_offset_minutes: 0
+            })
+        );
+        assert_eq!(
+            parse_digest("on 7:30 UTC+2"),
+            Some(DigestRequest::On {
+                local_minute: 7 * 60 + 30,
+                utc_offset_minutes: 120
+            })
+        );
+        assert_eq!(
+            parse_digest("on gmt-5:30"),
+            Some(DigestRequest::On {
+                local_minute: 9 * 60,
+                utc_offset_minutes: -330
+            })
+        );
+        assert_eq!(
+            parse_digest("on +2"),
+            Some(DigestRequest::On {
+                local_minute: 9 * 60,
+                utc_offset_minutes: 120
+            })
+        );
+        assert_eq!(
+            parse_digest("on 8 +5:30"),
+            Some(DigestRequest::On {
+                local_minute: 8 * 60,
+                utc_offset_minutes: 330
+            })
+        );
+        assert_eq!(parse_digest("on 24:00"), None);
+        assert_eq!(parse_digest("on 8 UTC+15"), None);
+        assert_eq!(parse_digest("on 8 UTC+2 extra"), None);
+        assert_eq!(parse_digest("weekly"), None);
+    }
+}
@@ -0,0 +1,197 @@
+use entity::trade_follow::Entity as TradeFollow;
+use teloxide::prelude::*;
+
+use crate::{
+    cache,
+    chat_settings::is_group_chat,
+    commands::{
+        message::is_valid_solana_mint_address, settings::is_chat_admin,
+        watchlist::sync_watched_mints,
+    },
+    i18n::{t, user_lang},
+    trade_feed::follows_changed,
+    HandlerResult,
+};
+
+// Every followed token adds a stream of messages to the chat
+const MAX_FOLLOWS_PER_CHAT: usize = 5;
+
+/// Parse the arguments of `/follow`: `<mint> [min_usd]`
+pub fn parse_follow(args: &str) -> Option<(String, f64)> {
+    let mut args = args.split_whitespace();
+    let mint_address = args
+        .next()
+        .filter(|arg| is_valid_solana_mint_address(arg))?;
+    let min_usd = match args.next() {
+        Some(arg) => arg
+            .trim_start_matches('$')
+            .replace(',', "")
+            .parse::<f64>()
+            .ok()
+            .filter(|min_usd| min_usd.is_finite() && *min_usd >= 0.0)?,
+        None => 0.0,
+    };
+
+    args.next()
+        .is_none()
+        .then(|| (mint_address.to_string(), min_usd))
+}
+
+// Anyone can follow in a private chat, only admins in a group
+async fn may_change_follows(bot: &Bot, message: &Message, from: &teloxide::types::User) -> bool {
+    if !is_group_chat(&message.chat) {
+        return true;
+    }
+    match is_chat_admin(bot, message.chat.id, from.id).await {
+        Ok(is_admin) => is_admin,
+        Err(err) => {
+            tracing::warn!("Failed to check admin status: {:?}", err);
+            false
+        }
+    }
+}
+
+pub async fn follow(bot: Bot, message: Message, args: String) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let lang = user_lang(Some(from)).await;
+    let Some((mint_address, min_usd)) = parse_follow(&args) else {
+        bot.send_message(message.chat.id, t!(lang, "follow-usage"))
+            .await?;
+        return Ok(());
+    };
+    if !may_change_follows(&bot, &message, from).await {
+        bot.send_message(message.chat.id, t!(lang, "follow-admins-only"))
+            .await?;
+        return Ok(());
+    }
+
+    let db = entity::get_db().await;
+    let chat_id = message.chat.id.0;
+    let follows = TradeFollow::find_by_chat(db, chat_id).await?;
+    if follows.len() >= MAX_FOLLOWS_PER_CHAT
+        && !follows
+            .iter()
+            .any(|follow| follow.mint_address == mint_address)
+    {
+        bot.send_message(
+            message.chat.id,
+            t!(lang, "follow-limit", count = MAX_FOLLOWS_PER_CHAT),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let symbol = match cache::token_details(&mint_address).await {
+        Ok(details) => details.symbol,
+        Err(err) => {
+            tracing::warn!("Failed to get token details for follow: {:?}", err);
+            bot.send_message(message.chat.id, t!(lang, "token-not-found"))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    TradeFollow::follow(
+        db,
+        chat_id,
+        &mint_address,
+        Some(symbol.clone()),
+        min_usd,
+        from.id.0 as i64,
+    )
+    .await?;
+    follows_changed();
+    sync_watched_mints().await;
+
+    bot.send_message(
+        message.chat.id,
+        t!(
+            lang,
+            "follow-set",
+            symbol = symbol,
+            min = lang.number(&format!("{:.0}", min_usd))
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn unfollow(bot: Bot, message: Message, args: String) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let lang = user_lang(Some(from)).await;
+    let db = entity::get_db().await;
+    let chat_id = message.chat.id.0;
+    let arg = args.trim();
+
+    // Without arguments list what can be unfollowed
+    if arg.is_empty() {
+        let follows = TradeFollow::find_by_chat(db, chat_id).await?;
+        let text = if follows.is_empty() {
+            t!(lang, "unfollow-none")
+        } else {
+            let lines = follows
+                .iter()
+                .map(|follow| {
+                    format!(
+                        "• {} {}",
+                        follow.symbol.as_deref().unwrap_or("?"),
+                        follow.mint_address
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("{}\n{}", t!(lang, "follow-list"), lines)
+        };
+        bot.send_message(message.chat.id, text).await?;
+        return Ok(());
+    }
+
+    if !may_change_follows(&bot, &message, from).await {
+        bot.send_message(message.chat.id, t!(lang, "follow-admins-only"))
+            .await?;
+        return Ok(());
+    }
+
+    let mint_address = (!arg.eq_ignore_ascii_case("all")).then_some(arg);
+    let removed = TradeFollow::unfollow(db, chat_id, mint_address).await?;
+    let text = if removed > 0 {
+        follows_changed();
+        sync_watched_mints().await;
+        t!(lang, "unfollow-done", count = removed)
+    } else {
+        t!(lang, "unfollow-none")
+    };
+    bot.send_message(message.chat.id, text).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    #[test]
+    fn test_parse_follow() {
+        assert_eq!(parse_follow(BONK), Some((BONK.to_string(), 0.0)));
+        assert_eq!(
+            parse_follow(&format!("{} $1,000", BONK)),
+            Some((BONK.to_string(), 1000.0))
+        );
+        assert_eq!(
+            parse_follow(&format!(" {}  250.5 ", BONK)),
+            Some((BONK.to_string(), 250.5))
+        );
+        assert_eq!(parse_follow(""), None);
+        assert_eq!(parse_follow("bonk"), None);
+        assert_eq!(parse_follow(&format!("{} -5", BONK)), None);
+        assert_eq!(parse_follow(&format!("{} lots", BONK)), None);
+        assert_eq!(parse_follow(&format!("{} 5 extra", BONK)), None);
+    }
+}
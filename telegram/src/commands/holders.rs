@@ -0,0 +1,144 @@
+use teloxide::prelude::*;
+use utils::{endpoints::vybe::types::VybeTopHolder, number::format_long_number};
+
+use super::message::is_valid_solana_mint_address;
+use crate::{
+    cache,
+    holders::{holder_label, top10_share, HolderKind},
+    i18n::{t, user_lang, Lang},
+    rich_text::{RichText, Style},
+    HandlerResult,
+};
+
+// Above this top 10 share a token is flagged as concentrated
+const CONCENTRATED_SHARE: f64 = 50.0;
+
+/// `Abcd…wxyz` for wallets without a label
+pub(crate) fn short_address(address: &str) -> String {
+    match (
+        address.get(..4),
+        address.get(address.len().saturating_sub(4)..),
+    ) {
+        (Some(start), Some(end)) if address.len() > 8 => format!("{}…{}", start, end),
+        _ => address.to_string(),
+    }
+}
+
+fn holder_row(lang: Lang, holder: &VybeTopHolder) -> String {
+    let (label, kind) = holder_label(holder);
+    let marker = match kind {
+        Some(HolderKind::Exchange) => "🏦",
+        Some(HolderKind::Burn) => "🔥",
+        Some(HolderKind::Liquidity) => "💧",
+        None => "  ",
+    };
+    let name = label
+        .map(|label| label.chars().take(12).collect::<String>())
+        .unwrap_or_else(|| short_address(&holder.owner_address));
+
+    format!(
+        "{:>2} {}{:<12} {:>7} {:>7}",
+        holder.rank,
+        marker,
+        name,
+        lang.number(&format!("{:.2}%", holder.percentage_of_supply_held)),
+        lang.number(&format_long_number(holder.balance.round()))
+    )
+}
+
+/// The holders table and concentration summary as HTML
+fn render_holders(lang: Lang, symbol: &str, holders: &[VybeTopHolder]) -> String {
+    let rows = holders
+        .iter()
+        .map(|holder| holder_row(lang, holder))
+        .collect::<Vec<_>>();
+    let summary = match top10_share(holders) {
+        Some(share) if share > CONCENTRATED_SHARE => t!(
+            lang,
+            "holders-concentrated",
+            share = lang.number(&format!("{:.1}", share))
+        ),
+        Some(share) => t!(
+            lang,
+            "holders-share",
+            share = lang.number(&format!("{:.1}", share))
+        ),
+        None => t!(lang, "holders-no-regular"),
+    };
+
+    let mut text = RichText::new();
+    text.bold(t!(lang, "holders-title", symbol = symbol))
+        .plain("\n")
+        .pre(rows.join("\n"))
+        .plain(format!("\n{}\n", summary))
+        .italic(t!(lang, "holders-legend"));
+    text.message(Style::Html)
+}
+
+pub async fn holders(bot: Bot, message: Message, mint_address: String) -> HandlerResult {
+    let lang = user_lang(message.from.as_ref()).await;
+    let mint_address = mint_address.trim();
+    if !is_valid_solana_mint_address(mint_address) {
+        bot.send_message(message.chat.id, t!(lang, "holders-usage"))
+            .await?;
+        return Ok(());
+    }
+
+    let holders = match cache::top_holders(mint_address).await {
+        Ok(holders) if !holders.is_empty() => holders,
+        Ok(_) => {
+            bot.send_message(message.chat.id, t!(lang, "holders-none"))
+                .await?;
+            return Ok(());
+        }
+        Err(err) => {
+            tracing::warn!("Failed to get holders of {}: {:?}", mint_address, err);
+            bot.send_message(message.chat.id, t!(lang, "holders-load-failed"))
+                .await?;
+            return Ok(());
+        }
+    };
+    let symbol = cache::token_details(mint_address)
+        .await
+        .map_or_else(|_| short_address(mint_address), |details| details.symbol);
+
+    bot.send_message(message.chat.id, render_holders(lang, &symbol, &holders))
+        .parse_mode(Style::Html.parse_mode())
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holder(rank: u32, owner_address: &str, percentage: f64) -> VybeTopHolder {
+        VybeTopHolder {
+            rank,
+            owner_address: owner_address.to_string(),
+            owner_name: None,
+            balance: 1_500_000.0,
+            percentage_of_supply_held: percentage,
+        }
+    }
+
+    #[test]
+    fn test_render_holders() {
+        let holders = [
+            holder(1, "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1", 40.0),
+            holder(2, "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263", 35.5),
+            holder(3, "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM", 20.0),
+        ];
+        let text = render_holders(Lang::En, "BONK", &holders);
+
+        assert!(text.starts_with("<b>Top holders of BONK</b>"));
+        assert!(text.contains(" 1 💧Raydium AMM   40.00%    1.5M"));
+        assert!(text.contains(" 2   DezX…B263     35.50%"));
+        assert!(text.contains("</pre>\n⚠️ Top 10 hold 55.5%"));
+        assert!(text.ends_with("</i>"));
+
+        // Symbols are user controlled
+        let text = render_holders(Lang::En, "<b>&", &holders);
+        assert!(text.starts_with("<b>Top holders of &lt;b&gt;&amp;</b>"));
+    }
+}
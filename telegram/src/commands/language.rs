@@ -0,0 +1,125 @@
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, User},
+};
+
+use crate::{
+    i18n::{set_language_override, t, user_lang, Lang},
+    HandlerResult,
+};
+
+pub const LANGUAGE_PREFIX: &str = "lang:";
+
+// Argument and callback value that drops the override
+const TELEGRAM_LANGUAGE: &str = "auto";
+
+/// `Some(None)` to go back to the Telegram language, `None` when nothing matches
+fn parse_language(arg: &str) -> Option<Option<Lang>> {
+    let arg = arg.trim();
+    if arg.eq_ignore_ascii_case(TELEGRAM_LANGUAGE) {
+        return Some(None);
+    }
+
+    Lang::from_code(arg)
+        .or_else(|| {
+            Lang::ALL
+                .into_iter()
+                .find(|lang| lang.native_name().eq_ignore_ascii_case(arg))
+        })
+        .map(Some)
+}
+
+fn language_markup(lang: Lang) -> InlineKeyboardMarkup {
+    let languages = Lang::ALL.into_iter().map(|option| {
+        let marker = if option == lang { "• " } else { "" };
+        InlineKeyboardButton::callback(
+            format!("{}{}", marker, option.native_name()),
+            format!("{}{}", LANGUAGE_PREFIX, option.code()),
+        )
+    });
+
+    InlineKeyboardMarkup::new([
+        languages.collect::<Vec<_>>(),
+        vec![InlineKeyboardButton::callback(
+            t!(lang, "language-telegram"),
+            format!("{}{}", LANGUAGE_PREFIX, TELEGRAM_LANGUAGE),
+        )],
+    ])
+}
+
+// Store the choice and confirm it in the language the user ends up with
+async fn apply_language(user: &User, choice: Option<Lang>) -> anyhow::Result<String> {
+    if !set_language_override(user.id.0 as i64, choice).await? {
+        return Ok(t!(user_lang(Some(user)).await, "language-start-first"));
+    }
+
+    let lang = user_lang(Some(user)).await;
+    Ok(match choice {
+        Some(choice) => t!(lang, "language-set", language = choice.native_name()),
+        None => t!(lang, "language-reset"),
+    })
+}
+
+pub async fn language(bot: Bot, message: Message, arg: String) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+    let lang = user_lang(Some(from)).await;
+
+    if arg.trim().is_empty() {
+        bot.send_message(
+            message.chat.id,
+            t!(lang, "language-choose", language = lang.native_name()),
+        )
+        .reply_markup(language_markup(lang))
+        .await?;
+        return Ok(());
+    }
+
+    let reply = match parse_language(&arg) {
+        Some(choice) => apply_language(from, choice).await?,
+        None => {
+            let languages = Lang::ALL
+                .iter()
+                .map(|lang| lang.code())
+                .chain([TELEGRAM_LANGUAGE])
+                .collect::<Vec<_>>();
+            t!(lang, "language-unknown", languages = languages.join(", "))
+        }
+    };
+    bot.send_message(message.chat.id, reply).await?;
+    Ok(())
+}
+
+/// Handle the buttons of `/language`
+pub async fn language_callback(bot: Bot, query: CallbackQuery) -> HandlerResult {
+    let choice = query
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(LANGUAGE_PREFIX))
+        .and_then(parse_language);
+    let (Some(choice), Some(message)) = (choice, &query.message) else {
+        bot.answer_callback_query(query.id.clone()).await?;
+        return Ok(());
+    };
+
+    let reply = apply_language(&query.from, choice).await?;
+    bot.answer_callback_query(query.id.clone()).await?;
+    bot.edit_message_text(message.chat().id, message.id(), reply)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_language() {
+        assert_eq!(parse_language("es"), Some(Some(Lang::Es)));
+        assert_eq!(parse_language(" español "), Some(Some(Lang::Es)));
+        assert_eq!(parse_language("English"), Some(Some(Lang::En)));
+        assert_eq!(parse_language("AUTO"), Some(None));
+        assert_eq!(parse_language("klingon"), None);
+    }
+}
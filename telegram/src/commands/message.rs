@@ -1,19 +1,39 @@
+use std::time::Duration;
+
+use entity::token_registry;
+use futures_util::future::join_all;
 use serde_json;
 use teloxide::{
     prelude::*,
-    types::{InputFile, ParseMode},
-    utils::markdown::link,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageEntityKind},
 };
-use url::Url;
 use utils::{
-    endpoints::vybe::{types::VybeTokenDetails, util::VYBE_TOKEN_API},
+    endpoints::vybe::{types::VybeTokenDetails, util::vybe_quota_low},
     http::HttpError,
-    math::calculate_price_change,
-    number::{format_decimal_price, format_long_number},
+    number::format_long_number,
+};
+
+use crate::{
+    addresses::extract_addresses,
+    cache,
+    card::{card_markup, token_card, token_card_with, CardContext, CardPeriod, CARD_PARSE_MODE},
+    card_sender::{send_card, NO_LINK_PREVIEW},
+    chat_settings::{auto_cards_enabled, chat_settings, is_group_chat},
+    commands::wallet::send_portfolio,
+    holders::token_top10_share,
+    i18n::{t, user_lang, Lang},
+    rate_limit::{check_lookup, Verdict},
+    registry::{self, parse_cashtag},
+    stats, HandlerResult,
 };
 
+pub const TOKEN_CARD_PREFIX: &str = "token:";
+
+// Other tokens sharing a cashtag offered below the top match
+const MAX_ALTERNATIVES: usize = 4;
+
 // Validate if a string is a valid Solana mint address
-fn is_valid_solana_mint_address(address: &str) -> bool {
+pub(crate) fn is_valid_solana_mint_address(address: &str) -> bool {
     // Simple validation: Solana addresses are base58 encoded and 32-44 characters long
     // This is a basic check and can be improved with more specific validation
     address.len() >= 32
@@ -23,98 +43,260 @@ fn is_valid_solana_mint_address(address: &str) -> bool {
             .all(|c| (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || (c >= '1' && c <= '9'))
 }
 
-// Function to display token details
+// Function to display token details, `extra_rows` go below the card buttons
 async fn display_token_details(
     bot: &Bot,
-    msg: &Message,
+    chat_id: ChatId,
+    lang: Lang,
     token_details: &VybeTokenDetails,
+    extra_rows: Option<InlineKeyboardMarkup>,
 ) -> Result<(), teloxide::RequestError> {
-    if let Some(logo_url) = &token_details.logo_url {
-        // Parse URL, return early if it fails
-        let url = match Url::parse(logo_url) {
-            Ok(url) => url,
-            Err(err) => {
-                eprintln!("Failed to parse logo URL: {:?}", err);
-                return Ok(());
-            }
-        };
+    let context = CardContext {
+        top10_share: token_top10_share(&token_details.mint_address).await,
+        ..CardContext::default()
+    };
+    let card = token_card_with(lang, token_details, &context);
+    let mut markup = card_markup(lang, &token_details.mint_address, CardPeriod::default());
+    if let Some(extra_rows) = extra_rows {
+        markup.inline_keyboard.extend(extra_rows.inline_keyboard);
+    }
 
-        let vybe_token_url = |token_mint_address: &str| {
+    send_card(bot, chat_id, &token_details.mint_address, &card, markup).await?;
+    Ok(())
+}
+
+fn alternatives_markup(alternatives: &[token_registry::Model]) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(alternatives.iter().map(|token| {
+        let verified = if token.verified { "🟢" } else { "🔴" };
+        [InlineKeyboardButton::callback(
             format!(
-                "https://alpha.vybenetwork.com/tokens/{}",
-                token_mint_address
-            )
-        };
+                "{} {} · {}… · ${}",
+                verified,
+                token.symbol,
+                &token.mint_address[..6.min(token.mint_address.len())],
+                format_long_number(token.volume_24h.round())
+            ),
+            format!("{}{}", TOKEN_CARD_PREFIX, token.mint_address),
+        )]
+    }))
+}
 
-        bot.send_photo(msg.chat.id, InputFile::url(url))
-            .caption(format!(
-                "🟣*{}* ({}) \n\
-                \n\
-                *Token details* 📊\n\
-                ├ Price: *${}* ({}%) \n\
-                ├ MC: *{}*\n\
-                ├ Supply: *{}*\n\
-                ├ Vol (24h): *${}*\n\
-                └ Verified: {}\n\
-                \n\
-                {} \n\
-                └ {}
-                ",
-                token_details
-                    .name
-                    .as_ref()
-                    .map_or("Unknown".to_string(), |s| s.clone()),
-                token_details.symbol,
-                format_decimal_price(token_details.price, None),
-                format!(
-                    "{:.*}",
-                    2,
-                    calculate_price_change(token_details.price, token_details.price_1d)
-                ),
-                format_long_number(token_details.market_cap),
-                format_long_number(token_details.current_supply),
-                format_long_number(token_details.usd_value_volume_24h.unwrap_or(0.0)),
-                if token_details.verified {
-                    "🟢"
-                } else {
-                    "🔴"
-                },
-                token_details.mint_address,
-                link(
-                    vybe_token_url(&token_details.mint_address).as_str(),
-                    "Open with Vybe"
-                )
-            ))
-            .parse_mode(ParseMode::Markdown)
+// Answer a `$SYMBOL` message with the best registry match and buttons for the others
+async fn display_cashtag(
+    bot: &Bot,
+    chat_id: ChatId,
+    lang: Lang,
+    symbol: &str,
+) -> Result<(), teloxide::RequestError> {
+    let tokens = registry::resolve(symbol);
+    let Some((top, alternatives)) = tokens.split_first() else {
+        bot.send_message(chat_id, t!(lang, "cashtag-not-found", symbol = symbol))
             .await?;
+        return Ok(());
+    };
+
+    match cache::token_details(&top.mint_address).await {
+        Ok(token_details) => {
+            let alternatives = &alternatives[..alternatives.len().min(MAX_ALTERNATIVES)];
+            let markup = (!alternatives.is_empty()).then(|| alternatives_markup(alternatives));
+            display_token_details(bot, chat_id, lang, &token_details, markup).await?;
+        }
+        Err(err) => {
+            tracing::warn!(
+                "Failed to get token details of {}: {:?}",
+                top.mint_address,
+                err
+            );
+            bot.send_message(chat_id, t!(lang, "cashtag-failed", symbol = symbol))
+                .await?;
+        }
     }
 
     Ok(())
 }
 
+/// Show the card of a token picked from the cashtag alternatives
+pub async fn show_token(bot: Bot, query: CallbackQuery) -> HandlerResult {
+    bot.answer_callback_query(query.id.clone()).await?;
+
+    let mint_address = query
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(TOKEN_CARD_PREFIX));
+    let (Some(mint_address), Some(message)) = (mint_address, &query.message) else {
+        return Ok(());
+    };
+
+    let lang = user_lang(Some(&query.from)).await;
+    send_token_card(&bot, message.chat().id, lang, mint_address).await?;
+    Ok(())
+}
+
+/// Send the full card of a token, returns false when its details can't be loaded
+pub(crate) async fn send_token_card(
+    bot: &Bot,
+    chat_id: ChatId,
+    lang: Lang,
+    mint_address: &str,
+) -> Result<bool, teloxide::RequestError> {
+    match cache::token_details(mint_address).await {
+        Ok(token_details) => {
+            display_token_details(bot, chat_id, lang, &token_details, None).await?;
+            Ok(true)
+        }
+        Err(err) => {
+            tracing::warn!("Failed to get token details of {}: {:?}", mint_address, err);
+            Ok(false)
+        }
+    }
+}
+
+// With little Vybe quota left groups only get cards that are already cached
+async fn lookup_token_details(
+    group: bool,
+    mint_address: &str,
+) -> Result<VybeTokenDetails, HttpError> {
+    if group && vybe_quota_low() {
+        cache::cached_token_details(mint_address).ok_or(HttpError::RateLimited {
+            retry_after: Duration::ZERO,
+        })
+    } else {
+        cache::token_details(mint_address).await
+    }
+}
+
+// Answer a message with several addresses with one compact line per token found
+async fn display_token_list(
+    bot: &Bot,
+    msg: &Message,
+    group: bool,
+    mint_addresses: &[String],
+) -> Result<(), teloxide::RequestError> {
+    let lang = user_lang(msg.from.as_ref()).await;
+    let lookups = mint_addresses
+        .iter()
+        .map(|mint_address| lookup_token_details(group, mint_address));
+    let cards = join_all(lookups)
+        .await
+        .into_iter()
+        .zip(mint_addresses)
+        .filter_map(|(token_details, mint_address)| match token_details {
+            Ok(token_details) => Some(token_card(lang, &token_details).compact),
+            Err(err) => {
+                tracing::warn!("Failed to get token details of {}: {:?}", mint_address, err);
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if cards.is_empty() {
+        if !group {
+            bot.send_message(msg.chat.id, t!(lang, "lookup-none-found"))
+                .await?;
+        }
+        return Ok(());
+    }
+
+    bot.send_message(msg.chat.id, cards.join("\n\n"))
+        .parse_mode(CARD_PARSE_MODE)
+        .link_preview_options(NO_LINK_PREVIEW)
+        .await?;
+    Ok(())
+}
+
+// Count a lookup against the user and chat limits, telling them to slow down once per window
+pub(crate) async fn allow_lookup(bot: &Bot, msg: &Message) -> Result<bool, teloxide::RequestError> {
+    let verdict = check_lookup(msg.from.as_ref(), &msg.chat);
+    let lang = user_lang(msg.from.as_ref()).await;
+    let text = match verdict {
+        Verdict::Allowed => return Ok(true),
+        Verdict::UserLimited { warn: true } => t!(lang, "rate-limited-user"),
+        Verdict::ChatLimited { warn: true } => t!(lang, "rate-limited-chat"),
+        Verdict::UserLimited { warn: false } | Verdict::ChatLimited { warn: false } => {
+            return Ok(false)
+        }
+    };
+
+    bot.send_message(msg.chat.id, text).await?;
+    Ok(false)
+}
+
 pub async fn handle_message(bot: Bot, msg: Message) -> Result<(), teloxide::RequestError> {
     // Extract message text, returning early if none
     let text = msg.text().unwrap_or_default().to_string();
 
-    // Check if the message is a valid Solana mint address
-    if is_valid_solana_mint_address(&text) {
-        // Use the global client instead of creating a new one
-        let vybe_token_api = &VYBE_TOKEN_API;
+    // Commands, known or not, are never token lookups even when they name a mint
+    let is_command = msg.entities().is_some_and(|entities| {
+        entities
+            .iter()
+            .any(|entity| entity.kind == MessageEntityKind::BotCommand)
+    });
+    if is_command {
+        return Ok(());
+    }
+
+    // Groups opt out of token cards, or mute them for some hours, from /settings
+    let group = is_group_chat(&msg.chat);
+    if group && !auto_cards_enabled(&msg.chat).await {
+        return Ok(());
+    }
+
+    // Addresses come pasted alone, inside conversation or as links to explorers and DEXes
+    let mint_addresses = extract_addresses(&text);
+    let cashtag = parse_cashtag(&text);
+    if mint_addresses.is_empty() && cashtag.is_none() {
+        return Ok(());
+    }
+    if !allow_lookup(&bot, &msg).await? {
+        return Ok(());
+    }
+    stats::record_lookup();
+
+    if mint_addresses.len() > 1 {
+        return display_token_list(&bot, &msg, group, &mint_addresses).await;
+    }
+
+    if let Some(mint_address) = mint_addresses.first().map(String::as_str) {
+        let token_details = lookup_token_details(group, mint_address).await;
 
-        // Get token details, returning early if it fails
-        let token_details = match vybe_token_api.get_token_details(text).await {
+        // Get token details through the shared cache, returning early if it fails
+        let token_details = match token_details {
             Ok(details) => details,
+            Err(HttpError::RateLimited { retry_after }) => {
+                tracing::warn!("Skipped lookup of {}, Vybe quota low", mint_address);
+                if !group {
+                    let lang = user_lang(msg.from.as_ref()).await;
+                    let seconds = retry_after.as_secs().max(1);
+                    bot.send_message(msg.chat.id, t!(lang, "lookup-busy", seconds = seconds))
+                        .await?;
+                }
+                return Ok(());
+            }
             Err(err) => {
                 eprintln!("Failed to get token details: {:?}", err);
 
-                // Extract and display error message to the user
-                if let HttpError::ApiError { status: _, body } = &err {
+                // Not a mint, pasted wallets get their portfolio in private chats
+                if !group
+                    && send_portfolio(
+                        &bot,
+                        msg.chat.id,
+                        user_lang(msg.from.as_ref()).await,
+                        mint_address,
+                    )
+                    .await?
+                {
+                    return Ok(());
+                }
+
+                // Extract and display error message to the user, groups only get cards
+                if let (false, HttpError::ApiError { status: _, body }) = (group, &err) {
                     // Try to parse the JSON error message
                     if let Ok(error_json) = serde_json::from_str::<serde_json::Value>(body) {
                         if let Some(error_message) =
                             error_json.get("message").and_then(|m| m.as_str())
                         {
-                            let error_text = format!("Error: {}", error_message);
+                            let lang = user_lang(msg.from.as_ref()).await;
+                            let error_text = t!(lang, "error-message", message = error_message);
                             if let Err(send_err) = bot.send_message(msg.chat.id, error_text).await {
                                 eprintln!("Failed to send error message: {:?}", send_err);
                             }
@@ -129,7 +311,17 @@ pub async fn handle_message(bot: Bot, msg: Message) -> Result<(), teloxide::Requ
         println!("Token details {:?}", token_details);
 
         // Display token details
-        display_token_details(&bot, &msg, &token_details).await?;
+        let lang = user_lang(msg.from.as_ref()).await;
+        if group && chat_settings(msg.chat.id).await.compact_cards {
+            bot.send_message(msg.chat.id, token_card(lang, &token_details).compact)
+                .parse_mode(CARD_PARSE_MODE)
+                .await?;
+        } else {
+            display_token_details(&bot, msg.chat.id, lang, &token_details, None).await?;
+        }
+    } else if let Some(symbol) = cashtag.filter(|_| !(group && vybe_quota_low())) {
+        let lang = user_lang(msg.from.as_ref()).await;
+        display_cashtag(&bot, msg.chat.id, lang, symbol).await?;
     }
     Ok(())
 }
@@ -1,3 +1,16 @@
+pub mod admin;
+pub mod alert;
+pub mod card_actions;
+pub mod chart;
+pub mod digest;
+pub mod follow;
+pub mod holders;
+pub mod language;
 pub mod message;
+pub mod referrals;
+pub mod settings;
 pub mod start;
 pub mod test;
+pub mod track;
+pub mod wallet;
+pub mod watchlist;
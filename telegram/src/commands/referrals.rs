@@ -0,0 +1,191 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash, Hasher},
+};
+
+use entity::{referral::Entity as Referral, referral_code::Entity as ReferralCode};
+use sea_orm::{DbErr, SqlErr};
+use teloxide::{
+    prelude::*,
+    types::{Me, User},
+};
+
+use super::message::is_valid_solana_mint_address;
+use crate::{
+    i18n::{stored_user_lang, t, user_lang},
+    HandlerResult,
+};
+
+const REFERRAL_PAYLOAD_PREFIX: &str = "ref_";
+const TOKEN_PAYLOAD_PREFIX: &str = "token_";
+
+const CODE_LENGTH: usize = 8;
+const CODE_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+// Codes are random, a clash with another user's code only costs a retry
+const MAX_CODE_ATTEMPTS: usize = 5;
+const RECENT_DAYS: i64 = 7;
+
+/// What a `t.me/<bot>?start=<payload>` link asks `/start` to do
+#[derive(Clone, Debug, PartialEq)]
+pub enum StartPayload {
+    /// Invited with the referral code of another user
+    Referral(String),
+    /// Open the card of a token
+    Token(String),
+}
+
+/// Parse the argument of `/start`, `ref_<code>` or `token_<mint>`
+pub fn parse_start_payload(args: &str) -> Option<StartPayload> {
+    let args = args.trim();
+    if let Some(code) = args.strip_prefix(REFERRAL_PAYLOAD_PREFIX) {
+        let valid = !code.is_empty()
+            && code.len() <= CODE_LENGTH
+            && code.bytes().all(|byte| CODE_ALPHABET.contains(&byte));
+        return valid.then(|| StartPayload::Referral(code.to_string()));
+    }
+
+    args.strip_prefix(TOKEN_PAYLOAD_PREFIX)
+        .filter(|mint_address| is_valid_solana_mint_address(mint_address))
+        .map(|mint_address| StartPayload::Token(mint_address.to_string()))
+}
+
+fn generate_code(telegram_id: i64) -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    telegram_id.hash(&mut hasher);
+    chrono::Utc::now().timestamp_nanos_opt().hash(&mut hasher);
+    let mut seed = hasher.finish();
+
+    (0..CODE_LENGTH)
+        .map(|_| {
+            let index = (seed % CODE_ALPHABET.len() as u64) as usize;
+            seed /= CODE_ALPHABET.len() as u64;
+            CODE_ALPHABET[index] as char
+        })
+        .collect()
+}
+
+/// The referral code of a user, created on first use
+pub async fn referral_code(telegram_id: i64) -> Result<String, DbErr> {
+    let db = entity::get_db().await;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match ReferralCode::get_or_create(db, telegram_id, &generate_code(telegram_id)).await {
+            Ok(code) => return Ok(code.code),
+            Err(err)
+                if attempts < MAX_CODE_ATTEMPTS
+                    && matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+pub fn referral_link(bot_username: &str, code: &str) -> String {
+    format!(
+        "https://t.me/{}?start={}{}",
+        bot_username, REFERRAL_PAYLOAD_PREFIX, code
+    )
+}
+
+/// Credit the owner of `code` with a user who just started the bot, telling them about it.
+///
+/// Users can't refer themselves and are only ever credited to their first referrer.
+pub async fn record_referral(bot: &Bot, referred: &User, code: &str) -> Result<(), DbErr> {
+    let db = entity::get_db().await;
+    let referred_id = referred.id.0 as i64;
+    let Some(referrer) = ReferralCode::find_by_code(db, code).await? else {
+        return Ok(());
+    };
+    if referrer.telegram_id == referred_id
+        || !Referral::record(db, referrer.telegram_id, referred_id).await?
+    {
+        return Ok(());
+    }
+
+    let count = Referral::count_by_referrer(db, referrer.telegram_id, None).await?;
+    let lang = stored_user_lang(referrer.telegram_id).await;
+    let text = t!(
+        lang,
+        "referral-joined",
+        name = referred.first_name.as_str(),
+        count = count
+    );
+    // The referrer may have blocked the bot, the referral still counts
+    if let Err(err) = bot.send_message(ChatId(referrer.telegram_id), text).await {
+        tracing::warn!(
+            "Failed to notify referrer {}: {:?}",
+            referrer.telegram_id,
+            err
+        );
+    }
+    Ok(())
+}
+
+pub async fn referrals(bot: Bot, message: Message, me: Me) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let db = entity::get_db().await;
+    let telegram_id = from.id.0 as i64;
+    let code = referral_code(telegram_id).await?;
+    let since = chrono::Utc::now() - chrono::Duration::days(RECENT_DAYS);
+    let total = Referral::count_by_referrer(db, telegram_id, None).await?;
+    let recent = Referral::count_by_referrer(db, telegram_id, Some(since)).await?;
+
+    let lang = user_lang(Some(from)).await;
+    bot.send_message(
+        message.chat.id,
+        t!(
+            lang,
+            "referrals-text",
+            link = referral_link(me.username(), &code),
+            total = total,
+            recent = recent,
+            days = RECENT_DAYS
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    #[test]
+    fn test_parse_start_payload() {
+        assert_eq!(
+            parse_start_payload("ref_ab12cd34"),
+            Some(StartPayload::Referral("ab12cd34".to_string()))
+        );
+        assert_eq!(
+            parse_start_payload(&format!("token_{}", BONK)),
+            Some(StartPayload::Token(BONK.to_string()))
+        );
+        assert_eq!(parse_start_payload(""), None);
+        assert_eq!(parse_start_payload("ref_"), None);
+        assert_eq!(parse_start_payload("ref_AB12CD34"), None);
+        assert_eq!(parse_start_payload("ref_ab12cd34ef"), None);
+        assert_eq!(parse_start_payload("token_bonk"), None);
+        assert_eq!(parse_start_payload(BONK), None);
+    }
+
+    #[test]
+    fn test_generated_codes_parse() {
+        for telegram_id in 0..20 {
+            let code = generate_code(telegram_id);
+            assert_eq!(code.len(), CODE_LENGTH);
+            assert_eq!(
+                parse_start_payload(&format!("ref_{}", code)),
+                Some(StartPayload::Referral(code))
+            );
+        }
+        assert_eq!(
+            referral_link("pixa_bot", "ab12cd34"),
+            "https://t.me/pixa_bot?start=ref_ab12cd34"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use entity::chat_setting;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+};
+
+use crate::{
+    chat_settings::{chat_settings, is_group_chat, save_chat_settings, GROUP_COMMANDS},
+    i18n::{t, user_lang, Lang},
+    HandlerResult,
+};
+
+pub const SETTINGS_PREFIX: &str = "gs:";
+
+// Mute windows cycled through by the mute button, in UTC hours
+const MUTE_PRESETS: [Option<(i16, i16)>; 4] = [None, Some((22, 6)), Some((0, 8)), Some((20, 8))];
+
+#[derive(Debug, PartialEq)]
+enum SettingsAction<'a> {
+    AutoCards,
+    CompactCards,
+    Mute,
+    Command(&'a str),
+    Close,
+}
+
+fn parse_action(data: &str) -> Option<SettingsAction<'_>> {
+    match data.strip_prefix(SETTINGS_PREFIX)? {
+        "auto" => Some(SettingsAction::AutoCards),
+        "compact" => Some(SettingsAction::CompactCards),
+        "mute" => Some(SettingsAction::Mute),
+        "close" => Some(SettingsAction::Close),
+        data => data
+            .strip_prefix("cmd:")
+            .filter(|command| GROUP_COMMANDS.contains(command))
+            .map(SettingsAction::Command),
+    }
+}
+
+fn next_mute_preset(settings: &mut chat_setting::Model) {
+    let current = settings.mute_start_hour.zip(settings.mute_end_hour);
+    let index = MUTE_PRESETS
+        .iter()
+        .position(|preset| *preset == current)
+        .map_or(0, |index| (index + 1) % MUTE_PRESETS.len());
+
+    (settings.mute_start_hour, settings.mute_end_hour) = match MUTE_PRESETS[index] {
+        Some((start, end)) => (Some(start), Some(end)),
+        None => (None, None),
+    };
+}
+
+fn on_off(lang: Lang, enabled: bool) -> String {
+    if enabled {
+        t!(lang, "settings-on")
+    } else {
+        t!(lang, "settings-off")
+    }
+}
+
+fn settings_markup(lang: Lang, settings: &chat_setting::Model) -> InlineKeyboardMarkup {
+    let button = |text: String, action: &str| {
+        InlineKeyboardButton::callback(text, format!("{}{}", SETTINGS_PREFIX, action))
+    };
+
+    let mute = match settings.mute_start_hour.zip(settings.mute_end_hour) {
+        Some((start, end)) => format!("{:02}:00-{:02}:00 UTC", start, end),
+        None => t!(lang, "settings-off"),
+    };
+    let style = if settings.compact_cards {
+        t!(lang, "settings-style-compact")
+    } else {
+        t!(lang, "settings-style-full")
+    };
+
+    let mut rows = vec![
+        vec![button(
+            t!(
+                lang,
+                "settings-token-cards",
+                state = on_off(lang, settings.auto_cards)
+            ),
+            "auto",
+        )],
+        vec![button(
+            t!(lang, "settings-card-style", style = style),
+            "compact",
+        )],
+        vec![button(t!(lang, "settings-mute", window = mute), "mute")],
+    ];
+
+    rows.extend(GROUP_COMMANDS.chunks(2).map(|commands| {
+        commands
+            .iter()
+            .map(|command| {
+                let enabled = !settings.is_command_disabled(command);
+                button(
+                    format!("{} /{}", if enabled { "✅" } else { "🚫" }, command),
+                    &format!("cmd:{}", command),
+                )
+            })
+            .collect()
+    }));
+    rows.push(vec![button(t!(lang, "settings-done"), "close")]);
+
+    InlineKeyboardMarkup::new(rows)
+}
+
+pub(crate) async fn is_chat_admin(
+    bot: &Bot,
+    chat_id: ChatId,
+    user_id: UserId,
+) -> anyhow::Result<bool> {
+    Ok(bot.get_chat_member(chat_id, user_id).await?.is_privileged())
+}
+
+/// Swallows commands the group disabled, answering them would defeat the setting
+pub async fn ignore_disabled_command() -> HandlerResult {
+    Ok(())
+}
+
+pub async fn settings(bot: Bot, message: Message) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let lang = user_lang(Some(from)).await;
+    if !is_group_chat(&message.chat) {
+        bot.send_message(message.chat.id, t!(lang, "settings-private"))
+            .await?;
+        return Ok(());
+    }
+
+    if !is_chat_admin(&bot, message.chat.id, from.id).await? {
+        bot.send_message(message.chat.id, t!(lang, "settings-admins-only"))
+            .await?;
+        return Ok(());
+    }
+
+    let settings = chat_settings(message.chat.id).await;
+    bot.send_message(message.chat.id, t!(lang, "settings-title"))
+        .reply_markup(settings_markup(lang, &settings))
+        .await?;
+    Ok(())
+}
+
+pub async fn settings_callback(bot: Bot, query: CallbackQuery) -> HandlerResult {
+    let (Some(action), Some(message)) = (
+        query.data.as_deref().and_then(parse_action),
+        query.message.as_ref(),
+    ) else {
+        bot.answer_callback_query(query.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let lang = user_lang(Some(&query.from)).await;
+
+    // Anyone in the group can press the buttons, only admins may change anything
+    if !is_chat_admin(&bot, chat_id, query.from.id).await? {
+        bot.answer_callback_query(query.id.clone())
+            .text(t!(lang, "settings-admins-only"))
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    bot.answer_callback_query(query.id.clone()).await?;
+
+    let mut settings = chat_settings(chat_id).await;
+    match action {
+        SettingsAction::AutoCards => settings.auto_cards = !settings.auto_cards,
+        SettingsAction::CompactCards => settings.compact_cards = !settings.compact_cards,
+        SettingsAction::Mute => next_mute_preset(&mut settings),
+        SettingsAction::Command(command) => settings.toggle_command(command),
+        SettingsAction::Close => {
+            bot.delete_message(chat_id, message.id()).await?;
+            return Ok(());
+        }
+    }
+
+    save_chat_settings(settings.clone()).await?;
+    bot.edit_message_reply_markup(chat_id, message.id())
+        .reply_markup(settings_markup(lang, &settings))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!(parse_action("gs:auto"), Some(SettingsAction::AutoCards));
+        assert_eq!(parse_action("gs:close"), Some(SettingsAction::Close));
+        assert_eq!(
+            parse_action("gs:cmd:watch"),
+            Some(SettingsAction::Command("watch"))
+        );
+        assert_eq!(parse_action("gs:cmd:settings"), None);
+        assert_eq!(parse_action("auto"), None);
+    }
+
+    #[test]
+    fn test_next_mute_preset_cycles() {
+        let mut settings = chat_setting::Model::new(1);
+        let mut seen = Vec::new();
+        for _ in 0..MUTE_PRESETS.len() {
+            next_mute_preset(&mut settings);
+            seen.push(settings.mute_start_hour.zip(settings.mute_end_hour));
+        }
+
+        assert_eq!(seen.first(), Some(&MUTE_PRESETS[1]));
+        assert_eq!(seen.last(), Some(&None));
+    }
+}
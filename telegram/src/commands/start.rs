@@ -1,19 +1,68 @@
-use entity::tg_user::ActiveModel;
-use sea_orm::ActiveValue::{NotSet, Set, Unchanged};
-use teloxide::{prelude::*, utils::command::BotCommands};
+use entity::tg_user::{ActiveModel, Entity as TgUser};
+use sea_orm::ActiveValue::Set;
+use teloxide::prelude::*;
 
-use crate::{GlobalCommand, HandlerResult};
+use super::{
+    message::{allow_lookup, send_token_card},
+    referrals::{parse_start_payload, record_referral, StartPayload},
+};
+use crate::{
+    i18n::{t, user_lang},
+    stats, GlobalCommand, HandlerResult,
+};
 
-pub async fn start(bot: Bot, message: Message) -> HandlerResult {
-    let db = entity::get_db();
-    let user = ActiveModel {
-        id: Set(message.from().unwrap().id.clone_from(source);),
-        username: Set(message.from().unwrap().username.clone()),
-        first_name: Set(message.from().unwrap().first_name.clone()),
-        ..Default::default()
-    };
+/// `/start`, with the payload of a `t.me/<bot>?start=<payload>` link when opened from one
+pub async fn start(bot: Bot, message: Message, args: String) -> HandlerResult {
+    let payload = parse_start_payload(&args);
+    let mut new_user = false;
+    if let Some(from) = message.from.as_ref() {
+        let db = entity::get_db().await;
+        new_user = TgUser::find_by_telegram_id(db, from.id.0 as i64)
+            .await?
+            .is_none();
+        let now = chrono::Utc::now();
+        let user = ActiveModel {
+            telegram_id: Set(from.id.0 as i64),
+            username: Set(from.username.clone()),
+            first_name: Set(from.first_name.clone()),
+            last_name: Set(from.last_name.clone()),
+            language_code: Set(from.language_code.clone()),
+            created_at: Set(now),
+            last_seen_at: Set(now),
+            ..Default::default()
+        };
 
-    bot.send_message(message.chat.id, GlobalCommand::descriptions().to_string())
+        TgUser::upsert(db, user).await?;
+
+        // Only users new to the bot count as referred
+        if let (true, Some(StartPayload::Referral(code))) = (new_user, &payload) {
+            record_referral(&bot, from, code).await?;
+        }
+    }
+
+    // Returning users opening a token link only want the card
+    let lang = user_lang(message.from.as_ref()).await;
+    if new_user || !matches!(payload, Some(StartPayload::Token(_))) {
+        bot.send_message(
+            message.chat.id,
+            format!(
+                "{}\n\n{}",
+                t!(lang, "start-welcome"),
+                GlobalCommand::help(lang)
+            ),
+        )
         .await?;
+    }
+
+    if let Some(StartPayload::Token(mint_address)) = payload {
+        if !allow_lookup(&bot, &message).await? {
+            return Ok(());
+        }
+        stats::record_lookup();
+        if !send_token_card(&bot, message.chat.id, lang, &mint_address).await? {
+            bot.send_message(message.chat.id, t!(lang, "token-not-found"))
+                .await?;
+        }
+    }
     Ok(())
 }
@@ -1,61 +1,26 @@
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use teloxide::{
-    dispatching::{
-        dialogue::{GetChatId, InMemStorage},
-        UpdateHandler,
-    },
+    dispatching::{dialogue::GetChatId, UpdateHandler},
     prelude::*,
-    types::{
-        InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup, ParseMode,
-        ReplyMarkup,
-    },
+    types::{KeyboardButton, KeyboardMarkup, ParseMode, ReplyMarkup},
     utils::markdown::bold,
 };
 
-use crate::{GlobalCommand, GlobalDialogue, GlobalState, HandlerResult};
+use crate::{storage::DialogueStorage, GlobalCommand, GlobalDialogue, GlobalState, HandlerResult};
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 
 pub enum State {
     #[default]
     Test,
 }
 
-async fn settings(bot: Bot, message: Message) -> HandlerResult {
-    bot.send_message(message.chat.id, "_Please select from an option below_")
+async fn settings(bot: Bot, message: Message, dialogue: GlobalDialogue) -> HandlerResult {
+    bot.send_message(message.chat.id, "_Send a new name, or cancel_")
         .parse_mode(ParseMode::MarkdownV2)
-        .reply_markup(create_main_markup())
         .await?;
-    Ok(())
-}
-
-async fn callback_handler(
-    bot: Bot,
-    callback: CallbackQuery,
-    dialogue: GlobalDialogue,
-    // client: DataClient,
-) -> anyhow::Result<()> {
-    match callback.data {
-        Some(data) => match data.as_str() {
-            "test" => {
-                if let Some(message) = callback.message {
-                    bot.delete_message(message.chat().id, message.id()).await?;
-                    println!("Test callback triggered, changing state to Test(State::Test)");
-                    let message = bot
-                        .send_message(message.chat().id, "Changing name")
-                        .parse_mode(ParseMode::MarkdownV2)
-                        .await?;
-
-                    dialogue.update(GlobalState::Test(State::Test)).await?;
-                    println!("State updated to Test(State::Test)");
-                }
-            }
-            _ => {}
-        },
-
-        None => {}
-    };
-
+    dialogue.update(GlobalState::Test(State::Test)).await?;
     Ok(())
 }
 
@@ -116,27 +81,17 @@ async fn change_name(
 }
 
 pub fn schema() -> UpdateHandler<anyhow::Error> {
-    dptree::entry()
-        .branch(
-            Update::filter_callback_query()
-                .enter_dialogue::<CallbackQuery, InMemStorage<GlobalState>, GlobalState>()
-                .branch(dptree::case![GlobalState::Idle].endpoint(callback_handler)),
-        )
-        .branch(
-            Update::filter_message()
-                .enter_dialogue::<Message, InMemStorage<GlobalState>, GlobalState>()
-                .branch(
-                    dptree::case![GlobalState::Idle]
-                        .filter_command::<GlobalCommand>()
-                        .branch(dptree::case![GlobalCommand::Test].endpoint(settings)),
-                )
-                .branch(
-                    dptree::case![GlobalState::Test(x)]
-                        .branch(dptree::case![State::Test].endpoint(change_name)),
-                ),
-        )
-}
-
-fn create_main_markup() -> InlineKeyboardMarkup {
-    InlineKeyboardMarkup::new([[InlineKeyboardButton::callback("Test", "test")]])
+    dptree::entry().branch(
+        Update::filter_message()
+            .enter_dialogue::<Message, DialogueStorage<GlobalState>, GlobalState>()
+            .branch(
+                dptree::case![GlobalState::Idle]
+                    .filter_command::<GlobalCommand>()
+                    .branch(dptree::case![GlobalCommand::Test].endpoint(settings)),
+            )
+            .branch(
+                dptree::case![GlobalState::Test(x)]
+                    .branch(dptree::case![State::Test].endpoint(change_name)),
+            ),
+    )
 }
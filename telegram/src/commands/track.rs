@@ -0,0 +1,195 @@
+use entity::tracked_wallet::{Entity as TrackedWallet, Model};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+};
+
+use super::{holders::short_address, message::is_valid_solana_mint_address};
+use crate::{
+    i18n::{t, user_lang, Lang},
+    wallet_tracker::tracked_changed,
+    HandlerResult,
+};
+
+pub const UNTRACK_PREFIX: &str = "track_del:";
+const MAX_TRACKED_WALLETS_PER_USER: usize = 10;
+
+// Labels go into every notification, keep them short
+const MAX_LABEL_CHARS: usize = 32;
+
+/// Parse the arguments of `/track`: `<wallet> [label]`
+pub fn parse_track(args: &str) -> Option<(String, Option<String>)> {
+    let args = args.trim();
+    let (wallet_address, label) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+    if !is_valid_solana_mint_address(wallet_address) {
+        return None;
+    }
+
+    let label = label.split_whitespace().collect::<Vec<_>>().join(" ");
+    let label = (!label.is_empty()).then(|| label.chars().take(MAX_LABEL_CHARS).collect());
+    Some((wallet_address.to_string(), label))
+}
+
+fn wallet_name(wallet: &Model) -> String {
+    wallet
+        .label
+        .clone()
+        .unwrap_or_else(|| short_address(&wallet.wallet_address))
+}
+
+pub async fn track(bot: Bot, message: Message, args: String) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let lang = user_lang(Some(from)).await;
+    let Some((wallet_address, label)) = parse_track(&args) else {
+        bot.send_message(message.chat.id, t!(lang, "track-usage"))
+            .await?;
+        return Ok(());
+    };
+
+    let db = entity::get_db().await;
+    let telegram_id = from.id.0 as i64;
+    let tracked = TrackedWallet::find_by_user(db, telegram_id).await?;
+    if tracked.len() >= MAX_TRACKED_WALLETS_PER_USER
+        && !tracked
+            .iter()
+            .any(|wallet| wallet.wallet_address == wallet_address)
+    {
+        bot.send_message(
+            message.chat.id,
+            t!(lang, "track-limit", count = MAX_TRACKED_WALLETS_PER_USER),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let wallet =
+        TrackedWallet::track(db, telegram_id, message.chat.id.0, &wallet_address, label).await?;
+    tracked_changed();
+
+    bot.send_message(
+        message.chat.id,
+        t!(lang, "track-set", wallet = wallet_name(&wallet)),
+    )
+    .await?;
+    Ok(())
+}
+
+// Buttons carry the owner so presses by others in a group can be turned away
+fn untrack_data(wallet: &Model) -> String {
+    format!("{}{}:{}", UNTRACK_PREFIX, wallet.telegram_id, wallet.id)
+}
+
+/// The owner and wallet id behind a remove button
+fn parse_untrack(data: &str) -> Option<(i64, i32)> {
+    let (owner_id, wallet_id) = data.strip_prefix(UNTRACK_PREFIX)?.split_once(':')?;
+    Some((owner_id.parse().ok()?, wallet_id.parse().ok()?))
+}
+
+fn tracked_markup(wallets: &[Model]) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(wallets.iter().map(|wallet| {
+        [InlineKeyboardButton::callback(
+            format!("❌ {}", wallet_name(wallet)),
+            untrack_data(wallet),
+        )]
+    }))
+}
+
+fn tracked_text(lang: Lang, wallets: &[Model]) -> String {
+    if wallets.is_empty() {
+        t!(lang, "tracked-empty")
+    } else {
+        t!(lang, "tracked-list")
+    }
+}
+
+pub async fn tracked(bot: Bot, message: Message) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let db = entity::get_db().await;
+    let wallets = TrackedWallet::find_by_user(db, from.id.0 as i64).await?;
+    let lang = user_lang(Some(from)).await;
+    bot.send_message(message.chat.id, tracked_text(lang, &wallets))
+        .reply_markup(tracked_markup(&wallets))
+        .await?;
+    Ok(())
+}
+
+pub async fn untrack(bot: Bot, query: CallbackQuery) -> HandlerResult {
+    let Some((owner_id, wallet_id)) = query.data.as_deref().and_then(parse_untrack) else {
+        bot.answer_callback_query(query.id.clone()).await?;
+        return Ok(());
+    };
+
+    let lang = user_lang(Some(&query.from)).await;
+    let telegram_id = query.from.id.0 as i64;
+    // Only the owner can remove a wallet, buttons can be pressed by anyone in a group
+    if owner_id != telegram_id {
+        bot.answer_callback_query(query.id.clone())
+            .text(t!(lang, "tracked-not-yours"))
+            .show_alert(true)
+            .await?;
+        return Ok(());
+    }
+
+    let db = entity::get_db().await;
+    if TrackedWallet::untrack(db, telegram_id, wallet_id).await? {
+        tracked_changed();
+    }
+
+    bot.answer_callback_query(query.id.clone()).await?;
+    if let Some(message) = &query.message {
+        let wallets = TrackedWallet::find_by_user(db, telegram_id).await?;
+        bot.edit_message_text(
+            message.chat().id,
+            message.id(),
+            tracked_text(lang, &wallets),
+        )
+        .reply_markup(tracked_markup(&wallets))
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    #[test]
+    fn test_parse_track() {
+        assert_eq!(parse_track(WALLET), Some((WALLET.to_string(), None)));
+        assert_eq!(
+            parse_track(&format!(" {}   big   whale ", WALLET)),
+            Some((WALLET.to_string(), Some("big whale".to_string())))
+        );
+        assert_eq!(
+            parse_track(&format!("{} {}", WALLET, "x".repeat(40)))
+                .and_then(|(_, label)| label)
+                .map(|label| label.len()),
+            Some(MAX_LABEL_CHARS)
+        );
+        assert_eq!(parse_track(""), None);
+        assert_eq!(parse_track("whale 9WzD"), None);
+    }
+
+    #[test]
+    fn test_untrack_data() {
+        let wallet = Model {
+            id: 7,
+            telegram_id: 42,
+            chat_id: -100,
+            wallet_address: WALLET.to_string(),
+            label: None,
+            created_at: chrono::Utc::now(),
+        };
+        assert_eq!(parse_untrack(&untrack_data(&wallet)), Some((42, 7)));
+        assert_eq!(parse_untrack("track_del:7"), None);
+        assert_eq!(parse_untrack("alert_del:42:7"), None);
+    }
+}
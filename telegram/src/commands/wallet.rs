@@ -0,0 +1,291 @@
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
+    utils::html,
+    RequestError,
+};
+use utils::{
+    endpoints::vybe::types::VybeWalletTokenBalances,
+    number::{format_compact_price, format_long_number},
+    ENV_CONFIG,
+};
+
+use super::message::is_valid_solana_mint_address;
+use crate::{
+    cache,
+    i18n::{t, user_lang, Lang},
+    HandlerResult,
+};
+
+pub const WALLET_PAGE_PREFIX: &str = "wp:";
+
+// Token rows shown per page of a portfolio
+const PAGE_SIZE: usize = 10;
+
+// Amounts and values under a thousand keep their decimals, bigger ones get a suffix
+fn compact_amount(amount: f64) -> String {
+    if amount.abs() >= 1000.0 {
+        format_long_number(amount)
+    } else {
+        format_compact_price(amount)
+    }
+}
+
+// Percent change of a value that moved by `change` to reach `value`
+fn percent_change(value: f64, change: f64) -> Option<f64> {
+    let previous = value - change;
+    (previous > 0.0).then(|| change / previous * 100.0)
+}
+
+fn page_markup(
+    lang: Lang,
+    owner_address: &str,
+    page: usize,
+    pages: usize,
+) -> Option<InlineKeyboardMarkup> {
+    if pages <= 1 {
+        return None;
+    }
+
+    let button = |text: &str, page: usize| {
+        InlineKeyboardButton::callback(
+            text,
+            format!("{}{}:{}", WALLET_PAGE_PREFIX, page, owner_address),
+        )
+    };
+    let mut row = Vec::new();
+    if page > 0 {
+        row.push(button(&t!(lang, "wallet-prev"), page - 1));
+    }
+    row.push(button(&format!("{}/{}", page + 1, pages), page));
+    if page + 1 < pages {
+        row.push(button(&t!(lang, "wallet-next"), page + 1));
+    }
+    Some(InlineKeyboardMarkup::new([row]))
+}
+
+/// One page of a portfolio as HTML, with buttons to the other pages
+fn render_portfolio(
+    lang: Lang,
+    balances: &VybeWalletTokenBalances,
+    dust_usd: f64,
+    page: usize,
+) -> (String, Option<InlineKeyboardMarkup>) {
+    let (tokens, dust): (Vec<_>, Vec<_>) = balances
+        .data
+        .iter()
+        .partition(|balance| balance.value_usd >= dust_usd);
+    let pages = tokens.len().div_ceil(PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
+
+    let change = match percent_change(
+        balances.total_token_value_usd,
+        balances.total_token_value_usd_1d_change,
+    ) {
+        Some(percent) => lang.number(&format!("{:+.2}% 24h", percent)),
+        None => "n/a 24h".to_string(),
+    };
+    let mut text = format!(
+        "<b>{}</b> <code>{}</code>\n{} <b>${}</b> ({})\n",
+        t!(lang, "wallet-title"),
+        html::escape(&balances.owner_address),
+        t!(lang, "wallet-value"),
+        lang.number(&compact_amount(balances.total_token_value_usd)),
+        change
+    );
+
+    if tokens.is_empty() {
+        text.push('\n');
+        text.push_str(&t!(
+            lang,
+            "wallet-no-balances",
+            amount = lang.number(&compact_amount(dust_usd))
+        ));
+    } else {
+        let rows = tokens
+            .iter()
+            .skip(page * PAGE_SIZE)
+            .take(PAGE_SIZE)
+            .map(|balance| {
+                let change = percent_change(balance.value_usd, balance.value_usd_1d_change)
+                    .map_or("n/a".to_string(), |percent| {
+                        lang.number(&format!("{:+.1}%", percent))
+                    });
+                format!(
+                    "{:<8} {:>8} {:>8} {:>7}",
+                    balance.symbol.chars().take(8).collect::<String>(),
+                    lang.number(&compact_amount(balance.amount)),
+                    lang.number(&compact_amount(balance.value_usd)),
+                    change
+                )
+            })
+            .collect::<Vec<_>>();
+        let table = format!(
+            "{:<8} {:>8} {:>8} {:>7}\n{}",
+            t!(lang, "table-token"),
+            t!(lang, "table-amount"),
+            t!(lang, "table-value"),
+            t!(lang, "table-change-1d"),
+            rows.join("\n")
+        );
+        text.push_str(&format!("<pre>{}</pre>", html::escape(&table)));
+    }
+
+    if !dust.is_empty() {
+        let dust_value = dust.iter().map(|balance| balance.value_usd).sum::<f64>();
+        text.push('\n');
+        text.push_str(&t!(
+            lang,
+            "wallet-dust",
+            count = dust.len(),
+            amount = lang.number(&compact_amount(dust_usd)),
+            total = lang.number(&compact_amount(dust_value))
+        ));
+    }
+
+    (
+        text,
+        page_markup(lang, &balances.owner_address, page, pages),
+    )
+}
+
+/// Send the first page of a wallet's portfolio, `false` when the wallet holds no tokens at all
+pub async fn send_portfolio(
+    bot: &Bot,
+    chat_id: ChatId,
+    lang: Lang,
+    owner_address: &str,
+) -> Result<bool, RequestError> {
+    let balances = match cache::wallet_balances(owner_address).await {
+        Ok(balances) if !balances.data.is_empty() => balances,
+        Ok(_) => return Ok(false),
+        Err(err) => {
+            tracing::warn!("Failed to get balances of {}: {:?}", owner_address, err);
+            return Ok(false);
+        }
+    };
+
+    let (text, markup) = render_portfolio(lang, &balances, ENV_CONFIG.wallet_dust_usd, 0);
+    let request = bot.send_message(chat_id, text).parse_mode(ParseMode::Html);
+    match markup {
+        Some(markup) => request.reply_markup(markup).await?,
+        None => request.await?,
+    };
+    Ok(true)
+}
+
+pub async fn wallet(bot: Bot, message: Message, owner_address: String) -> HandlerResult {
+    let lang = user_lang(message.from.as_ref()).await;
+    let owner_address = owner_address.trim();
+    if !is_valid_solana_mint_address(owner_address) {
+        bot.send_message(message.chat.id, t!(lang, "wallet-usage"))
+            .await?;
+        return Ok(());
+    }
+
+    if !send_portfolio(&bot, message.chat.id, lang, owner_address).await? {
+        bot.send_message(message.chat.id, t!(lang, "wallet-not-found"))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Turn the page of a portfolio message
+pub async fn wallet_page(bot: Bot, query: CallbackQuery) -> HandlerResult {
+    bot.answer_callback_query(query.id.clone()).await?;
+
+    let page = query
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(WALLET_PAGE_PREFIX))
+        .and_then(|data| data.split_once(':'))
+        .and_then(|(page, owner_address)| Some((page.parse::<usize>().ok()?, owner_address)));
+    let (Some((page, owner_address)), Some(message)) = (page, &query.message) else {
+        return Ok(());
+    };
+
+    let balances = match cache::wallet_balances(owner_address).await {
+        Ok(balances) => balances,
+        Err(err) => {
+            tracing::warn!("Failed to get balances of {}: {:?}", owner_address, err);
+            return Ok(());
+        }
+    };
+
+    let lang = user_lang(Some(&query.from)).await;
+    let (text, markup) = render_portfolio(lang, &balances, ENV_CONFIG.wallet_dust_usd, page);
+    let request = bot
+        .edit_message_text(message.chat().id, message.id(), text)
+        .parse_mode(ParseMode::Html);
+    let result = match markup {
+        Some(markup) => request.reply_markup(markup).await,
+        None => request.await,
+    };
+    match result {
+        Ok(_) | Err(RequestError::Api(teloxide::ApiError::MessageNotModified)) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::endpoints::vybe::types::VybeWalletTokenBalance;
+
+    fn balance(symbol: &str, value_usd: f64, value_usd_1d_change: f64) -> VybeWalletTokenBalance {
+        VybeWalletTokenBalance {
+            symbol: symbol.to_string(),
+            name: None,
+            mint_address: format!("{}-mint", symbol),
+            amount: value_usd * 2.0,
+            price_usd: 0.5,
+            value_usd,
+            value_usd_1d_change,
+            logo_url: None,
+            verified: true,
+        }
+    }
+
+    fn portfolio(count: usize) -> VybeWalletTokenBalances {
+        let mut data = (0..count)
+            .map(|i| balance(&format!("T{}", i), 1000.0 - i as f64, 10.0))
+            .collect::<Vec<_>>();
+        data.push(balance("DUST", 0.25, 0.0));
+        VybeWalletTokenBalances {
+            owner_address: "owner".to_string(),
+            total_token_value_usd: 1100.0,
+            total_token_value_usd_1d_change: 100.0,
+            total_token_count: data.len() as u32,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_render_portfolio_hides_dust() {
+        let (text, markup) = render_portfolio(Lang::En, &portfolio(3), 1.0, 0);
+
+        assert!(text.contains("Value: <b>$1.1K</b> (+10.00% 24h)"));
+        assert!(text.contains("T0 "));
+        assert!(!text.contains("DUST"));
+        assert!(text.contains("1 tokens under $1.00 hidden ($0.250 total)"));
+        assert!(markup.is_none());
+    }
+
+    #[test]
+    fn test_render_portfolio_pages() {
+        let balances = portfolio(25);
+
+        let (first, markup) = render_portfolio(Lang::En, &balances, 1.0, 0);
+        assert!(first.contains("T9 ") && !first.contains("T10 "));
+        let row = &markup.unwrap().inline_keyboard[0];
+        assert_eq!(row.len(), 2);
+        assert_eq!(row[1].text, "Next ▶️");
+
+        // Pages past the end clamp to the last one
+        let (last, markup) = render_portfolio(Lang::En, &balances, 1.0, 7);
+        assert!(last.contains("T24 ") && !last.contains("T19 "));
+        let row = &markup.unwrap().inline_keyboard[0];
+        assert_eq!(row[0].text, "◀️ Prev");
+        assert_eq!(row[1].text, "3/3");
+    }
+}
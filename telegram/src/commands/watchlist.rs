@@ -0,0 +1,221 @@
+use std::collections::BTreeSet;
+
+use entity::{
+    trade_follow::Entity as TradeFollow,
+    watchlist_entry::{self, ActiveModel, Column, Entity as WatchlistEntry},
+};
+use futures_util::{stream, StreamExt};
+use sea_orm::{sea_query::OnConflict, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter};
+use teloxide::{prelude::*, types::ParseMode, utils::html};
+use utils::{
+    endpoints::vybe::{types::VybeTokenDetails, util::VYBE_TOKEN_API},
+    math::calculate_price_change,
+    number::{format_compact_price, format_long_number},
+};
+
+use super::message::is_valid_solana_mint_address;
+use crate::{
+    i18n::{t, user_lang, Lang},
+    HandlerResult,
+};
+
+const MAX_WATCHLIST_SIZE: usize = 30;
+
+// Token detail requests in flight at once while rendering a watchlist
+const DETAILS_CONCURRENCY: usize = 4;
+
+// Stream prices older than this fall back to the REST price
+const LIVE_PRICE_MAX_AGE_SECS: u64 = 120;
+
+/// Subscribe the aggregator to every watched or followed mint so their trades stay live
+pub async fn sync_watched_mints() {
+    let db = entity::get_db().await;
+    let mints = tokio::try_join!(
+        WatchlistEntry::find_watched_mints(db),
+        TradeFollow::find_followed_mints(db)
+    );
+    match mints {
+        Ok((watched, followed)) => aggregator::set_watched_mints(
+            watched.into_iter().chain(followed).collect::<BTreeSet<_>>(),
+        ),
+        Err(err) => tracing::error!("Failed to load watched mints: {:?}", err),
+    }
+}
+
+/// Add a token to a user's watchlist, returning the reply for the user
+pub async fn add_to_watchlist(
+    lang: Lang,
+    telegram_id: i64,
+    mint_address: String,
+) -> anyhow::Result<String> {
+    let db = entity::get_db().await;
+    let entries = WatchlistEntry::find_by_user(db, telegram_id).await?;
+    if entries
+        .iter()
+        .any(|entry| entry.mint_address == mint_address)
+    {
+        return Ok(t!(lang, "watch-already"));
+    }
+    if entries.len() >= MAX_WATCHLIST_SIZE {
+        return Ok(t!(lang, "watch-full", count = MAX_WATCHLIST_SIZE));
+    }
+
+    let token_details = match VYBE_TOKEN_API.get_token_details(mint_address.clone()).await {
+        Ok(details) => details,
+        Err(err) => {
+            tracing::warn!("Failed to get token details for watchlist: {:?}", err);
+            return Ok(t!(lang, "token-not-found"));
+        }
+    };
+
+    WatchlistEntry::insert(ActiveModel {
+        telegram_id: Set(telegram_id),
+        mint_address: Set(mint_address),
+        symbol: Set(Some(token_details.symbol.clone())),
+        created_at: Set(chrono::Utc::now()),
+        ..Default::default()
+    })
+    .on_conflict(
+        OnConflict::columns([Column::TelegramId, Column::MintAddress])
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    sync_watched_mints().await;
+
+    Ok(t!(lang, "watch-added", symbol = token_details.symbol))
+}
+
+pub async fn watch(bot: Bot, message: Message, mint_address: String) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let lang = user_lang(Some(from)).await;
+    let mint_address = mint_address.trim().to_string();
+    if !is_valid_solana_mint_address(&mint_address) {
+        bot.send_message(message.chat.id, t!(lang, "watch-usage"))
+            .await?;
+        return Ok(());
+    }
+
+    let reply = add_to_watchlist(lang, from.id.0 as i64, mint_address).await?;
+    bot.send_message(message.chat.id, reply).await?;
+    Ok(())
+}
+
+pub async fn unwatch(bot: Bot, message: Message, mint_address: String) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let db = entity::get_db().await;
+    let result = WatchlistEntry::delete_many()
+        .filter(Column::TelegramId.eq(from.id.0 as i64))
+        .filter(Column::MintAddress.eq(mint_address.trim()))
+        .exec(db)
+        .await?;
+
+    let lang = user_lang(Some(from)).await;
+    let text = if result.rows_affected > 0 {
+        sync_watched_mints().await;
+        t!(lang, "unwatch-removed")
+    } else {
+        t!(lang, "unwatch-missing")
+    };
+    bot.send_message(message.chat.id, text).await?;
+    Ok(())
+}
+
+// One row of the watchlist table: symbol, price, 1d change and 24h volume
+fn watchlist_row(
+    lang: Lang,
+    entry: &watchlist_entry::Model,
+    details: Option<&VybeTokenDetails>,
+) -> String {
+    let symbol = details
+        .map(|details| details.symbol.as_str())
+        .or(entry.symbol.as_deref())
+        .unwrap_or("?");
+    let symbol = symbol.chars().take(8).collect::<String>();
+
+    let Some(details) = details else {
+        return format!("{:<8} {:>10} {:>7} {:>6}", symbol, "n/a", "", "");
+    };
+
+    let now = chrono::Utc::now().timestamp() as u64;
+    let price = aggregator::latest_price(&entry.mint_address)
+        .filter(|price| price.block_time + LIVE_PRICE_MAX_AGE_SECS >= now)
+        .map_or(details.price, |price| price.usd);
+
+    format!(
+        "{:<8} {:>10} {:>7} {:>6}",
+        symbol,
+        lang.number(&format_compact_price(price)),
+        lang.number(&format!(
+            "{:+.1}%",
+            calculate_price_change(price, details.price_1d)
+        )),
+        lang.number(&format_long_number(
+            details.usd_value_volume_24h.unwrap_or(0.0).round()
+        ))
+    )
+}
+
+pub async fn watchlist(bot: Bot, message: Message) -> HandlerResult {
+    let Some(from) = message.from.as_ref() else {
+        return Ok(());
+    };
+
+    let lang = user_lang(Some(from)).await;
+    let db = entity::get_db().await;
+    let entries = WatchlistEntry::find_by_user(db, from.id.0 as i64).await?;
+    if entries.is_empty() {
+        bot.send_message(message.chat.id, t!(lang, "watchlist-empty"))
+            .await?;
+        return Ok(());
+    }
+
+    // Fetch details concurrently but bounded, keeping the watchlist order
+    let rows = stream::iter(entries)
+        .map(|entry| async move {
+            let details = VYBE_TOKEN_API
+                .get_token_details(entry.mint_address.clone())
+                .await
+                .inspect_err(|err| {
+                    tracing::warn!(
+                        "Failed to get details for {}: {:?}",
+                        entry.mint_address,
+                        err
+                    )
+                })
+                .ok();
+            watchlist_row(lang, &entry, details.as_ref())
+        })
+        .buffered(DETAILS_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    let table = format!(
+        "{:<8} {:>10} {:>7} {:>6}\n{}",
+        t!(lang, "table-token"),
+        t!(lang, "table-price"),
+        t!(lang, "table-change-1d"),
+        t!(lang, "table-volume"),
+        rows.join("\n")
+    );
+
+    bot.send_message(
+        message.chat.id,
+        format!(
+            "<b>{}</b>\n<pre>{}</pre>",
+            t!(lang, "watchlist-title"),
+            html::escape(&table)
+        ),
+    )
+    .parse_mode(ParseMode::Html)
+    .await?;
+    Ok(())
+}
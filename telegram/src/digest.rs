@@ -0,0 +1,335 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use entity::{
+    digest_subscription::{self, Entity as DigestSubscription},
+    price_alert::{self, Entity as PriceAlert},
+    watchlist_entry::Entity as WatchlistEntry,
+};
+use sea_orm::{ConnectionTrait, DbErr};
+use teloxide::prelude::*;
+use utils::{math::calculate_price_change, number::format_compact_price};
+
+use crate::{
+    cache,
+    commands::alert::describe_alert,
+    i18n::{stored_user_lang, t, Lang},
+};
+
+// How often due digests are looked up
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+// Digests missed by more than this, like while the bot was down, are skipped instead of sent late
+const MAX_LATENESS: chrono::Duration = chrono::Duration::hours(6);
+
+// Biggest 24h moves listed on top of the watchlist
+const MAX_MOVERS: usize = 3;
+
+/// Source of the current time, so the scheduler can be driven by tests
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// One watched token in a digest
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestRow {
+    pub symbol: String,
+    pub price: f64,
+    pub change_1d: f64,
+}
+
+/// Local time of day as `HH:MM`
+pub fn format_local_time(local_minute: i16) -> String {
+    format!("{:02}:{:02}", local_minute / 60, local_minute % 60)
+}
+
+/// A UTC offset as `UTC`, `UTC+2` or `UTC-5:30`
+pub fn format_utc_offset(utc_offset_minutes: i16) -> String {
+    let sign = if utc_offset_minutes < 0 { '-' } else { '+' };
+    let (hours, minutes) = (utc_offset_minutes.abs() / 60, utc_offset_minutes.abs() % 60);
+    match (hours, minutes) {
+        (0, 0) => "UTC".to_string(),
+        (hours, 0) => format!("UTC{}{}", sign, hours),
+        (hours, minutes) => format!("UTC{}{}:{:02}", sign, hours, minutes),
+    }
+}
+
+fn signed_percent(lang: Lang, change: f64) -> String {
+    lang.number(&format!("{:+.1}%", change))
+}
+
+/// The digest text, watchlist rows in watchlist order
+pub fn render_digest(lang: Lang, rows: &[DigestRow], triggered: &[price_alert::Model]) -> String {
+    let mut text = t!(lang, "digest-title");
+
+    if rows.is_empty() {
+        text.push_str("\n\n");
+        text.push_str(&t!(lang, "watchlist-empty"));
+    } else {
+        let mut movers = rows.iter().collect::<Vec<_>>();
+        movers.sort_by(|a, b| b.change_1d.abs().total_cmp(&a.change_1d.abs()));
+        text.push_str(&format!("\n\n{}", t!(lang, "digest-movers")));
+        for row in movers.into_iter().take(MAX_MOVERS) {
+            let marker = if row.change_1d >= 0.0 { "🟢" } else { "🔴" };
+            text.push_str(&format!(
+                "\n{} {} {}",
+                marker,
+                row.symbol,
+                signed_percent(lang, row.change_1d)
+            ));
+        }
+
+        text.push_str(&format!("\n\n{}", t!(lang, "watchlist-title")));
+        for row in rows {
+            text.push_str(&format!(
+                "\n{} ${} ({})",
+                row.symbol,
+                lang.number(&format_compact_price(row.price)),
+                signed_percent(lang, row.change_1d)
+            ));
+        }
+    }
+
+    text.push_str("\n\n");
+    if triggered.is_empty() {
+        text.push_str(&t!(lang, "digest-no-alerts"));
+    } else {
+        text.push_str(&t!(lang, "digest-alerts"));
+        for alert in triggered {
+            text.push_str(&format!("\n• {}", describe_alert(lang, alert)));
+        }
+    }
+    text
+}
+
+async fn digest_rows(telegram_id: i64) -> Result<Vec<DigestRow>, DbErr> {
+    let db = entity::get_db().await;
+    let mut rows = Vec::new();
+    for entry in WatchlistEntry::find_by_user(db, telegram_id).await? {
+        match cache::token_details(&entry.mint_address).await {
+            Ok(details) => rows.push(DigestRow {
+                change_1d: calculate_price_change(details.price, details.price_1d),
+                symbol: details.symbol,
+                price: details.price,
+            }),
+            Err(err) => tracing::warn!(
+                "Failed to get details of {} for a digest: {:?}",
+                entry.mint_address,
+                err
+            ),
+        }
+    }
+    Ok(rows)
+}
+
+async fn send_digest(
+    bot: &Bot,
+    subscription: &digest_subscription::Model,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let db = entity::get_db().await;
+    let lang = stored_user_lang(subscription.telegram_id).await;
+    let rows = digest_rows(subscription.telegram_id).await?;
+    let triggered = PriceAlert::find_triggered_since(
+        db,
+        subscription.telegram_id,
+        now - chrono::Duration::hours(24),
+    )
+    .await?;
+
+    bot.send_message(
+        ChatId(subscription.chat_id),
+        render_digest(lang, &rows, &triggered),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Claim every digest due at `now` and return the ones to send.
+///
+/// Claiming moves the next run forward first, so an instance that loses the race or a
+/// restart in the middle of sending never sends the same digest twice. After downtime only
+/// the latest missed run is sent, and only when it isn't more than [`MAX_LATENESS`] late.
+pub async fn claim_due<C>(
+    db: &C,
+    now: DateTime<Utc>,
+) -> Result<Vec<digest_subscription::Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut claimed = Vec::new();
+    for subscription in DigestSubscription::find_due(db, now).await? {
+        if !DigestSubscription::claim(db, &subscription, now).await? {
+            continue;
+        }
+        // The run at or before `now`, later than `next_run_at` when the bot was down
+        let latest_run = subscription.next_run_after(now) - chrono::Duration::days(1);
+        if now - latest_run > MAX_LATENESS {
+            tracing::info!(
+                "Skipping digest of {} due at {}",
+                subscription.telegram_id,
+                latest_run
+            );
+            continue;
+        }
+        claimed.push(subscription);
+    }
+    Ok(claimed)
+}
+
+/// Send the daily digests as they come due, driven by `clock`
+pub async fn run(bot: Bot, clock: impl Clock) {
+    let mut interval = tokio::time::interval(TICK_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        let now = clock.now();
+        let db = entity::get_db().await;
+        let due = match claim_due(db, now).await {
+            Ok(due) => due,
+            Err(err) => {
+                tracing::error!("Failed to load due digests: {:?}", err);
+                continue;
+            }
+        };
+
+        for subscription in due {
+            if let Err(err) = send_digest(&bot, &subscription, now).await {
+                tracing::warn!(
+                    "Failed to send digest to {}: {:?}",
+                    subscription.telegram_id,
+                    err
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use entity::price_alert::AlertCondition;
+    use entity::test_support::test_db;
+    use std::sync::Mutex;
+
+    /// A clock that only moves when told to
+    struct ManualClock(Mutex<DateTime<Utc>>);
+
+    impl ManualClock {
+        fn advance(&self, by: chrono::Duration) {
+            *self.0.lock().unwrap() += by;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    fn row(symbol: &str, price: f64, change_1d: f64) -> DigestRow {
+        DigestRow {
+            symbol: symbol.to_string(),
+            price,
+            change_1d,
+        }
+    }
+
+    #[test]
+    fn test_format_time_and_offset() {
+        assert_eq!(format_local_time(9 * 60 + 5), "09:05");
+        assert_eq!(format_utc_offset(0), "UTC");
+        assert_eq!(format_utc_offset(120), "UTC+2");
+        assert_eq!(format_utc_offset(-330), "UTC-5:30");
+    }
+
+    #[test]
+    fn test_render_digest() {
+        let rows = [
+            row("BONK", 0.00002, 3.0),
+            row("WIF", 2.5, -12.5),
+            row("JUP", 0.8, 0.5),
+            row("POPCAT", 0.4, 7.5),
+        ];
+        let alert = price_alert::Model {
+            id: 1,
+            telegram_id: 1,
+            chat_id: 1,
+            mint_address: "mint".to_string(),
+            symbol: Some("WIF".to_string()),
+            condition: AlertCondition::Below,
+            target: 2.6,
+            reference_price: None,
+            repeating: false,
+            cooldown_secs: 0,
+            active: false,
+            last_triggered_at: None,
+            created_at: Utc::now(),
+        };
+
+        let text = render_digest(Lang::En, &rows, &[alert]);
+        let movers = text.find("🔴 WIF -12.5%").unwrap();
+        assert!(movers < text.find("🟢 POPCAT +7.5%").unwrap());
+        assert!(!text.contains("🟢 JUP"));
+        assert!(text.contains("\nJUP $0.800 (+0.5%)"));
+        assert!(text.contains("• WIF below $2.6"));
+
+        let empty = render_digest(Lang::En, &[], &[]);
+        assert!(empty.contains("/watch"));
+    }
+
+    #[tokio::test]
+    async fn test_claim_due_with_clock() {
+        let db = test_db().await;
+
+        let telegram_id = Utc::now().timestamp_micros();
+        let clock = ManualClock(Mutex::new(
+            Utc.with_ymd_and_hms(2000, 1, 1, 8, 0, 0).unwrap(),
+        ));
+        DigestSubscription::subscribe(&db, telegram_id, telegram_id, 9 * 60, 0, clock.now())
+            .await
+            .unwrap();
+        let claimed = |now| {
+            let db = &db;
+            async move {
+                claim_due(db, now)
+                    .await
+                    .unwrap()
+                    .iter()
+                    .filter(|subscription| subscription.telegram_id == telegram_id)
+                    .count()
+            }
+        };
+
+        assert_eq!(claimed(clock.now()).await, 0);
+        clock.advance(chrono::Duration::hours(1));
+        // A second instance ticking at the same time gets nothing
+        assert_eq!(claimed(clock.now()).await, 1);
+        assert_eq!(claimed(clock.now()).await, 0);
+
+        // Back up minutes after today's run, yesterday's missed digest is dropped and
+        // today's is sent
+        clock.advance(chrono::Duration::days(2) + chrono::Duration::minutes(10));
+        assert_eq!(claimed(clock.now()).await, 1);
+        assert_eq!(claimed(clock.now()).await, 0);
+
+        // Back up in the evening, the morning's digest is too late and the schedule moves on
+        clock.advance(chrono::Duration::days(2) + chrono::Duration::minutes(660 - 10));
+        assert_eq!(claimed(clock.now()).await, 0);
+        clock.advance(chrono::Duration::hours(13));
+        assert_eq!(claimed(clock.now()).await, 1);
+
+        DigestSubscription::unsubscribe(&db, telegram_id)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+use utils::endpoints::vybe::types::VybeTopHolder;
+
+use crate::cache;
+
+/// Holders that aren't regular wallets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HolderKind {
+    Exchange,
+    /// Tokens sent here are gone for good
+    Burn,
+    /// Pool and AMM accounts holding the liquidity side of a market
+    Liquidity,
+}
+
+// Well known owners, checked before Vybe's own labels
+const KNOWN_WALLETS: &[(&str, &str, HolderKind)] = &[
+    (
+        "1nc1nerator11111111111111111111111111111111",
+        "Incinerator",
+        HolderKind::Burn,
+    ),
+    (
+        "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1",
+        "Raydium AMM",
+        HolderKind::Liquidity,
+    ),
+    (
+        "GpMZbSM2GgvTKHJirzeGfMFoaZ8UR2X7F4v8vHTvxFbL",
+        "Raydium CPMM",
+        HolderKind::Liquidity,
+    ),
+    (
+        "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+        "Binance",
+        HolderKind::Exchange,
+    ),
+    (
+        "5tzFkiKscXHK5ZXCGbXZxdw7gTjjD1mBwuoFbhUvuAi9",
+        "Binance",
+        HolderKind::Exchange,
+    ),
+    (
+        "H8sMJSCQxfKiFTCfDR3DUMLPwcRbM61LGFJ8N4dK3WjS",
+        "Coinbase",
+        HolderKind::Exchange,
+    ),
+    (
+        "AC5RDfQFmDS1deWZos921JfqscXdByf8BKHs5ACWjtW2",
+        "Bybit",
+        HolderKind::Exchange,
+    ),
+    (
+        "5VCwKtCXgCJ6kit5FybXjvriW3xELsFDhYrPSqtJNmcD",
+        "OKX",
+        HolderKind::Exchange,
+    ),
+    (
+        "FWznbcNXWQuHTawe9RxvQ2LdCENssh12dsznf4RiouN5",
+        "Kraken",
+        HolderKind::Exchange,
+    ),
+];
+
+// Words in Vybe's owner names that tell what kind of account it is
+const EXCHANGE_WORDS: &[&str] = &[
+    "binance", "coinbase", "okx", "bybit", "kraken", "kucoin", "gate.io", "mexc", "bitget",
+];
+const LIQUIDITY_WORDS: &[&str] = &["raydium", "orca", "meteora", "pool", "amm", "pump"];
+const BURN_WORDS: &[&str] = &["burn", "incinerator"];
+
+/// Label and kind of a holder, `None` label for wallets nobody named
+pub fn holder_label(holder: &VybeTopHolder) -> (Option<String>, Option<HolderKind>) {
+    if let Some((_, label, kind)) = KNOWN_WALLETS
+        .iter()
+        .find(|(address, _, _)| *address == holder.owner_address)
+    {
+        return (Some(label.to_string()), Some(*kind));
+    }
+
+    let Some(name) = holder.owner_name.as_deref().filter(|name| !name.is_empty()) else {
+        return (None, None);
+    };
+    let lowercase = name.to_lowercase();
+    let contains_any = |words: &[&str]| words.iter().any(|word| lowercase.contains(word));
+    let kind = if contains_any(BURN_WORDS) {
+        Some(HolderKind::Burn)
+    } else if contains_any(LIQUIDITY_WORDS) {
+        Some(HolderKind::Liquidity)
+    } else if contains_any(EXCHANGE_WORDS) {
+        Some(HolderKind::Exchange)
+    } else {
+        None
+    };
+    (Some(name.to_string()), kind)
+}
+
+/// Share of the supply held by the 10 largest holders, burn and liquidity accounts left out
+pub fn top10_share(holders: &[VybeTopHolder]) -> Option<f64> {
+    let mut circulating = holders
+        .iter()
+        .filter(|holder| {
+            !matches!(
+                holder_label(holder).1,
+                Some(HolderKind::Burn | HolderKind::Liquidity)
+            )
+        })
+        .take(10)
+        .peekable();
+    circulating.peek()?;
+
+    Some(
+        circulating
+            .map(|holder| holder.percentage_of_supply_held)
+            .sum(),
+    )
+}
+
+/// Top 10 share of a token for its card, `None` when the holders can't be loaded
+pub async fn token_top10_share(mint_address: &str) -> Option<f64> {
+    match cache::top_holders(mint_address).await {
+        Ok(holders) => top10_share(&holders),
+        Err(err) => {
+            tracing::warn!("Failed to get holders of {}: {:?}", mint_address, err);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holder(owner_address: &str, owner_name: Option<&str>, percentage: f64) -> VybeTopHolder {
+        VybeTopHolder {
+            rank: 0,
+            owner_address: owner_address.to_string(),
+            owner_name: owner_name.map(str::to_string),
+            balance: 0.0,
+            percentage_of_supply_held: percentage,
+        }
+    }
+
+    #[test]
+    fn test_holder_label() {
+        assert_eq!(
+            holder_label(&holder(
+                "1nc1nerator11111111111111111111111111111111",
+                None,
+                1.0
+            )),
+            (Some("Incinerator".to_string()), Some(HolderKind::Burn))
+        );
+        assert_eq!(
+            holder_label(&holder("pool", Some("Meteora DLMM Pool"), 1.0)),
+            (
+                Some("Meteora DLMM Pool".to_string()),
+                Some(HolderKind::Liquidity)
+            )
+        );
+        assert_eq!(
+            holder_label(&holder("cex", Some("MEXC Hot Wallet"), 1.0)).1,
+            Some(HolderKind::Exchange)
+        );
+        assert_eq!(holder_label(&holder("someone", None, 1.0)), (None, None));
+    }
+
+    #[test]
+    fn test_top10_share_skips_burn_and_liquidity() {
+        let mut holders = vec![
+            holder("1nc1nerator11111111111111111111111111111111", None, 30.0),
+            holder("lp", Some("Raydium Pool"), 20.0),
+        ];
+        holders.extend((0..12).map(|i| holder(&format!("wallet{}", i), None, 2.0)));
+
+        assert_eq!(top10_share(&holders), Some(20.0));
+        assert_eq!(top10_share(&holders[..2]), None);
+        assert_eq!(top10_share(&[]), None);
+    }
+}
@@ -0,0 +1,298 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, RwLock},
+};
+
+use entity::tg_user::Entity as TgUser;
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use sea_orm::DbErr;
+use teloxide::types::User;
+use unic_langid::LanguageIdentifier;
+use utils::number::{localize_number, Separators};
+
+/// Languages the bot speaks, each backed by a file in `telegram/locales`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    Es,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::En, Lang::Es];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Es => "es",
+        }
+    }
+
+    /// Name of the language in itself, for the `/language` buttons
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Lang::En => "English",
+            Lang::Es => "Español",
+        }
+    }
+
+    /// Match a Telegram `language_code` like `es` or `es-AR`
+    pub fn from_code(code: &str) -> Option<Self> {
+        let primary = code.split(['-', '_']).next()?.to_ascii_lowercase();
+        Lang::ALL.into_iter().find(|lang| lang.code() == primary)
+    }
+
+    pub fn separators(self) -> Separators {
+        match self {
+            Lang::En => Separators::ENGLISH,
+            Lang::Es => Separators::SPANISH,
+        }
+    }
+
+    /// A number formatted by `utils::number` with this language's separators
+    pub fn number(self, formatted: &str) -> String {
+        localize_number(formatted, self.separators())
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Lang::En => include_str!("../locales/en.ftl"),
+            Lang::Es => include_str!("../locales/es.ftl"),
+        }
+    }
+}
+
+static BUNDLES: LazyLock<HashMap<Lang, FluentBundle<FluentResource>>> = LazyLock::new(|| {
+    Lang::ALL
+        .into_iter()
+        .map(|lang| {
+            let resource =
+                FluentResource::try_new(lang.source().to_string()).unwrap_or_else(|(_, errors)| {
+                    panic!("Invalid {} locale: {:?}", lang.code(), errors)
+                });
+            let id = lang
+                .code()
+                .parse::<LanguageIdentifier>()
+                .expect("Locale codes are valid language identifiers");
+
+            let mut bundle = FluentBundle::new_concurrent(vec![id]);
+            // Telegram shows the bidi isolation marks around arguments as garbage in some clients
+            bundle.set_use_isolating(false);
+            bundle.add_resource(resource).unwrap_or_else(|errors| {
+                panic!("Duplicate {} messages: {:?}", lang.code(), errors)
+            });
+            (lang, bundle)
+        })
+        .collect()
+});
+
+fn format(lang: Lang, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let bundle = &BUNDLES[&lang];
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        tracing::warn!("Errors formatting {} in {}: {:?}", id, lang.code(), errors);
+    }
+    Some(text.into_owned())
+}
+
+/// The message `id` in `lang`, falling back to English and then to the id itself
+pub fn translate(lang: Lang, id: &str, args: Option<&FluentArgs>) -> String {
+    format(lang, id, args)
+        .or_else(|| format(Lang::En, id, args))
+        .unwrap_or_else(|| {
+            tracing::error!("Missing message {}", id);
+            id.to_string()
+        })
+}
+
+/// Translate a message, `t!(lang, <id>)` or `t!(lang, <id>, name = value, ...)`
+macro_rules! t {
+    ($lang:expr, $id:literal) => {
+        $crate::i18n::translate($lang, $id, None)
+    };
+    ($lang:expr, $id:literal, $($key:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($key), $value);)+
+        $crate::i18n::translate($lang, $id, Some(&args))
+    }};
+}
+pub(crate) use t;
+
+// `/language` overrides by Telegram user id, `None` for users who kept their Telegram language
+static OVERRIDES: LazyLock<RwLock<HashMap<i64, Option<Lang>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+async fn language_override(telegram_id: i64) -> Option<Lang> {
+    if let Some(lang) = OVERRIDES.read().unwrap().get(&telegram_id) {
+        return *lang;
+    }
+
+    let db = entity::get_db().await;
+    match TgUser::find_by_telegram_id(db, telegram_id).await {
+        Ok(user) => {
+            let lang = user
+                .and_then(|user| user.language)
+                .and_then(|code| Lang::from_code(&code));
+            OVERRIDES.write().unwrap().insert(telegram_id, lang);
+            lang
+        }
+        Err(err) => {
+            tracing::warn!("Failed to load language of {}: {:?}", telegram_id, err);
+            None
+        }
+    }
+}
+
+/// Language to answer a user in, the `/language` override or else their Telegram language
+pub async fn user_lang(user: Option<&User>) -> Lang {
+    let Some(user) = user else {
+        return Lang::default();
+    };
+
+    match language_override(user.id.0 as i64).await {
+        Some(lang) => lang,
+        None => user
+            .language_code
+            .as_deref()
+            .and_then(Lang::from_code)
+            .unwrap_or_default(),
+    }
+}
+
+/// Language of a user known only by id, for messages sent outside of an update
+pub async fn stored_user_lang(telegram_id: i64) -> Lang {
+    let db = entity::get_db().await;
+    match TgUser::find_by_telegram_id(db, telegram_id).await {
+        Ok(Some(user)) => user
+            .language
+            .or(user.language_code)
+            .and_then(|code| Lang::from_code(&code))
+            .unwrap_or_default(),
+        Ok(None) => Lang::default(),
+        Err(err) => {
+            tracing::warn!("Failed to load language of {}: {:?}", telegram_id, err);
+            Lang::default()
+        }
+    }
+}
+
+/// Store a `/language` override, `None` goes back to the Telegram language.
+///
+/// Returns whether the user exists, users are created by `/start`.
+pub async fn set_language_override(telegram_id: i64, lang: Option<Lang>) -> Result<bool, DbErr> {
+    let db = entity::get_db().await;
+    let stored =
+        TgUser::set_language(db, telegram_id, lang.map(|lang| lang.code().to_string())).await?;
+    if stored {
+        OVERRIDES.write().unwrap().insert(telegram_id, lang);
+    }
+    Ok(stored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::BTreeSet, path::Path};
+
+    // Message ids defined at the start of a line, `id = ...`
+    fn message_ids(source: &str) -> BTreeSet<&str> {
+        source
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id.trim()))
+            .collect()
+    }
+
+    // Ids passed to `t!` anywhere in the crate
+    fn used_ids(dir: &Path, ids: &mut BTreeSet<String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                used_ids(&path, ids);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                let source = std::fs::read_to_string(&path).unwrap();
+                for (index, _) in source.match_indices("t!(") {
+                    // `format!(` and `assert!(` end the same way
+                    let before = source[..index].chars().next_back();
+                    if before.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                        continue;
+                    }
+                    // The id is the string literal after the language argument
+                    let rest = &source[index + 3..];
+                    let Some(start) = rest.find('"') else {
+                        continue;
+                    };
+                    if rest[..start].contains(')') || !rest[..start].contains(',') {
+                        continue;
+                    }
+                    ids.insert(rest[start + 1..].split('"').next().unwrap().to_string());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_locale_has_every_message() {
+        let english = message_ids(Lang::En.source());
+        assert!(!english.is_empty());
+
+        for lang in Lang::ALL {
+            assert_eq!(
+                message_ids(lang.source()),
+                english,
+                "{} doesn't match the English messages",
+                lang.code()
+            );
+            for id in &english {
+                assert!(
+                    BUNDLES[&lang].has_message(id),
+                    "{} misses {}",
+                    lang.code(),
+                    id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_used_message_exists() {
+        let mut ids = BTreeSet::new();
+        used_ids(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut ids);
+        assert!(ids.len() > 10);
+
+        for id in ids {
+            assert!(
+                BUNDLES[&Lang::En].has_message(&id),
+                "missing message {}",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn test_lang_from_code() {
+        assert_eq!(Lang::from_code("es"), Some(Lang::Es));
+        assert_eq!(Lang::from_code("es-AR"), Some(Lang::Es));
+        assert_eq!(Lang::from_code("EN_us"), Some(Lang::En));
+        assert_eq!(Lang::from_code("fr"), None);
+    }
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(
+            t!(Lang::Es, "watch-added", symbol = "BONK"),
+            "👀 BONK añadido a tu lista."
+        );
+        assert_eq!(
+            t!(Lang::En, "alert-limit", count = 20),
+            "You already have 20 alerts, remove some with /alerts first."
+        );
+        assert_eq!(
+            translate(Lang::Es, "no-such-message", None),
+            "no-such-message"
+        );
+        assert_eq!(Lang::Es.number("1,234.5"), "1.234,5");
+    }
+}
@@ -0,0 +1,126 @@
+use teloxide::{
+    prelude::*,
+    types::{
+        InlineQueryResult, InlineQueryResultArticle, InlineQueryResultPhoto, InputMessageContent,
+        InputMessageContentText,
+    },
+};
+use utils::endpoints::vybe::types::VybeTokenDetails;
+
+use crate::{
+    cache,
+    card::{token_card, CARD_PARSE_MODE},
+    commands::message::is_valid_solana_mint_address,
+    i18n::{user_lang, Lang},
+    registry::{self, parse_cashtag},
+    HandlerResult,
+};
+
+// Seconds Telegram may cache our answer to an identical query
+const INLINE_CACHE_TIME: u32 = 30;
+
+// Symbols can be ambiguous, offer at most this many tokens
+const MAX_INLINE_RESULTS: usize = 5;
+
+/// What an inline query asks for
+#[derive(Debug, PartialEq)]
+enum InlineLookup<'a> {
+    Mint(&'a str),
+    Symbol(&'a str),
+}
+
+fn parse_inline_query(query: &str) -> Option<InlineLookup<'_>> {
+    if let Some(symbol) = parse_cashtag(query) {
+        return Some(InlineLookup::Symbol(symbol));
+    }
+
+    let query = query.trim();
+    is_valid_solana_mint_address(query).then_some(InlineLookup::Mint(query))
+}
+
+fn inline_result(lang: Lang, token_details: &VybeTokenDetails) -> InlineQueryResult {
+    let card = token_card(lang, token_details);
+
+    match card.logo_url {
+        Some(logo_url) => InlineQueryResult::Photo(
+            InlineQueryResultPhoto::new(
+                token_details.mint_address.clone(),
+                logo_url.clone(),
+                logo_url,
+            )
+            .title(card.title)
+            .description(card.description)
+            .caption(card.caption)
+            .parse_mode(CARD_PARSE_MODE),
+        ),
+        None => InlineQueryResult::Article(
+            InlineQueryResultArticle::new(
+                token_details.mint_address.clone(),
+                card.title,
+                InputMessageContent::Text(
+                    InputMessageContentText::new(card.caption).parse_mode(CARD_PARSE_MODE),
+                ),
+            )
+            .description(card.description),
+        ),
+    }
+}
+
+async fn lookup(query: &str) -> Vec<VybeTokenDetails> {
+    let mints = match parse_inline_query(query) {
+        Some(InlineLookup::Mint(mint)) => vec![mint.to_string()],
+        Some(InlineLookup::Symbol(symbol)) => registry::resolve(symbol)
+            .into_iter()
+            .map(|token| token.mint_address)
+            .collect(),
+        None => return Vec::new(),
+    };
+
+    let mut tokens = Vec::new();
+    for mint in mints.into_iter().take(MAX_INLINE_RESULTS) {
+        match cache::token_details(&mint).await {
+            Ok(details) => tokens.push(details),
+            Err(err) => tracing::debug!("Inline lookup of {} failed: {:?}", mint, err),
+        }
+    }
+    tokens
+}
+
+pub async fn inline_query(bot: Bot, query: InlineQuery) -> HandlerResult {
+    let lang = user_lang(Some(&query.from)).await;
+    let results = lookup(&query.query)
+        .await
+        .iter()
+        .map(|token_details| inline_result(lang, token_details))
+        .collect::<Vec<_>>();
+
+    // Cards are in the language of whoever asked, so Telegram can't share them between users
+    bot.answer_inline_query(query.id.clone(), results)
+        .cache_time(INLINE_CACHE_TIME)
+        .is_personal(true)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_inline_query() {
+        assert_eq!(
+            parse_inline_query(" DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263 "),
+            Some(InlineLookup::Mint(
+                "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263"
+            ))
+        );
+        assert_eq!(
+            parse_inline_query("$BONK"),
+            Some(InlineLookup::Symbol("BONK"))
+        );
+        assert_eq!(parse_inline_query("$"), None);
+        assert_eq!(parse_inline_query("$BO NK"), None);
+        assert_eq!(parse_inline_query("bonk"), None);
+        assert_eq!(parse_inline_query(""), None);
+    }
+}
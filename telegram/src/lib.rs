@@ -1,8 +1,33 @@
+mod addresses;
+mod alerts;
+mod bans;
+mod cache;
+mod card;
+mod card_sender;
+mod chart;
+mod chat_settings;
 mod commands;
+mod digest;
+mod holders;
+mod i18n;
+mod inline;
+mod rate_limit;
+mod registry;
+mod rich_text;
+mod stats;
+mod storage;
+#[cfg(feature = "test-harness")]
+pub mod test_harness;
+mod trade_feed;
+mod wallet_tracker;
+pub mod webhook;
 use commands::{message::handle_message, start};
 use entity::{tg_user, tg_user::Entity as TgUser};
+use i18n::{t, Lang};
+use serde::{Deserialize, Serialize};
+use storage::DialogueStorage;
 use teloxide::{
-    dispatching::dialogue::InMemStorage,
+    dispatching::UpdateHandler,
     payloads::SetWebhook,
     prelude::*,
     utils::command::{self, BotCommands},
@@ -10,12 +35,16 @@ use teloxide::{
 use tracing::instrument::WithSubscriber;
 use url::Url;
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub enum GlobalState {
     #[default]
     Idle,
     Start,
     Test(commands::test::State),
+    /// Waiting for the condition of an alert started from a token card
+    AwaitingAlert {
+        mint_address: String,
+    },
 }
 
 #[derive(BotCommands, Clone)]
@@ -25,57 +54,359 @@ pub enum GlobalState {
 )]
 enum GlobalCommand {
     #[command(description = "start bot.")]
-    Start,
+    Start(String),
+    #[command(description = "set a price alert: /alert <mint> above|below <price> or <mint> +20%")]
+    Alert(String),
+    #[command(description = "list and remove your price alerts.")]
+    Alerts,
+    #[command(description = "add a token to your watchlist: /watch <mint>")]
+    Watch(String),
+    #[command(description = "remove a token from your watchlist: /unwatch <mint>")]
+    Unwatch(String),
+    #[command(description = "show prices of your watched tokens.")]
+    Watchlist,
+    #[command(description = "price chart of a token: /chart <mint> [1h|24h|7d]")]
+    Chart(String),
+    #[command(description = "token balances of a wallet: /wallet <address>")]
+    Wallet(String),
+    #[command(description = "largest holders of a token: /holders <mint>")]
+    Holders(String),
+    #[command(
+        description = "daily digest of your watchlist: /digest on [HH:MM] [UTC+2] or /digest off"
+    )]
+    Digest(String),
+    #[command(description = "stream trades of a token into this chat: /follow <mint> [min_usd]")]
+    Follow(String),
+    #[command(description = "stop a trade feed: /unfollow <mint|all>")]
+    Unfollow(String),
+    #[command(description = "get notified when a wallet swaps: /track <wallet> [label]")]
+    Track(String),
+    #[command(description = "list and remove the wallets you track.")]
+    Tracked,
+    #[command(description = "your referral link and how many users joined with it.")]
+    Referrals,
+    #[command(description = "choose the language of the bot: /language [en|es|auto]")]
+    Language(String),
+    #[command(description = "configure the bot in a group (admins only).")]
+    Settings,
     #[command(description = "testing")]
     Test,
     #[command(description = "display this text.")]
     Help,
 }
 
-pub type GlobalDialogue = Dialogue<GlobalState, InMemStorage<GlobalState>>;
+/// Operator commands, only accepted from `ADMIN_IDS` and left out of `/help`
+#[derive(BotCommands, Clone)]
+#[command(rename_rule = "lowercase")]
+enum AdminCommand {
+    Stats,
+    Broadcast(String),
+    Ban(String),
+    Unban(String),
+}
+
+impl GlobalCommand {
+    /// Name under which a group can turn the command off, `None` when it is always available
+    fn group_toggle_name(&self) -> Option<&'static str> {
+        match self {
+            GlobalCommand::Alert(_) => Some("alert"),
+            GlobalCommand::Alerts => Some("alerts"),
+            GlobalCommand::Watch(_) => Some("watch"),
+            GlobalCommand::Unwatch(_) => Some("unwatch"),
+            GlobalCommand::Watchlist => Some("watchlist"),
+            GlobalCommand::Chart(_) => Some("chart"),
+            GlobalCommand::Wallet(_) => Some("wallet"),
+            GlobalCommand::Holders(_) => Some("holders"),
+            GlobalCommand::Digest(_) => Some("digest"),
+            GlobalCommand::Follow(_) => Some("follow"),
+            GlobalCommand::Unfollow(_) => Some("unfollow"),
+            GlobalCommand::Track(_) => Some("track"),
+            GlobalCommand::Tracked => Some("tracked"),
+            GlobalCommand::Referrals => Some("referrals"),
+            GlobalCommand::Start(_)
+            | GlobalCommand::Language(_)
+            | GlobalCommand::Settings
+            | GlobalCommand::Test
+            | GlobalCommand::Help => None,
+        }
+    }
+
+    fn description(&self, lang: Lang) -> String {
+        match self {
+            GlobalCommand::Start(_) => t!(lang, "help-start"),
+            GlobalCommand::Alert(_) => t!(lang, "help-alert"),
+            GlobalCommand::Alerts => t!(lang, "help-alerts"),
+            GlobalCommand::Watch(_) => t!(lang, "help-watch"),
+            GlobalCommand::Unwatch(_) => t!(lang, "help-unwatch"),
+            GlobalCommand::Watchlist => t!(lang, "help-watchlist"),
+            GlobalCommand::Chart(_) => t!(lang, "help-chart"),
+            GlobalCommand::Wallet(_) => t!(lang, "help-wallet"),
+            GlobalCommand::Holders(_) => t!(lang, "help-holders"),
+            GlobalCommand::Digest(_) => t!(lang, "help-digest"),
+            GlobalCommand::Follow(_) => t!(lang, "help-follow"),
+            GlobalCommand::Unfollow(_) => t!(lang, "help-unfollow"),
+            GlobalCommand::Track(_) => t!(lang, "help-track"),
+            GlobalCommand::Tracked => t!(lang, "help-tracked"),
+            GlobalCommand::Referrals => t!(lang, "help-referrals"),
+            GlobalCommand::Language(_) => t!(lang, "help-language"),
+            GlobalCommand::Settings => t!(lang, "help-settings"),
+            GlobalCommand::Test => t!(lang, "help-test"),
+            GlobalCommand::Help => t!(lang, "help-help"),
+        }
+    }
+
+    /// The command list of `/start` in `lang`, the `description` attributes above only feed
+    /// Telegram's English command menu
+    fn help(lang: Lang) -> String {
+        let lines = GlobalCommand::bot_commands()
+            .into_iter()
+            .filter_map(|command| {
+                let parsed = GlobalCommand::parse(&command.command, "").ok()?;
+                Some(format!(
+                    "{} — {}",
+                    command.command,
+                    parsed.description(lang)
+                ))
+            })
+            .collect::<Vec<_>>();
+        format!("{}\n\n{}", t!(lang, "help-header"), lines.join("\n"))
+    }
+}
+
+pub type GlobalDialogue = Dialogue<GlobalState, DialogueStorage<GlobalState>>;
 type HandlerResult = Result<(), anyhow::Error>;
 // type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
 
+/// Every handler of the bot, the dispatcher needs a `DialogueStorage<GlobalState>` dependency
+pub(crate) fn schema() -> UpdateHandler<anyhow::Error> {
+    dptree::entry()
+        .branch(
+            dptree::filter(|update: Update| bans::is_banned(&update)).endpoint(bans::ignore_banned),
+        )
+        .branch(
+            Update::filter_message()
+                .filter_command::<AdminCommand>()
+                .filter(|message: Message| {
+                    message
+                        .from
+                        .as_ref()
+                        .is_some_and(|user| bans::is_admin(user.id))
+                })
+                .branch(dptree::case![AdminCommand::Stats].endpoint(commands::admin::stats))
+                .branch(
+                    dptree::case![AdminCommand::Broadcast(text)]
+                        .endpoint(commands::admin::broadcast),
+                )
+                .branch(dptree::case![AdminCommand::Ban(args)].endpoint(commands::admin::ban))
+                .branch(dptree::case![AdminCommand::Unban(args)].endpoint(commands::admin::unban)),
+        )
+        .branch(
+            Update::filter_message()
+                .filter_command::<GlobalCommand>()
+                // Commands the group turned off in /settings stop here, their arguments aren't
+                // scanned for tokens either
+                .branch(
+                    dptree::filter_async(|message: Message, command: GlobalCommand| async move {
+                        !chat_settings::command_allowed(&message.chat, command.group_toggle_name())
+                            .await
+                    })
+                    .endpoint(commands::settings::ignore_disabled_command),
+                )
+                .branch(dptree::case![GlobalCommand::Start(args)].endpoint(commands::start::start))
+                .branch(dptree::case![GlobalCommand::Alert(args)].endpoint(commands::alert::alert))
+                .branch(dptree::case![GlobalCommand::Alerts].endpoint(commands::alert::alerts))
+                .branch(
+                    dptree::case![GlobalCommand::Watch(mint)].endpoint(commands::watchlist::watch),
+                )
+                .branch(
+                    dptree::case![GlobalCommand::Unwatch(mint)]
+                        .endpoint(commands::watchlist::unwatch),
+                )
+                .branch(
+                    dptree::case![GlobalCommand::Watchlist]
+                        .endpoint(commands::watchlist::watchlist),
+                )
+                .branch(dptree::case![GlobalCommand::Chart(args)].endpoint(commands::chart::chart))
+                .branch(
+                    dptree::case![GlobalCommand::Wallet(address)]
+                        .endpoint(commands::wallet::wallet),
+                )
+                .branch(
+                    dptree::case![GlobalCommand::Holders(mint)]
+                        .endpoint(commands::holders::holders),
+                )
+                .branch(
+                    dptree::case![GlobalCommand::Digest(args)].endpoint(commands::digest::digest),
+                )
+                .branch(
+                    dptree::case![GlobalCommand::Follow(args)].endpoint(commands::follow::follow),
+                )
+                .branch(
+                    dptree::case![GlobalCommand::Unfollow(args)]
+                        .endpoint(commands::follow::unfollow),
+                )
+                .branch(dptree::case![GlobalCommand::Track(args)].endpoint(commands::track::track))
+                .branch(dptree::case![GlobalCommand::Tracked].endpoint(commands::track::tracked))
+                .branch(
+                    dptree::case![GlobalCommand::Referrals]
+                        .endpoint(commands::referrals::referrals),
+                )
+                .branch(
+                    dptree::case![GlobalCommand::Language(arg)]
+                        .endpoint(commands::language::language),
+                )
+                .branch(
+                    dptree::case![GlobalCommand::Settings].endpoint(commands::settings::settings),
+                ),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|query: CallbackQuery| {
+                    query
+                        .data
+                        .as_deref()
+                        .is_some_and(|data| data.starts_with(commands::alert::DELETE_ALERT_PREFIX))
+                })
+                .endpoint(commands::alert::delete_alert),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|query: CallbackQuery| {
+                    query
+                        .data
+                        .as_deref()
+                        .is_some_and(|data| data.starts_with(commands::track::UNTRACK_PREFIX))
+                })
+                .endpoint(commands::track::untrack),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|query: CallbackQuery| {
+                    query
+                        .data
+                        .as_deref()
+                        .is_some_and(|data| data.starts_with(commands::message::TOKEN_CARD_PREFIX))
+                })
+                .endpoint(commands::message::show_token),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|query: CallbackQuery| {
+                    query
+                        .data
+                        .as_deref()
+                        .is_some_and(|data| data.starts_with(commands::wallet::WALLET_PAGE_PREFIX))
+                })
+                .endpoint(commands::wallet::wallet_page),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|query: CallbackQuery| {
+                    query
+                        .data
+                        .as_deref()
+                        .is_some_and(|data| data.starts_with(commands::settings::SETTINGS_PREFIX))
+                })
+                .endpoint(commands::settings::settings_callback),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|query: CallbackQuery| {
+                    query
+                        .data
+                        .as_deref()
+                        .is_some_and(|data| data.starts_with(commands::admin::BROADCAST_PREFIX))
+                })
+                .endpoint(commands::admin::broadcast_callback),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|query: CallbackQuery| {
+                    query
+                        .data
+                        .as_deref()
+                        .is_some_and(|data| data.starts_with(commands::language::LANGUAGE_PREFIX))
+                })
+                .endpoint(commands::language::language_callback),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|query: CallbackQuery| {
+                    query
+                        .data
+                        .as_deref()
+                        .is_some_and(|data| data.starts_with(card::CARD_ACTION_PREFIX))
+                })
+                .enter_dialogue::<CallbackQuery, DialogueStorage<GlobalState>, GlobalState>()
+                .endpoint(commands::card_actions::card_callback),
+        )
+        .branch(
+            Update::filter_message()
+                .enter_dialogue::<Message, DialogueStorage<GlobalState>, GlobalState>()
+                .branch(
+                    dptree::case![GlobalState::AwaitingAlert { mint_address }]
+                        .endpoint(commands::card_actions::alert_condition),
+                ),
+        )
+        .branch(Update::filter_inline_query().endpoint(inline::inline_query))
+        .branch(commands::test::schema())
+}
+
+/// Updates no handler took, plain messages are scanned for tokens to look up
+pub(crate) async fn handle_unhandled(bot: Bot, update: &Update) {
+    // tracing::warn!("Unhandled update: {:?}", update);
+    if let teloxide::types::UpdateKind::Message(message) = &update.kind {
+        let _ = handle_message(bot, message.clone()).await;
+    }
+}
+
 pub async fn telegram_bot_entrypoint() {
     // let tg_user = tg_user
 
     let bot = Bot::from_env();
     let bot_clone = bot.clone();
 
-    // const WEBHOOK_URL: &str = "https://api.vybenetwork.xyz/telegram/webhook";
-    // let wh = SetWebhook::new(Url::parse(WEBHOOK_URL).unwrap());
+    tokio::spawn(alerts::run(bot.clone()));
+    tokio::spawn(commands::watchlist::sync_watched_mints());
+    tokio::spawn(registry::run());
+    tokio::spawn(stats::run());
+    tokio::spawn(digest::run(bot.clone(), digest::SystemClock));
+    tokio::spawn(trade_feed::run(bot.clone()));
+    tokio::spawn(wallet_tracker::run(bot.clone()));
+    bans::load_bans().await;
+
+    let listener = webhook::listener(&bot).await;
 
     // GlobalCommand::repl(bot, answer).await;
-    Dispatcher::builder(
-        bot,
-        dptree::entry()
-            .branch(
-                Update::filter_message()
-                    .filter_command::<GlobalCommand>()
-                    .branch(dptree::case![GlobalCommand::Start].endpoint(commands::start::start)),
-            )
-            .branch(commands::test::schema()),
-    )
-    .dependencies(dptree::deps![InMemStorage::<GlobalState>::new()])
-    .default_handler(move |upd| {
-        tracing::info!("Unhandled update");
-        let bot = bot_clone.clone();
-        async move {
-            // tracing::warn!("Unhandled update: {:?}", upd);
-            if let teloxide::types::UpdateKind::Message(message) = &upd.kind {
-                let _ = handle_message(bot, message.clone()).await;
-            }
+    let mut dispatcher = Dispatcher::builder(bot, schema())
+        .dependencies(dptree::deps![
+            DialogueStorage::<GlobalState>::from_config().await
+        ])
+        .default_handler(move |upd| {
+            tracing::info!("Unhandled update");
+            let bot = bot_clone.clone();
+            async move { handle_unhandled(bot, &upd).await }
+        })
+        // If the dispatcher fails for some reason, execute this handler
+        .error_handler(LoggingErrorHandler::with_custom_text(
+            "An error has occurred in the dispatcher",
+        ))
+        // .distribution_function(|_| None::<()>)
+        .enable_ctrlc_handler()
+        .build();
+
+    match listener {
+        Some(listener) => {
+            dispatcher
+                .dispatch_with_listener(
+                    listener,
+                    LoggingErrorHandler::with_custom_text("An error from the webhook listener"),
+                )
+                .await
         }
-    })
-    // If the dispatcher fails for some reason, execute this handler
-    .error_handler(LoggingErrorHandler::with_custom_text(
-        "An error has occurred in the dispatcher",
-    ))
-    // .distribution_function(|_| None::<()>)
-    .enable_ctrlc_handler()
-    .build()
-    .dispatch()
-    .await;
+        // Polling removes any webhook left over from a previous webhook run
+        None => dispatcher.dispatch().await,
+    }
 }
 
 // async fn answer(bot: Bot, msg: Message, cmd: GlobalCommand) -> ResponseResult<()> {
@@ -92,3 +423,20 @@ pub async fn telegram_bot_entrypoint() {
 
 //     Ok(())
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_matches_command_descriptions() {
+        // The English locale repeats the `description` attributes, keep them in sync
+        assert_eq!(
+            GlobalCommand::help(Lang::En),
+            GlobalCommand::descriptions().to_string()
+        );
+        let spanish = GlobalCommand::help(Lang::Es);
+        assert!(spanish.starts_with("Estos son los comandos disponibles:\n\n/start — "));
+        assert!(spanish.contains("\n/referrals — tu enlace de invitación"));
+    }
+}
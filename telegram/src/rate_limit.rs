@@ -0,0 +1,199 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{LazyLock, Mutex},
+    time::Instant,
+};
+
+use teloxide::types::{Chat, User};
+use utils::{
+    rate_limit::{RateTier, TokenBucket},
+    ENV_CONFIG,
+};
+
+use crate::chat_settings::is_group_chat;
+
+// Past this many keys full buckets are forgotten on the next check
+const MAX_TRACKED_KEYS: usize = 4096;
+
+/// Outcome of a lookup against the limits, limited lookups `warn` only once per window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Allowed,
+    UserLimited { warn: bool },
+    ChatLimited { warn: bool },
+}
+
+struct Limited {
+    bucket: TokenBucket,
+    warned_at: Option<Instant>,
+}
+
+/// One token bucket per key, all sharing a tier
+pub struct KeyedLimiter<K> {
+    tier: RateTier,
+    buckets: HashMap<K, Limited>,
+}
+
+impl<K: Hash + Eq> KeyedLimiter<K> {
+    pub fn new(tier: RateTier) -> Self {
+        Self {
+            tier,
+            buckets: HashMap::new(),
+        }
+    }
+
+    fn entry(&mut self, key: K, now: Instant) -> &mut Limited {
+        if self.buckets.len() >= MAX_TRACKED_KEYS {
+            self.buckets
+                .retain(|_, limited| !limited.bucket.is_full(now));
+        }
+        let tier = self.tier;
+        self.buckets.entry(key).or_insert_with(|| Limited {
+            bucket: TokenBucket::new(tier, now),
+            warned_at: None,
+        })
+    }
+
+    fn has_token(&mut self, key: K, now: Instant) -> bool {
+        self.entry(key, now).bucket.has_token(now)
+    }
+
+    fn take(&mut self, key: K, now: Instant) {
+        self.entry(key, now).bucket.try_take(now);
+    }
+
+    // Whether to tell the key to slow down, true at most once per window of the tier
+    fn should_warn(&mut self, key: K, now: Instant) -> bool {
+        let per = self.tier.per;
+        let limited = self.entry(key, now);
+        let warn = limited
+            .warned_at
+            .is_none_or(|warned_at| now.saturating_duration_since(warned_at) >= per);
+        if warn {
+            limited.warned_at = Some(now);
+        }
+        warn
+    }
+}
+
+/// Limits of token lookups, per user everywhere and per group chat on top of that
+pub struct LookupLimiter {
+    users: KeyedLimiter<i64>,
+    chats: KeyedLimiter<i64>,
+}
+
+impl LookupLimiter {
+    pub fn new(user_tier: RateTier, chat_tier: RateTier) -> Self {
+        Self {
+            users: KeyedLimiter::new(user_tier),
+            chats: KeyedLimiter::new(chat_tier),
+        }
+    }
+
+    /// Count a lookup, only taking tokens when both the user and the chat allow it
+    pub fn check(&mut self, user_id: Option<i64>, chat_id: Option<i64>, now: Instant) -> Verdict {
+        if let Some(user_id) = user_id {
+            if !self.users.has_token(user_id, now) {
+                return Verdict::UserLimited {
+                    warn: self.users.should_warn(user_id, now),
+                };
+            }
+        }
+        if let Some(chat_id) = chat_id {
+            if !self.chats.has_token(chat_id, now) {
+                return Verdict::ChatLimited {
+                    warn: self.chats.should_warn(chat_id, now),
+                };
+            }
+            self.chats.take(chat_id, now);
+        }
+        if let Some(user_id) = user_id {
+            self.users.take(user_id, now);
+        }
+
+        Verdict::Allowed
+    }
+}
+
+static LOOKUPS: LazyLock<Mutex<LookupLimiter>> = LazyLock::new(|| {
+    Mutex::new(LookupLimiter::new(
+        ENV_CONFIG.rate_limit_user,
+        ENV_CONFIG.rate_limit_chat,
+    ))
+});
+
+/// Count a token lookup by `user` in `chat`, private chats only count against the user
+pub fn check_lookup(user: Option<&User>, chat: &Chat) -> Verdict {
+    let user_id = user.map(|user| user.id.0 as i64);
+    let chat_id = is_group_chat(chat).then_some(chat.id.0);
+    LOOKUPS
+        .lock()
+        .unwrap()
+        .check(user_id, chat_id, Instant::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_user_limit_warns_once_per_window() {
+        let start = Instant::now();
+        let mut limiter = LookupLimiter::new(RateTier::new(2, 10), RateTier::new(100, 10));
+
+        assert_eq!(limiter.check(Some(1), None, start), Verdict::Allowed);
+        assert_eq!(limiter.check(Some(1), None, start), Verdict::Allowed);
+        assert_eq!(
+            limiter.check(Some(1), None, start),
+            Verdict::UserLimited { warn: true }
+        );
+        assert_eq!(
+            limiter.check(Some(1), None, start + Duration::from_secs(1)),
+            Verdict::UserLimited { warn: false }
+        );
+        // Other users have their own bucket
+        assert_eq!(limiter.check(Some(2), None, start), Verdict::Allowed);
+
+        // A token is back after 5 seconds, the warning only after the 10 second window
+        let later = start + Duration::from_secs(5);
+        assert_eq!(limiter.check(Some(1), None, later), Verdict::Allowed);
+        assert_eq!(
+            limiter.check(Some(1), None, later),
+            Verdict::UserLimited { warn: false }
+        );
+        assert_eq!(
+            limiter.check(Some(1), None, start + Duration::from_secs(10)),
+            Verdict::Allowed
+        );
+        assert_eq!(
+            limiter.check(Some(1), None, start + Duration::from_secs(10)),
+            Verdict::UserLimited { warn: true }
+        );
+    }
+
+    #[test]
+    fn test_chat_limit_spans_users() {
+        let start = Instant::now();
+        let mut limiter = LookupLimiter::new(RateTier::new(2, 10), RateTier::new(3, 30));
+
+        for user_id in 1..=3 {
+            assert_eq!(
+                limiter.check(Some(user_id), Some(-100), start),
+                Verdict::Allowed
+            );
+        }
+        assert_eq!(
+            limiter.check(Some(4), Some(-100), start),
+            Verdict::ChatLimited { warn: true }
+        );
+        // Rejected lookups don't spend the user's tokens
+        assert_eq!(limiter.check(Some(4), Some(-200), start), Verdict::Allowed);
+        assert_eq!(limiter.check(Some(4), Some(-200), start), Verdict::Allowed);
+        assert_eq!(
+            limiter.check(Some(4), Some(-200), start),
+            Verdict::UserLimited { warn: true }
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{LazyLock, RwLock},
+    time::Duration,
+};
+
+use entity::token_registry::{self, Entity as TokenRegistry};
+use sea_orm::{ActiveValue::Set, EntityTrait};
+use tokio::sync::broadcast::error::RecvError;
+use utils::endpoints::vybe::{types::VybeTokenDetails, util::VYBE_TOKEN_API};
+
+use crate::cache;
+
+// How often the Vybe token listing is pulled into the registry
+const LISTING_REFRESH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+const LISTING_PAGE_SIZE: u32 = 500;
+const LISTING_PAGES: u32 = 4;
+
+// Unknown mints seen on the trade stream are looked up a few at a time to spare the API quota
+const SEEN_MINTS_INTERVAL: Duration = Duration::from_secs(60);
+const SEEN_MINTS_PER_TICK: usize = 20;
+const MAX_PENDING_MINTS: usize = 1000;
+
+static REGISTRY: LazyLock<RwLock<RegistryIndex>> =
+    LazyLock::new(|| RwLock::new(RegistryIndex::default()));
+
+/// Symbol and name lookup over the registry, keyed case-insensitively
+#[derive(Default)]
+struct RegistryIndex {
+    tokens: HashMap<String, token_registry::Model>,
+    by_symbol: HashMap<String, Vec<String>>,
+    by_name: HashMap<String, Vec<String>>,
+}
+
+fn remove_key(index: &mut HashMap<String, Vec<String>>, key: &str, mint_address: &str) {
+    if let Some(mints) = index.get_mut(key) {
+        mints.retain(|mint| mint != mint_address);
+        if mints.is_empty() {
+            index.remove(key);
+        }
+    }
+}
+
+impl RegistryIndex {
+    fn insert(&mut self, token: token_registry::Model) {
+        if let Some(previous) = self.tokens.remove(&token.mint_address) {
+            remove_key(
+                &mut self.by_symbol,
+                &previous.symbol.to_lowercase(),
+                &previous.mint_address,
+            );
+            if let Some(name) = &previous.name {
+                remove_key(
+                    &mut self.by_name,
+                    &name.to_lowercase(),
+                    &previous.mint_address,
+                );
+            }
+        }
+
+        self.by_symbol
+            .entry(token.symbol.to_lowercase())
+            .or_default()
+            .push(token.mint_address.clone());
+        if let Some(name) = &token.name {
+            self.by_name
+                .entry(name.to_lowercase())
+                .or_default()
+                .push(token.mint_address.clone());
+        }
+        self.tokens.insert(token.mint_address.clone(), token);
+    }
+
+    /// Tokens whose symbol, or failing that name, matches `query`, best match first
+    fn resolve(&self, query: &str) -> Vec<token_registry::Model> {
+        let key = query.trim().to_lowercase();
+        let Some(mints) = self.by_symbol.get(&key).or_else(|| self.by_name.get(&key)) else {
+            return Vec::new();
+        };
+
+        let mut tokens = mints
+            .iter()
+            .filter_map(|mint| self.tokens.get(mint))
+            .cloned()
+            .collect::<Vec<_>>();
+        // Verified tokens first, then the most traded among duplicates
+        tokens.sort_by(|a, b| {
+            b.verified
+                .cmp(&a.verified)
+                .then(b.volume_24h.total_cmp(&a.volume_24h))
+        });
+        tokens
+    }
+}
+
+/// The symbol of a `$SYMBOL` cashtag
+pub fn parse_cashtag(text: &str) -> Option<&str> {
+    let symbol = text.trim().strip_prefix('$')?;
+    let valid = !symbol.is_empty()
+        && symbol.len() <= 16
+        && symbol.chars().all(|c| c.is_ascii_alphanumeric());
+    valid.then_some(symbol)
+}
+
+/// Registry tokens matching a symbol or name, best match first
+pub fn resolve(query: &str) -> Vec<token_registry::Model> {
+    REGISTRY.read().unwrap().resolve(query)
+}
+
+fn is_known(mint_address: &str) -> bool {
+    REGISTRY.read().unwrap().tokens.contains_key(mint_address)
+}
+
+fn registry_token(details: &VybeTokenDetails) -> token_registry::Model {
+    token_registry::Model {
+        mint_address: details.mint_address.clone(),
+        symbol: details.symbol.clone(),
+        name: details.name.clone(),
+        verified: details.verified,
+        volume_24h: details.usd_value_volume_24h.unwrap_or(0.0),
+        updated_at: chrono::Utc::now(),
+    }
+}
+
+/// Add looked up tokens to the registry and its table
+pub async fn record(tokens: &[VybeTokenDetails]) {
+    // One upsert statement can't touch the same row twice
+    let models = tokens
+        .iter()
+        .map(|details| (details.mint_address.as_str(), registry_token(details)))
+        .collect::<HashMap<_, _>>()
+        .into_values()
+        .collect::<Vec<_>>();
+
+    let db = entity::get_db().await;
+    let active_models = models
+        .iter()
+        .map(|model| token_registry::ActiveModel {
+            mint_address: Set(model.mint_address.clone()),
+            symbol: Set(model.symbol.clone()),
+            name: Set(model.name.clone()),
+            verified: Set(model.verified),
+            volume_24h: Set(model.volume_24h),
+            updated_at: Set(model.updated_at),
+        })
+        .collect();
+    if let Err(err) = TokenRegistry::upsert_many(db, active_models).await {
+        tracing::warn!("Failed to store registry tokens: {:?}", err);
+    }
+
+    let mut registry = REGISTRY.write().unwrap();
+    for model in models {
+        registry.insert(model);
+    }
+}
+
+async fn load_registry() {
+    let db = entity::get_db().await;
+    match TokenRegistry::find().all(db).await {
+        Ok(models) => {
+            let mut registry = REGISTRY.write().unwrap();
+            for model in models {
+                registry.insert(model);
+            }
+        }
+        Err(err) => tracing::error!("Failed to load token registry: {:?}", err),
+    }
+}
+
+async fn refresh_listing() {
+    for page in 0..LISTING_PAGES {
+        match VYBE_TOKEN_API
+            .get_tokens("usdValueVolume24h", LISTING_PAGE_SIZE, page)
+            .await
+        {
+            Ok(listing) if listing.data.is_empty() => break,
+            Ok(listing) => record(&listing.data).await,
+            Err(err) => {
+                tracing::warn!("Failed to fetch token listing page {}: {:?}", page, err);
+                break;
+            }
+        }
+    }
+}
+
+/// Keep the token registry filled from the Vybe listing and from mints trading on the aggregator
+pub async fn run() {
+    load_registry().await;
+
+    let mut trades = aggregator::subscribe();
+    let mut listing_refresh = tokio::time::interval(LISTING_REFRESH_INTERVAL);
+    let mut seen_mints_tick = tokio::time::interval(SEEN_MINTS_INTERVAL);
+    let mut pending = HashSet::new();
+    // Mints already tried, so unlisted ones are not looked up on every trade
+    let mut attempted = HashSet::new();
+
+    loop {
+        tokio::select! {
+            trade = trades.recv() => match trade {
+                Ok(trade) => {
+                    for mint in [trade.base_mint_address, trade.quote_mint_address] {
+                        if pending.len() < MAX_PENDING_MINTS
+                            && !attempted.contains(&mint)
+                            && !is_known(&mint)
+                        {
+                            pending.insert(mint);
+                        }
+                    }
+                }
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            },
+            _ = listing_refresh.tick() => {
+                refresh_listing().await;
+                attempted.clear();
+            }
+            _ = seen_mints_tick.tick() => {
+                let batch = pending.iter().take(SEEN_MINTS_PER_TICK).cloned().collect::<Vec<_>>();
+                let mut found = Vec::new();
+                for mint in batch {
+                    pending.remove(&mint);
+                    attempted.insert(mint.clone());
+                    match cache::token_details(&mint).await {
+                        Ok(details) => found.push(details),
+                        Err(err) => tracing::debug!("Failed to look up seen mint {}: {:?}", mint, err),
+                    }
+                }
+                record(&found).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(
+        mint: &str,
+        symbol: &str,
+        name: &str,
+        verified: bool,
+        volume: f64,
+    ) -> token_registry::Model {
+        token_registry::Model {
+            mint_address: mint.to_string(),
+            symbol: symbol.to_string(),
+            name: Some(name.to_string()),
+            verified,
+            volume_24h: volume,
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn mints(tokens: Vec<token_registry::Model>) -> Vec<String> {
+        tokens.into_iter().map(|token| token.mint_address).collect()
+    }
+
+    #[test]
+    fn test_resolve_ranks_duplicates() {
+        let mut index = RegistryIndex::default();
+        index.insert(token("fake", "BONK", "Bonk Inu", false, 9_000_000.0));
+        index.insert(token("bonk", "Bonk", "Bonk", true, 5_000_000.0));
+        index.insert(token("copy", "BONK", "Bonk 2", false, 100.0));
+
+        assert_eq!(mints(index.resolve("bonk")), vec!["bonk", "fake", "copy"]);
+        assert_eq!(mints(index.resolve("Bonk Inu")), vec!["fake"]);
+        assert!(index.resolve("wif").is_empty());
+    }
+
+    #[test]
+    fn test_insert_replaces_previous_keys() {
+        let mut index = RegistryIndex::default();
+        index.insert(token("mint", "OLD", "Old name", false, 1.0));
+        index.insert(token("mint", "NEW", "New name", true, 1.0));
+
+        assert!(index.resolve("old").is_empty());
+        assert!(index.resolve("old name").is_empty());
+        assert_eq!(mints(index.resolve("new")), vec!["mint"]);
+        assert_eq!(index.tokens.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_cashtag() {
+        assert_eq!(parse_cashtag("$BONK"), Some("BONK"));
+        assert_eq!(parse_cashtag(" $wif "), Some("wif"));
+        assert_eq!(parse_cashtag("$"), None);
+        assert_eq!(parse_cashtag("$BO NK"), None);
+        assert_eq!(parse_cashtag("BONK"), None);
+    }
+}
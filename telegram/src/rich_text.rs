@@ -0,0 +1,259 @@
+use teloxide::types::ParseMode;
+
+/// Longest caption of a photo Telegram accepts, counted in UTF-16 code units of the visible text
+pub const MAX_CAPTION_LEN: usize = 1024;
+
+/// Longest text message Telegram accepts, counted like [`MAX_CAPTION_LEN`]
+pub const MAX_MESSAGE_LEN: usize = 4096;
+
+// Appended to truncated text
+const ELLIPSIS: &str = "…";
+
+/// Markup language a [`RichText`] is rendered to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    MarkdownV2,
+    Html,
+}
+
+impl Style {
+    pub const fn parse_mode(self) -> ParseMode {
+        match self {
+            Style::MarkdownV2 => ParseMode::MarkdownV2,
+            Style::Html => ParseMode::Html,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Plain,
+    Bold,
+    Italic,
+    Code,
+    Pre,
+    Link(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Span {
+    kind: Kind,
+    text: String,
+}
+
+// Every character MarkdownV2 treats specially outside of code, including the backslash itself
+fn escape_markdown(text: &str) -> String {
+    escape_chars(text, "\\_*[]()~`>#+-=|{}.!")
+}
+
+fn escape_chars(text: &str, special: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if special.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn visible_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+// Longest prefix of `text` that fits `max_len` UTF-16 code units
+fn prefix(text: &str, max_len: usize) -> &str {
+    let mut len = 0;
+    for (index, c) in text.char_indices() {
+        len += c.len_utf16();
+        if len > max_len {
+            return &text[..index];
+        }
+    }
+    text
+}
+
+impl Span {
+    fn render(&self, style: Style, text: &str) -> String {
+        match style {
+            Style::MarkdownV2 => match &self.kind {
+                Kind::Plain => escape_markdown(text),
+                Kind::Bold => format!("*{}*", escape_markdown(text)),
+                Kind::Italic => format!("_{}_", escape_markdown(text)),
+                Kind::Code => format!("`{}`", escape_chars(text, "\\`")),
+                Kind::Pre => format!("```\n{}\n```", escape_chars(text, "\\`")),
+                Kind::Link(url) => {
+                    format!("[{}]({})", escape_markdown(text), escape_chars(url, "\\)"))
+                }
+            },
+            Style::Html => match &self.kind {
+                Kind::Plain => escape_html(text),
+                Kind::Bold => format!("<b>{}</b>", escape_html(text)),
+                Kind::Italic => format!("<i>{}</i>", escape_html(text)),
+                Kind::Code => format!("<code>{}</code>", escape_html(text)),
+                Kind::Pre => format!("<pre>{}</pre>", escape_html(text)),
+                Kind::Link(url) => {
+                    format!("<a href=\"{}\">{}</a>", escape_html(url), escape_html(text))
+                }
+            },
+        }
+    }
+}
+
+/// Text with formatting, built from unescaped pieces and escaped only when rendered.
+///
+/// User controlled strings like token names can go in any piece without breaking the markup.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RichText {
+    spans: Vec<Span>,
+}
+
+impl RichText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&mut self, kind: Kind, text: impl Into<String>) -> &mut Self {
+        let text = text.into();
+        if !text.is_empty() {
+            self.spans.push(Span { kind, text });
+        }
+        self
+    }
+
+    pub fn plain(&mut self, text: impl Into<String>) -> &mut Self {
+        self.push(Kind::Plain, text)
+    }
+
+    pub fn bold(&mut self, text: impl Into<String>) -> &mut Self {
+        self.push(Kind::Bold, text)
+    }
+
+    pub fn italic(&mut self, text: impl Into<String>) -> &mut Self {
+        self.push(Kind::Italic, text)
+    }
+
+    pub fn code(&mut self, text: impl Into<String>) -> &mut Self {
+        self.push(Kind::Code, text)
+    }
+
+    /// Preformatted block, for tables
+    pub fn pre(&mut self, text: impl Into<String>) -> &mut Self {
+        self.push(Kind::Pre, text)
+    }
+
+    pub fn link(&mut self, text: impl Into<String>, url: impl Into<String>) -> &mut Self {
+        self.push(Kind::Link(url.into()), text)
+    }
+
+    /// Length of the text as Telegram counts it, without the markup
+    fn len(&self) -> usize {
+        self.spans.iter().map(|span| visible_len(&span.text)).sum()
+    }
+
+    /// Render in `style`, cutting the text to `max_len` visible characters with an ellipsis.
+    ///
+    /// A piece cut in half keeps its formatting, so the result always parses.
+    pub fn render(&self, style: Style, max_len: usize) -> String {
+        if self.len() <= max_len {
+            return self
+                .spans
+                .iter()
+                .map(|span| span.render(style, &span.text))
+                .collect();
+        }
+
+        let mut budget = max_len.saturating_sub(visible_len(ELLIPSIS));
+        let mut rendered = String::new();
+        for span in &self.spans {
+            let text = prefix(&span.text, budget);
+            if !text.is_empty() {
+                rendered.push_str(&span.render(style, text));
+            }
+            if text.len() < span.text.len() {
+                break;
+            }
+            budget -= visible_len(text);
+        }
+        rendered.push_str(ELLIPSIS);
+        rendered
+    }
+
+    /// Render to fit a photo caption
+    pub fn caption(&self, style: Style) -> String {
+        self.render(style, MAX_CAPTION_LEN)
+    }
+
+    /// Render to fit a text message
+    pub fn message(&self, style: Style) -> String {
+        self.render(style, MAX_MESSAGE_LEN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> RichText {
+        let mut text = RichText::new();
+        text.bold("Dog_Wif*Hat")
+            .plain(" (1.5x!) ")
+            .code("a`b\\c")
+            .plain(" ")
+            .link("Open [here]", "https://example.com/a_(b)")
+            .italic(" <tag> & co");
+        text
+    }
+
+    #[test]
+    fn test_escapes_markdown_v2() {
+        assert_eq!(
+            sample().render(Style::MarkdownV2, MAX_MESSAGE_LEN),
+            "*Dog\\_Wif\\*Hat* \\(1\\.5x\\!\\) `a\\`b\\\\c` [Open \\[here\\]](https://example.com/a_(b\\))_ <tag\\> & co_"
+        );
+        assert_eq!(
+            RichText::new()
+                .plain("back\\slash")
+                .render(Style::MarkdownV2, 100),
+            "back\\\\slash"
+        );
+    }
+
+    #[test]
+    fn test_escapes_html() {
+        assert_eq!(
+            sample().render(Style::Html, MAX_MESSAGE_LEN),
+            "<b>Dog_Wif*Hat</b> (1.5x!) <code>a`b\\c</code> <a href=\"https://example.com/a_(b)\">Open [here]</a><i> &lt;tag&gt; &amp; co</i>"
+        );
+    }
+
+    #[test]
+    fn test_truncates_visible_text() {
+        let text = sample();
+        assert_eq!(text.len(), 48);
+        assert_eq!(text.render(Style::Html, 48), text.render(Style::Html, 1000));
+
+        // The bold piece is cut but stays bold, nothing after it is kept
+        assert_eq!(text.render(Style::MarkdownV2, 5), "*Dog\\_*…");
+        assert_eq!(text.render(Style::Html, 12), "<b>Dog_Wif*Hat</b>…");
+        assert_eq!(text.render(Style::Html, 13), "<b>Dog_Wif*Hat</b> …");
+
+        // Characters outside the BMP count twice, and are never split
+        let mut emoji = RichText::new();
+        emoji.plain("🟣🟣🟣");
+        assert_eq!(emoji.len(), 6);
+        assert_eq!(emoji.render(Style::MarkdownV2, 4), "🟣…");
+
+        let mut long = RichText::new();
+        long.plain("x".repeat(5000));
+        assert_eq!(long.caption(Style::Html).chars().count(), MAX_CAPTION_LEN);
+        assert_eq!(long.message(Style::Html).chars().count(), MAX_MESSAGE_LEN);
+    }
+}
@@ -0,0 +1,45 @@
+use std::{
+    sync::atomic::{AtomicI64, Ordering},
+    time::Duration,
+};
+
+use entity::daily_lookup::Entity as DailyLookup;
+
+// Counts are kept in memory and written in batches instead of once per lookup
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+static PENDING_LOOKUPS: AtomicI64 = AtomicI64::new(0);
+
+/// Count a token lookup answered by the bot
+pub fn record_lookup() {
+    PENDING_LOOKUPS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Lookups counted but not stored yet
+pub fn pending_lookups() -> i64 {
+    PENDING_LOOKUPS.load(Ordering::Relaxed)
+}
+
+async fn flush() {
+    let lookups = PENDING_LOOKUPS.swap(0, Ordering::Relaxed);
+    if lookups == 0 {
+        return;
+    }
+
+    let db = entity::get_db().await;
+    let today = chrono::Utc::now().date_naive();
+    if let Err(err) = DailyLookup::add(db, today, lookups).await {
+        tracing::warn!("Failed to store {} lookups: {:?}", lookups, err);
+        // Keep them for the next flush
+        PENDING_LOOKUPS.fetch_add(lookups, Ordering::Relaxed);
+    }
+}
+
+/// Store the lookup counts every minute
+pub async fn run() {
+    let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+    loop {
+        interval.tick().await;
+        flush().await;
+    }
+}
@@ -0,0 +1,202 @@
+use std::{marker::PhantomData, sync::Arc, time::Duration};
+
+use entity::dialogue_state::Entity as DialogueState;
+use futures_util::future::BoxFuture;
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait};
+use serde::{de::DeserializeOwned, Serialize};
+use teloxide::{
+    dispatching::dialogue::{InMemStorage, InMemStorageError, Storage},
+    types::ChatId,
+};
+use utils::{config::DialogueStorageBackend, ENV_CONFIG};
+
+// How often abandoned dialogues are removed from the table
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, thiserror::Error)]
+pub enum SeaOrmStorageError {
+    #[error("database error: {0}")]
+    Database(#[from] DbErr),
+    #[error("dialogue (de)serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("row not found")]
+    DialogueNotFound,
+}
+
+/// Dialogue storage in the `dialogue_states` table, with states serialized as JSON.
+///
+/// Dialogues untouched for longer than `ttl` read as absent and are pruned in the background.
+pub struct SeaOrmStorage<D> {
+    db: DatabaseConnection,
+    ttl: chrono::Duration,
+    _dialogue: PhantomData<fn() -> D>,
+}
+
+impl<D> SeaOrmStorage<D>
+where
+    D: Send + 'static,
+{
+    pub fn new(db: DatabaseConnection, ttl: Duration) -> Arc<Self> {
+        let storage = Arc::new(Self {
+            db,
+            ttl: chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX),
+            _dialogue: PhantomData,
+        });
+        tokio::spawn(storage.clone().prune_expired());
+        storage
+    }
+
+    async fn prune_expired(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+        loop {
+            interval.tick().await;
+            // With a TTL reaching past the earliest date nothing can have expired yet
+            let Some(before) = chrono::Utc::now().checked_sub_signed(self.ttl) else {
+                continue;
+            };
+            match DialogueState::delete_expired(&self.db, before).await {
+                Ok(0) => {}
+                Ok(removed) => tracing::debug!("Pruned {} expired dialogues", removed),
+                Err(err) => tracing::warn!("Failed to prune expired dialogues: {:?}", err),
+            }
+        }
+    }
+}
+
+impl<D> Storage<D> for SeaOrmStorage<D>
+where
+    D: Serialize + DeserializeOwned + Send + 'static,
+{
+    type Error = SeaOrmStorageError;
+
+    fn remove_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+    ) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let result = DialogueState::delete_by_id(chat_id.0)
+                .exec(&self.db)
+                .await?;
+            if result.rows_affected == 0 {
+                return Err(SeaOrmStorageError::DialogueNotFound);
+            }
+            Ok(())
+        })
+    }
+
+    fn update_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+        dialogue: D,
+    ) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let state = serde_json::to_value(dialogue)?;
+            DialogueState::upsert(&self.db, chat_id.0, state).await?;
+            Ok(())
+        })
+    }
+
+    fn get_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+    ) -> BoxFuture<'static, Result<Option<D>, Self::Error>> {
+        Box::pin(async move {
+            let Some(model) = DialogueState::find_by_id(chat_id.0).one(&self.db).await? else {
+                return Ok(None);
+            };
+
+            if model.is_expired(chrono::Utc::now(), self.ttl) {
+                DialogueState::delete_by_id(chat_id.0)
+                    .exec(&self.db)
+                    .await?;
+                return Ok(None);
+            }
+
+            match serde_json::from_value(model.state) {
+                Ok(dialogue) => Ok(Some(dialogue)),
+                Err(err) => {
+                    // A state written by an older build, start the dialogue over
+                    tracing::warn!("Dropping unreadable dialogue of {}: {:?}", chat_id, err);
+                    Ok(None)
+                }
+            }
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DialogueStorageError {
+    #[error(transparent)]
+    Memory(#[from] InMemStorageError),
+    #[error(transparent)]
+    Postgres(#[from] SeaOrmStorageError),
+}
+
+/// The dialogue storage backend selected by `DIALOGUE_STORAGE`
+pub enum DialogueStorage<D> {
+    Memory(Arc<InMemStorage<D>>),
+    Postgres(Arc<SeaOrmStorage<D>>),
+}
+
+impl<D> DialogueStorage<D>
+where
+    D: Send + 'static,
+{
+    pub async fn from_config() -> Arc<Self> {
+        Arc::new(match ENV_CONFIG.dialogue_storage {
+            DialogueStorageBackend::Memory => Self::Memory(InMemStorage::new()),
+            DialogueStorageBackend::Postgres => Self::Postgres(SeaOrmStorage::new(
+                entity::get_db().await.clone(),
+                Duration::from_secs(ENV_CONFIG.dialogue_ttl_secs),
+            )),
+        })
+    }
+}
+
+impl<D> Storage<D> for DialogueStorage<D>
+where
+    D: Clone + Serialize + DeserializeOwned + Send + 'static,
+{
+    type Error = DialogueStorageError;
+
+    fn remove_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+    ) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            match &*self {
+                Self::Memory(storage) => storage.clone().remove_dialogue(chat_id).await?,
+                Self::Postgres(storage) => storage.clone().remove_dialogue(chat_id).await?,
+            }
+            Ok(())
+        })
+    }
+
+    fn update_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+        dialogue: D,
+    ) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            match &*self {
+                Self::Memory(storage) => storage.clone().update_dialogue(chat_id, dialogue).await?,
+                Self::Postgres(storage) => {
+                    storage.clone().update_dialogue(chat_id, dialogue).await?
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn get_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+    ) -> BoxFuture<'static, Result<Option<D>, Self::Error>> {
+        Box::pin(async move {
+            Ok(match &*self {
+                Self::Memory(storage) => storage.clone().get_dialogue(chat_id).await?,
+                Self::Postgres(storage) => storage.clone().get_dialogue(chat_id).await?,
+            })
+        })
+    }
+}
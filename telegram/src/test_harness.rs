@@ -0,0 +1,404 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicI32, AtomicI64, Ordering},
+        Arc, LazyLock, Mutex, Once,
+    },
+};
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use entity::test_support::{migrate, IN_MEMORY_DATABASE_URL};
+use serde_json::{json, Map, Value};
+use teloxide::{dispatching::dialogue::InMemStorage, prelude::*, types::Me};
+use tokio::runtime::Runtime;
+use url::Url;
+use utils::ENV_CONFIG;
+
+use crate::{handle_unhandled, schema, storage::DialogueStorage, GlobalState};
+
+// What the handler tests in `tests/` need from the private modules
+pub use crate::{
+    card::{encode_card_action, CardAction, CardPeriod},
+    i18n::{translate, Lang},
+};
+
+const BOT_TOKEN: &str = "123456:TEST";
+const BOT_ID: i64 = 123456;
+
+// The database pool and the mock Vybe server live as long as the runtime they were created
+// on, so every harness test runs on this one
+static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+});
+
+// Token details served by the mock Vybe API by mint
+static VYBE_TOKENS: LazyLock<Mutex<HashMap<String, Value>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static VYBE_URL: LazyLock<String> = LazyLock::new(|| {
+    let router = Router::new().route("/token/:mint", get(vybe_token));
+    format!("http://{}", RUNTIME.block_on(serve(router)))
+});
+
+static NEXT_USER_ID: LazyLock<AtomicI64> =
+    LazyLock::new(|| AtomicI64::new(chrono::Utc::now().timestamp_micros()));
+
+async fn serve(router: Router) -> std::net::SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    address
+}
+
+async fn vybe_token(Path(mint): Path<String>) -> Result<Json<Value>, StatusCode> {
+    VYBE_TOKENS
+        .lock()
+        .unwrap()
+        .get(&mint)
+        .cloned()
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Serve `details` from the mock Vybe API under its `mintAddress`
+pub fn mock_token(details: Value) {
+    let mint = details["mintAddress"].as_str().unwrap().to_string();
+    VYBE_TOKENS.lock().unwrap().insert(mint, details);
+}
+
+/// Token details as Vybe returns them
+pub fn token_json(mint_address: &str, symbol: &str, logo_url: Option<&str>) -> Value {
+    json!({
+        "symbol": symbol,
+        "name": symbol,
+        "mintAddress": mint_address,
+        "price": 0.00002,
+        "price1d": 0.000018,
+        "price7d": 0.000025,
+        "decimal": 5,
+        "verified": true,
+        "updateTime": 1718000000,
+        "currentSupply": 88_000_000_000_000.0,
+        "marketCap": 1_760_000_000.0,
+        "category": null,
+        "logoUrl": logo_url,
+        "subcategory": null,
+        "tokenAmountVolume24h": 1_000_000_000.0,
+        "usdValueVolume24h": 20_000.0,
+    })
+}
+
+/// Run a harness test on the shared runtime, with the environment pointed at the mocks and
+/// an in-memory database
+pub fn run<F: Future<Output = ()>>(test: F) {
+    static ENV: Once = Once::new();
+    ENV.call_once(|| {
+        std::env::set_var("TELOXIDE_TOKEN", BOT_TOKEN);
+        std::env::set_var("TELEGRAM_HOOK_URL", "http://localhost");
+        std::env::set_var("VIBE_API_KEY", "test");
+        std::env::set_var("VYBE_API_URL", VYBE_URL.as_str());
+        std::env::set_var("DATABASE_URL", IN_MEMORY_DATABASE_URL);
+        RUNTIME.block_on(async { migrate(entity::get_db().await).await });
+    });
+    assert_eq!(
+        ENV_CONFIG.vybe_api_url, *VYBE_URL,
+        "the environment was loaded before the harness set it up"
+    );
+
+    RUNTIME.block_on(test);
+}
+
+/// A Telegram user id no other test uses, so rate limits and rows don't collide
+pub fn unique_user_id() -> i64 {
+    NEXT_USER_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A request the bot sent to the Bot API
+#[derive(Debug, Clone)]
+pub struct ApiCall {
+    pub method: String,
+    pub params: Map<String, Value>,
+}
+
+#[derive(Clone, Default)]
+struct MockBotApi {
+    calls: Arc<Mutex<Vec<ApiCall>>>,
+    next_message_id: Arc<AtomicI32>,
+}
+
+fn chat_json(chat_id: i64) -> Value {
+    if chat_id < 0 {
+        json!({ "id": chat_id, "type": "supergroup", "title": "Test group" })
+    } else {
+        json!({ "id": chat_id, "type": "private", "first_name": "Test" })
+    }
+}
+
+fn user_json(user_id: i64) -> Value {
+    json!({
+        "id": user_id,
+        "is_bot": false,
+        "first_name": "Test",
+        "language_code": "en",
+    })
+}
+
+fn me_json() -> Value {
+    json!({
+        "id": BOT_ID,
+        "is_bot": true,
+        "first_name": "Pixa",
+        "username": "pixa_bot",
+        "can_join_groups": true,
+        "can_read_all_group_messages": false,
+        "supports_inline_queries": true,
+        "can_connect_to_business": false,
+        "has_main_web_app": false,
+    })
+}
+
+fn message_json(message_id: i32, chat_id: i64, from: Value, text: &str) -> Value {
+    json!({
+        "message_id": message_id,
+        "date": 1718000000,
+        "chat": chat_json(chat_id),
+        "from": from,
+        "text": text,
+    })
+}
+
+// What users send, commands are marked with an entity like Telegram does
+fn user_message_json(chat_id: i64, user_id: i64, text: &str) -> Value {
+    let mut message = message_json(1, chat_id, user_json(user_id), text);
+    if text.starts_with('/') {
+        let command = text.split_whitespace().next().unwrap_or_default();
+        message["entities"] = json!([{
+            "type": "bot_command",
+            "offset": 0,
+            "length": command.encode_utf16().count(),
+        }]);
+    }
+    message
+}
+
+// Multipart values are all text, the JSON encoded ones like `reply_markup` are decoded
+fn form_value(value: &str) -> Value {
+    match serde_json::from_str::<Value>(value) {
+        Ok(value @ (Value::Object(_) | Value::Array(_) | Value::Number(_) | Value::Bool(_))) => {
+            value
+        }
+        _ => Value::String(value.to_string()),
+    }
+}
+
+// Requests with files, like `sendPhoto`, come as `multipart/form-data`
+fn parse_form(body: &str, boundary: &str) -> Map<String, Value> {
+    body.split(&format!("--{}", boundary))
+        .filter_map(|part| {
+            let (headers, value) = part.split_once("\r\n\r\n")?;
+            let name = headers.split("name=\"").nth(1)?.split('"').next()?;
+            let value = value.strip_suffix("\r\n").unwrap_or(value);
+            Some((name.to_string(), form_value(value)))
+        })
+        .collect()
+}
+
+async fn bot_api(
+    State(api): State<MockBotApi>,
+    Path((_token, method)): Path<(String, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Json<Value> {
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let params = match content_type.split_once("boundary=") {
+        Some((_, boundary)) => parse_form(&String::from_utf8_lossy(&body), boundary),
+        None => serde_json::from_slice(&body).unwrap_or_default(),
+    };
+
+    // Method names are case insensitive, teloxide sends `SendMessage` for `sendMessage`
+    let mut chars = method.chars();
+    let method = chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default();
+
+    let chat_id = params.get("chat_id").and_then(Value::as_i64).unwrap_or(0);
+    let text = ["text", "caption"]
+        .into_iter()
+        .find_map(|key| params.get(key).and_then(Value::as_str))
+        .unwrap_or_default()
+        .to_string();
+    api.calls.lock().unwrap().push(ApiCall {
+        method: method.clone(),
+        params,
+    });
+
+    let message_id = api.next_message_id.fetch_add(1, Ordering::Relaxed) + 1;
+    let result = match method.as_str() {
+        "getMe" => me_json(),
+        "getChatMember" => {
+            json!({ "status": "creator", "user": user_json(0), "is_anonymous": false })
+        }
+        "sendMessage" | "editMessageText" | "editMessageCaption" | "editMessageReplyMarkup" => {
+            message_json(message_id, chat_id, me_json(), &text)
+        }
+        "sendPhoto" => {
+            let mut message = message_json(message_id, chat_id, me_json(), "");
+            let message = message.as_object_mut().unwrap();
+            message.remove("text");
+            message.insert("caption".to_string(), Value::String(text));
+            message.insert(
+                "photo".to_string(),
+                json!([{
+                    "file_id": format!("photo-{}", message_id),
+                    "file_unique_id": format!("unique-{}", message_id),
+                    "width": 320,
+                    "height": 320,
+                }]),
+            );
+            Value::Object(message.clone())
+        }
+        _ => Value::Bool(true),
+    };
+    Json(json!({ "ok": true, "result": result }))
+}
+
+/// The bot's handlers wired to a mock Bot API that records every request
+pub struct TestBot {
+    bot: Bot,
+    me: Me,
+    storage: Arc<DialogueStorage<GlobalState>>,
+    api: MockBotApi,
+}
+
+impl TestBot {
+    pub async fn new() -> Self {
+        let api = MockBotApi::default();
+        let router = Router::new()
+            .route("/:token/:method", post(bot_api))
+            .with_state(api.clone());
+        let address = serve(router).await;
+        let api_url = Url::parse(&format!("http://{}", address)).unwrap();
+
+        Self {
+            bot: Bot::new(BOT_TOKEN).set_api_url(api_url),
+            me: serde_json::from_value(me_json()).unwrap(),
+            storage: Arc::new(DialogueStorage::Memory(InMemStorage::new())),
+            api,
+        }
+    }
+
+    /// Run an update through the handlers like the dispatcher does, panicking on handler errors
+    pub async fn dispatch(&self, update: Update) {
+        let deps = dptree::deps![
+            self.bot.clone(),
+            self.me.clone(),
+            update.clone(),
+            self.storage.clone()
+        ];
+        match schema().dispatch(deps).await {
+            ControlFlow::Break(Ok(())) => {}
+            ControlFlow::Break(Err(err)) => panic!("Handler failed: {:?}", err),
+            ControlFlow::Continue(_) => handle_unhandled(self.bot.clone(), &update).await,
+        }
+    }
+
+    /// A user writes `text` in their private chat with the bot
+    pub async fn send_text(&self, user_id: i64, text: &str) {
+        self.send_text_in(user_id, user_id, text).await;
+    }
+
+    /// A user writes `text` in a chat, groups have negative ids
+    pub async fn send_text_in(&self, chat_id: i64, user_id: i64, text: &str) {
+        let message = user_message_json(chat_id, user_id, text);
+        self.dispatch(update_json(json!({ "message": message })))
+            .await;
+    }
+
+    /// A user presses a button with `data` below a message of the bot in their private chat
+    pub async fn press_button(&self, user_id: i64, data: &str) {
+        self.press_button_in(user_id, user_id, data).await;
+    }
+
+    /// A user presses a button with `data` below a message of the bot in a chat
+    pub async fn press_button_in(&self, chat_id: i64, user_id: i64, data: &str) {
+        let query = json!({
+            "id": "1",
+            "from": user_json(user_id),
+            "chat_instance": "1",
+            "data": data,
+            "message": message_json(1, chat_id, me_json(), "card"),
+        });
+        self.dispatch(update_json(json!({ "callback_query": query })))
+            .await;
+    }
+
+    /// Every request made with `method` so far
+    pub fn calls(&self, method: &str) -> Vec<ApiCall> {
+        self.api
+            .calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|call| call.method == method)
+            .cloned()
+            .collect()
+    }
+
+    /// Texts of every message sent so far
+    pub fn sent_texts(&self) -> Vec<String> {
+        self.calls("sendMessage")
+            .into_iter()
+            .filter_map(|call| call.params["text"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    /// The dialogue state of a chat, `None` before any dialogue was stored
+    pub async fn state(&self, chat_id: i64) -> Option<GlobalState> {
+        use teloxide::dispatching::dialogue::Storage;
+
+        self.storage
+            .clone()
+            .get_dialogue(ChatId(chat_id))
+            .await
+            .unwrap()
+    }
+}
+
+fn update_json(kind: Value) -> Update {
+    let mut update = json!({ "update_id": 1 });
+    update
+        .as_object_mut()
+        .unwrap()
+        .extend(kind.as_object().unwrap().clone());
+    // The update kind only deserializes from text, a `Value` turns it into `UpdateKind::Error`
+    serde_json::from_str(&update.to_string()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_form() {
+        let body = "--b\r\nContent-Disposition: form-data; name=\"chat_id\"\r\n\r\n42\r\n\
+                    --b\r\nContent-Disposition: form-data; name=\"caption\"\r\n\r\n*Bonk*\r\n--b--\r\n";
+        let form = parse_form(body, "b");
+        assert_eq!(form["chat_id"], json!(42));
+        assert_eq!(form["caption"], json!("*Bonk*"));
+    }
+}
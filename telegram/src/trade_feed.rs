@@ -0,0 +1,471 @@
+use std::{
+    collections::HashMap,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use aggregator::Trade;
+use entity::trade_follow::{self, Entity as TradeFollow};
+use sea_orm::EntityTrait;
+use teloxide::{prelude::*, ApiError, RequestError};
+use tokio::sync::{broadcast::error::RecvError, Notify};
+use utils::number::{format_compact_price, format_long_number};
+
+use crate::{
+    card::CARD_PARSE_MODE,
+    card_sender::NO_LINK_PREVIEW,
+    commands::{holders::short_address, watchlist::sync_watched_mints},
+    i18n::{stored_user_lang, t, Lang},
+    rich_text::{RichText, Style},
+};
+
+// Telegram allows about 20 messages a minute in a group, so a chat gets one batch every 3 seconds
+const FLUSH_INTERVAL: Duration = Duration::from_secs(3);
+
+// Trades past this many in one batch are only counted
+const MAX_LINES_PER_BATCH: usize = 15;
+
+// A chat getting more trades than this in a minute is flooding and its feed is suspended
+const FLOOD_WINDOW: Duration = Duration::from_secs(60);
+const MAX_TRADES_PER_WINDOW: usize = 120;
+
+// How long a flooding chat stays suspended
+const SUSPENSION_MINUTES: i64 = 30;
+
+// How often follows are reloaded, which also resumes suspended feeds
+const RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+static FOLLOWS_CHANGED: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Ask the feed to reload follows after they were created or removed
+pub fn follows_changed() {
+    FOLLOWS_CHANGED.notify_one();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+/// One trade of a followed token as shown in the feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedLine {
+    pub side: Side,
+    /// Tokens traded
+    pub amount: f64,
+    pub symbol: String,
+    /// USD value of the trade
+    pub usd: f64,
+    /// USD price of the token
+    pub price: f64,
+    pub wallet: String,
+    pub signature: String,
+}
+
+/// The feed line of a trade of `mint_address`, `None` when the trade can't be priced in USD
+pub fn feed_line(trade: &Trade, mint_address: &str, symbol: &str) -> Option<FeedLine> {
+    let (priced_mint, price) = aggregator::usd_price(trade)?;
+    if priced_mint != mint_address {
+        return None;
+    }
+
+    let (side, amount) = match (trade.bought(), trade.sold()) {
+        ((bought_mint, amount), _) if bought_mint == mint_address => (Side::Buy, amount),
+        (_, (sold_mint, amount)) if sold_mint == mint_address => (Side::Sell, amount),
+        _ => return None,
+    };
+
+    Some(FeedLine {
+        side,
+        amount,
+        symbol: symbol.to_string(),
+        usd: amount * price,
+        price,
+        wallet: trade.fee_payer.clone(),
+        signature: trade.signature.clone(),
+    })
+}
+
+fn solscan_tx_url(signature: &str) -> String {
+    format!("https://solscan.io/tx/{}", signature)
+}
+
+/// A batch of feed lines as one MarkdownV2 message, `skipped` trades didn't fit in the batch
+pub fn render_batch(lang: Lang, lines: &[FeedLine], skipped: usize) -> String {
+    let mut text = RichText::new();
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            text.plain("\n");
+        }
+        let marker = match line.side {
+            Side::Buy => "🟢",
+            Side::Sell => "🔴",
+        };
+        text.plain(format!("{} ", marker))
+            .bold(format!(
+                "${}",
+                lang.number(&format_long_number(line.usd.round()))
+            ))
+            .plain(format!(
+                " · {} {} @ ${} · {} · ",
+                lang.number(&format_long_number(line.amount.round())),
+                line.symbol,
+                lang.number(&format_compact_price(line.price)),
+                short_address(&line.wallet)
+            ))
+            .link("tx", solscan_tx_url(&line.signature));
+    }
+    if skipped > 0 {
+        text.plain("\n")
+            .italic(t!(lang, "follow-skipped", count = skipped));
+    }
+    text.message(Style::MarkdownV2)
+}
+
+/// Outcome of queueing a trade for a chat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Queued {
+    Queued,
+    /// Too many trades in the last minute, the chat's queue was dropped
+    Flooding,
+}
+
+#[derive(Debug)]
+struct ChatQueue {
+    lines: Vec<FeedLine>,
+    skipped: usize,
+    next_flush: Instant,
+    window_start: Instant,
+    window_trades: usize,
+}
+
+/// Per chat queues of feed lines, sent as one message per chat every [`FLUSH_INTERVAL`].
+///
+/// Methods take the current time so tests control the clock.
+#[derive(Debug, Default)]
+pub struct FeedBatcher {
+    chats: HashMap<i64, ChatQueue>,
+}
+
+impl FeedBatcher {
+    pub fn push(&mut self, chat_id: i64, line: FeedLine, now: Instant) -> Queued {
+        let queue = self.chats.entry(chat_id).or_insert_with(|| ChatQueue {
+            lines: Vec::new(),
+            skipped: 0,
+            next_flush: now,
+            window_start: now,
+            window_trades: 0,
+        });
+
+        if now.saturating_duration_since(queue.window_start) >= FLOOD_WINDOW {
+            queue.window_start = now;
+            queue.window_trades = 0;
+        }
+        queue.window_trades += 1;
+        if queue.window_trades > MAX_TRADES_PER_WINDOW {
+            self.chats.remove(&chat_id);
+            return Queued::Flooding;
+        }
+
+        if queue.lines.len() < MAX_LINES_PER_BATCH {
+            queue.lines.push(line);
+        } else {
+            queue.skipped += 1;
+        }
+        Queued::Queued
+    }
+
+    /// Take the batches of every chat that may be sent to at `now`
+    pub fn take_due(&mut self, now: Instant) -> Vec<(i64, Vec<FeedLine>, usize)> {
+        // Quiet chats are forgotten once their flood window is over
+        self.chats.retain(|_, queue| {
+            !queue.lines.is_empty()
+                || now.saturating_duration_since(queue.window_start) < FLOOD_WINDOW
+        });
+
+        self.chats
+            .iter_mut()
+            .filter(|(_, queue)| !queue.lines.is_empty() && queue.next_flush <= now)
+            .map(|(chat_id, queue)| {
+                queue.next_flush = now + FLUSH_INTERVAL;
+                (
+                    *chat_id,
+                    std::mem::take(&mut queue.lines),
+                    std::mem::take(&mut queue.skipped),
+                )
+            })
+            .collect()
+    }
+
+    /// Hold back the next batch of a chat, when Telegram asked to retry later
+    pub fn delay(&mut self, chat_id: i64, until: Instant) {
+        if let Some(queue) = self.chats.get_mut(&chat_id) {
+            queue.next_flush = queue.next_flush.max(until);
+        }
+    }
+}
+
+type FollowsByMint = HashMap<String, Vec<trade_follow::Model>>;
+
+async fn load_follows() -> FollowsByMint {
+    let db = entity::get_db().await;
+    let now = chrono::Utc::now();
+    let mut follows = FollowsByMint::new();
+
+    match TradeFollow::find().all(db).await {
+        Ok(models) => {
+            for follow in models
+                .into_iter()
+                .filter(|follow| !follow.is_suspended(now))
+            {
+                follows
+                    .entry(follow.mint_address.clone())
+                    .or_default()
+                    .push(follow);
+            }
+        }
+        Err(err) => tracing::error!("Failed to load trade follows: {:?}", err),
+    }
+
+    follows
+}
+
+// Language of a chat's feed, the one of whoever followed first
+fn chat_follower(follows: &FollowsByMint, chat_id: i64) -> Option<i64> {
+    follows
+        .values()
+        .flatten()
+        .filter(|follow| follow.chat_id == chat_id)
+        .min_by_key(|follow| follow.id)
+        .map(|follow| follow.created_by)
+}
+
+async fn suspend(bot: &Bot, chat_id: i64, lang: Lang) {
+    let until = chrono::Utc::now() + chrono::Duration::minutes(SUSPENSION_MINUTES);
+    if let Err(err) = TradeFollow::suspend_chat(entity::get_db().await, chat_id, until).await {
+        tracing::error!("Failed to suspend the trade feed of {}: {:?}", chat_id, err);
+    }
+
+    let text = t!(lang, "follow-suspended", minutes = SUSPENSION_MINUTES);
+    if let Err(err) = bot.send_message(ChatId(chat_id), text).await {
+        tracing::warn!(
+            "Failed to tell {} its feed is suspended: {:?}",
+            chat_id,
+            err
+        );
+    }
+}
+
+// Send a batch, returns whether the chat is gone and its follows should be dropped
+async fn send_batch(bot: &Bot, batcher: &mut FeedBatcher, chat_id: i64, text: String) -> bool {
+    let result = bot
+        .send_message(ChatId(chat_id), text)
+        .parse_mode(CARD_PARSE_MODE)
+        .link_preview_options(NO_LINK_PREVIEW)
+        .await;
+
+    match result {
+        Ok(_) => false,
+        Err(RequestError::RetryAfter(wait)) => {
+            batcher.delay(chat_id, Instant::now() + wait.duration());
+            false
+        }
+        Err(RequestError::Api(
+            ApiError::BotBlocked
+            | ApiError::BotKicked
+            | ApiError::BotKickedFromSupergroup
+            | ApiError::ChatNotFound,
+        )) => true,
+        Err(err) => {
+            tracing::warn!("Failed to send trade feed to {}: {:?}", chat_id, err);
+            false
+        }
+    }
+}
+
+/// Stream trades of followed tokens into their chats, restoring follows from the database
+pub async fn run(bot: Bot) {
+    let mut trades = aggregator::subscribe();
+    let mut flush = tokio::time::interval(Duration::from_secs(1));
+    let mut reload = tokio::time::interval(RELOAD_INTERVAL);
+    let mut follows = load_follows().await;
+    let mut batcher = FeedBatcher::default();
+
+    loop {
+        tokio::select! {
+            trade = trades.recv() => match trade {
+                Ok(trade) => {
+                    let mut flooding = Vec::new();
+                    for mint in [&trade.base_mint_address, &trade.quote_mint_address] {
+                        for follow in follows.get(mint).into_iter().flatten() {
+                            let symbol = follow
+                                .symbol
+                                .clone()
+                                .unwrap_or_else(|| short_address(mint));
+                            let Some(line) = feed_line(&trade, mint, &symbol) else {
+                                continue;
+                            };
+                            if line.usd >= follow.min_usd
+                                && batcher.push(follow.chat_id, line, Instant::now())
+                                    == Queued::Flooding
+                            {
+                                flooding.push((follow.chat_id, follow.created_by));
+                            }
+                        }
+                    }
+
+                    for (chat_id, follower) in flooding {
+                        suspend(&bot, chat_id, stored_user_lang(follower).await).await;
+                        follows
+                            .values_mut()
+                            .for_each(|mint_follows| mint_follows.retain(|follow| follow.chat_id != chat_id));
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Trade feed lagged, skipped {} trades", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            _ = flush.tick() => {
+                let mut gone = Vec::new();
+                for (chat_id, lines, skipped) in batcher.take_due(Instant::now()) {
+                    let lang = match chat_follower(&follows, chat_id) {
+                        Some(follower) => stored_user_lang(follower).await,
+                        None => Lang::default(),
+                    };
+                    if send_batch(&bot, &mut batcher, chat_id, render_batch(lang, &lines, skipped)).await {
+                        gone.push(chat_id);
+                    }
+                }
+
+                if !gone.is_empty() {
+                    let db = entity::get_db().await;
+                    for chat_id in gone {
+                        tracing::info!("Dropping the trade feeds of unreachable chat {}", chat_id);
+                        if let Err(err) = TradeFollow::unfollow(db, chat_id, None).await {
+                            tracing::error!("Failed to drop follows of {}: {:?}", chat_id, err);
+                        }
+                    }
+                    follows = load_follows().await;
+                    sync_watched_mints().await;
+                }
+            }
+            _ = reload.tick() => {
+                follows = load_follows().await;
+            }
+            _ = FOLLOWS_CHANGED.notified() => {
+                follows = load_follows().await;
+            }
+        }
+
+        follows.retain(|_, mint_follows| !mint_follows.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aggregator::test_support::trade;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn line(side: Side, usd: f64) -> FeedLine {
+        FeedLine {
+            side,
+            amount: 25_000_000.0,
+            symbol: "Bonk".to_string(),
+            usd,
+            price: 0.0000213,
+            wallet: "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM".to_string(),
+            signature: "5sig".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_feed_line() {
+        let usdc = aggregator::USDC_MINT;
+        let buy = feed_line(&trade(BONK, "1000000", usdc, "20"), BONK, "Bonk").unwrap();
+        assert_eq!(buy.side, Side::Buy);
+        assert_eq!(buy.amount, 1_000_000.0);
+        assert!((buy.usd - 20.0).abs() < 1e-9);
+
+        // Selling puts the token on the quote side, the sizes stay unsigned
+        let sell = feed_line(&trade(usdc, "20", BONK, "1000000"), BONK, "Bonk").unwrap();
+        assert_eq!(sell.side, Side::Sell);
+        assert_eq!(sell.amount, 1_000_000.0);
+        assert!((sell.usd - 20.0).abs() < 1e-9);
+
+        // The stable side is not what is being followed
+        assert_eq!(
+            feed_line(&trade(BONK, "1000000", usdc, "20"), usdc, "USDC"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_render_batch() {
+        let text = render_batch(
+            Lang::En,
+            &[line(Side::Buy, 532.6), line(Side::Sell, 12.0)],
+            3,
+        );
+        assert_eq!(
+            text,
+            "🟢 *$533* · 25M Bonk @ $0\\.0000213 · 9WzD…AWWM · [tx](https://solscan.io/tx/5sig)\n\
+             🔴 *$12* · 25M Bonk @ $0\\.0000213 · 9WzD…AWWM · [tx](https://solscan.io/tx/5sig)\n\
+             _\\+3 more trades_"
+        );
+    }
+
+    #[test]
+    fn test_batches_are_throttled_and_capped() {
+        let start = Instant::now();
+        let mut batcher = FeedBatcher::default();
+
+        for _ in 0..MAX_LINES_PER_BATCH + 2 {
+            assert_eq!(batcher.push(1, line(Side::Buy, 1.0), start), Queued::Queued);
+        }
+        let batches = batcher.take_due(start);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].1.len(), MAX_LINES_PER_BATCH);
+        assert_eq!(batches[0].2, 2);
+
+        // The next batch waits for the flush interval
+        batcher.push(1, line(Side::Buy, 1.0), start);
+        assert!(batcher.take_due(start + Duration::from_secs(1)).is_empty());
+        assert_eq!(batcher.take_due(start + FLUSH_INTERVAL).len(), 1);
+
+        // Telegram asking to retry later holds the chat back further
+        batcher.push(1, line(Side::Buy, 1.0), start + FLUSH_INTERVAL);
+        batcher.delay(1, start + Duration::from_secs(30));
+        assert!(batcher.take_due(start + FLUSH_INTERVAL * 2).is_empty());
+        assert_eq!(batcher.take_due(start + Duration::from_secs(30)).len(), 1);
+    }
+
+    #[test]
+    fn test_flooding_chat_is_dropped() {
+        let start = Instant::now();
+        let mut batcher = FeedBatcher::default();
+
+        // The window counts every trade, whether it fit in a batch or not
+        for _ in 0..MAX_TRADES_PER_WINDOW {
+            assert_eq!(batcher.push(1, line(Side::Buy, 1.0), start), Queued::Queued);
+        }
+        assert_eq!(
+            batcher.push(1, line(Side::Buy, 1.0), start),
+            Queued::Flooding
+        );
+        assert!(batcher.take_due(start).is_empty());
+
+        // A new window starts a minute later
+        let mut batcher = FeedBatcher::default();
+        for _ in 0..MAX_TRADES_PER_WINDOW {
+            batcher.push(2, line(Side::Buy, 1.0), start);
+        }
+        assert_eq!(
+            batcher.push(2, line(Side::Buy, 1.0), start + FLOOD_WINDOW),
+            Queued::Queued
+        );
+    }
+}
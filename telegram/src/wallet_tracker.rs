@@ -0,0 +1,426 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use aggregator::{Trade, SOL_MINT, USDC_MINT, USDT_MINT};
+use entity::tracked_wallet::{self, Entity as TrackedWallet};
+use sea_orm::EntityTrait;
+use teloxide::prelude::*;
+use tokio::sync::{broadcast::error::RecvError, Notify};
+use utils::number::{format_compact_price, format_long_number};
+
+use crate::{
+    cache,
+    card::CARD_PARSE_MODE,
+    card_sender::NO_LINK_PREVIEW,
+    commands::holders::short_address,
+    i18n::{stored_user_lang, t, Lang},
+    rich_text::{RichText, Style},
+};
+
+// Legs of a multi-hop swap arrive back to back, a swap is reported once this long passed
+// since its first leg
+const SETTLE_DELAY: Duration = Duration::from_secs(2);
+
+// Net flows this small next to what went through a mint are the middle of a route
+const ROUTE_TOLERANCE: f64 = 1e-6;
+
+static TRACKED_CHANGED: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Ask the tracker to reload tracked wallets after they were added or removed
+pub fn tracked_changed() {
+    TRACKED_CHANGED.notify_one();
+}
+
+/// What a tracked wallet did in one transaction, every leg of a route netted together
+#[derive(Debug, Clone, PartialEq)]
+pub struct Swap {
+    pub wallet: String,
+    pub signature: String,
+    /// Mint and amount that left the wallet
+    pub sold: (String, f64),
+    /// Mint and amount that reached the wallet
+    pub bought: (String, f64),
+    /// USD value of the swap, when one side has a known price
+    pub usd: Option<f64>,
+}
+
+fn is_quote_token(mint: &str) -> bool {
+    mint == SOL_MINT || mint == USDC_MINT || mint == USDT_MINT
+}
+
+/// Net the legs of one transaction into a single swap, `None` when nothing left or reached
+/// the wallet in the end.
+///
+/// What each leg bought counts in and what it sold counts out, so intermediate tokens of a
+/// route cancel out.
+pub fn summarize(legs: &[Trade], usd_price: impl Fn(&str) -> Option<f64>) -> Option<Swap> {
+    let first = legs.first()?;
+    // Mint to (net amount, amount traded through it)
+    let mut flows = BTreeMap::<&str, (f64, f64)>::new();
+    for leg in legs {
+        let (bought_mint, bought_amount) = leg.bought();
+        let (sold_mint, sold_amount) = leg.sold();
+        for (mint, size) in [(bought_mint, bought_amount), (sold_mint, -sold_amount)] {
+            let flow = flows.entry(mint).or_default();
+            flow.0 += size;
+            flow.1 += size.abs();
+        }
+    }
+
+    let net = flows
+        .into_iter()
+        .filter(|(_, (net, gross))| net.abs() > gross * ROUTE_TOLERANCE)
+        .map(|(mint, (net, _))| (mint, net))
+        .collect::<Vec<_>>();
+    // Splits over several tokens are reported by their largest side
+    let largest = |bought: bool| {
+        net.iter()
+            .filter(|(_, net)| (*net > 0.0) == bought)
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .map(|(mint, net)| (mint.to_string(), net.abs()))
+    };
+    let sold = largest(false)?;
+    let bought = largest(true)?;
+
+    let usd = [&sold, &bought]
+        .into_iter()
+        .find_map(|(mint, amount)| usd_price(mint).map(|price| price * amount));
+
+    Some(Swap {
+        wallet: first.fee_payer.clone(),
+        signature: first.signature.clone(),
+        sold,
+        bought,
+        usd,
+    })
+}
+
+fn stream_usd_price(mint: &str) -> Option<f64> {
+    if mint == USDC_MINT || mint == USDT_MINT {
+        return Some(1.0);
+    }
+    aggregator::latest_price(mint).map(|price| price.usd)
+}
+
+fn token_symbol(mint: &str) -> String {
+    match mint {
+        SOL_MINT => "SOL".to_string(),
+        USDC_MINT => "USDC".to_string(),
+        USDT_MINT => "USDT".to_string(),
+        _ => cache::cached_token_details(mint)
+            .map(|details| details.symbol)
+            .unwrap_or_else(|| short_address(mint)),
+    }
+}
+
+fn format_amount(lang: Lang, amount: f64) -> String {
+    let amount = if amount >= 1.0 {
+        format_long_number(amount)
+    } else {
+        format_compact_price(amount)
+    };
+    lang.number(&amount)
+}
+
+/// A swap notification as MarkdownV2, named after the wallet's label
+pub fn render_swap(
+    lang: Lang,
+    label: &str,
+    swap: &Swap,
+    symbol: impl Fn(&str) -> String,
+) -> String {
+    let (sold_mint, sold_amount) = &swap.sold;
+    let (bought_mint, bought_amount) = &swap.bought;
+    let sold_amount = format_amount(lang, *sold_amount);
+    let bought_amount = format_amount(lang, *bought_amount);
+
+    // Paying with SOL or a stable is a buy, receiving them a sell
+    let (marker, action) = if is_quote_token(sold_mint) {
+        let action = t!(
+            lang,
+            "track-bought",
+            sold_amount = sold_amount,
+            sold_token = symbol(sold_mint),
+            bought_amount = bought_amount,
+            bought_token = symbol(bought_mint)
+        );
+        ("🟢", action)
+    } else if is_quote_token(bought_mint) {
+        let action = t!(
+            lang,
+            "track-sold",
+            sold_amount = sold_amount,
+            sold_token = symbol(sold_mint),
+            bought_amount = bought_amount,
+            bought_token = symbol(bought_mint)
+        );
+        ("🔴", action)
+    } else {
+        let action = t!(
+            lang,
+            "track-swapped",
+            sold_amount = sold_amount,
+            sold_token = symbol(sold_mint),
+            bought_amount = bought_amount,
+            bought_token = symbol(bought_mint)
+        );
+        ("🔄", action)
+    };
+
+    let mut text = RichText::new();
+    text.plain(format!("{} ", marker))
+        .bold(label)
+        .plain(format!(" {}", action));
+    if let Some(usd) = swap.usd {
+        text.plain(format!(
+            " · ~${}",
+            lang.number(&format_long_number(usd.round()))
+        ));
+    }
+    text.plain(" · ")
+        .link("tx", format!("https://solscan.io/tx/{}", swap.signature));
+    text.message(Style::MarkdownV2)
+}
+
+#[derive(Debug)]
+struct PendingSwap {
+    legs: Vec<Trade>,
+    first_seen: Instant,
+}
+
+/// Legs of tracked wallets' transactions, grouped by signature until they settle.
+///
+/// Methods take the current time so tests control the clock.
+#[derive(Debug, Default)]
+pub struct PendingSwaps {
+    swaps: HashMap<String, PendingSwap>,
+}
+
+impl PendingSwaps {
+    pub fn push(&mut self, trade: Trade, now: Instant) {
+        self.swaps
+            .entry(trade.signature.clone())
+            .or_insert_with(|| PendingSwap {
+                legs: Vec::new(),
+                first_seen: now,
+            })
+            .legs
+            .push(trade);
+    }
+
+    /// Take the legs of every transaction whose first leg is at least [`SETTLE_DELAY`] old
+    pub fn take_settled(&mut self, now: Instant) -> Vec<Vec<Trade>> {
+        let settled = self
+            .swaps
+            .iter()
+            .filter(|(_, swap)| now.saturating_duration_since(swap.first_seen) >= SETTLE_DELAY)
+            .map(|(signature, _)| signature.clone())
+            .collect::<Vec<_>>();
+
+        settled
+            .into_iter()
+            .filter_map(|signature| self.swaps.remove(&signature))
+            .map(|swap| swap.legs)
+            .collect()
+    }
+}
+
+type TrackedByWallet = HashMap<String, Vec<tracked_wallet::Model>>;
+
+// Load tracked wallets and subscribe the aggregator to their trades
+async fn load_tracked() -> TrackedByWallet {
+    let db = entity::get_db().await;
+    let mut tracked = TrackedByWallet::new();
+
+    match TrackedWallet::find().all(db).await {
+        Ok(models) => {
+            for model in models {
+                tracked
+                    .entry(model.wallet_address.clone())
+                    .or_default()
+                    .push(model);
+            }
+            aggregator::set_tracked_wallets(tracked.keys().cloned().collect());
+        }
+        Err(err) => tracing::error!("Failed to load tracked wallets: {:?}", err),
+    }
+
+    tracked
+}
+
+async fn notify(bot: &Bot, trackers: &[tracked_wallet::Model], swap: &Swap) {
+    for tracker in trackers {
+        let lang = stored_user_lang(tracker.telegram_id).await;
+        let label = tracker
+            .label
+            .clone()
+            .unwrap_or_else(|| short_address(&tracker.wallet_address));
+        let text = render_swap(lang, &label, swap, token_symbol);
+
+        let result = bot
+            .send_message(ChatId(tracker.chat_id), text)
+            .parse_mode(CARD_PARSE_MODE)
+            .link_preview_options(NO_LINK_PREVIEW)
+            .await;
+        if let Err(err) = result {
+            tracing::warn!("Failed to send wallet alert {}: {:?}", tracker.id, err);
+        }
+    }
+}
+
+/// Notify users about swaps of the wallets they track
+pub async fn run(bot: Bot) {
+    let mut trades = aggregator::subscribe();
+    let mut settle = tokio::time::interval(Duration::from_millis(500));
+    let mut tracked = load_tracked().await;
+    let mut pending = PendingSwaps::default();
+
+    loop {
+        tokio::select! {
+            trade = trades.recv() => match trade {
+                Ok(trade) => {
+                    if tracked.contains_key(&trade.fee_payer) {
+                        pending.push(trade, Instant::now());
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Wallet tracker lagged, skipped {} trades", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            _ = settle.tick() => {
+                for legs in pending.take_settled(Instant::now()) {
+                    let Some(swap) = summarize(&legs, stream_usd_price) else {
+                        continue;
+                    };
+                    if let Some(trackers) = tracked.get(&swap.wallet) {
+                        notify(&bot, trackers, &swap).await;
+                    }
+                }
+            }
+            _ = TRACKED_CHANGED.notified() => {
+                tracked = load_tracked().await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // Every leg of a swap is a trade of the same wallet
+    use aggregator::test_support::{trade as leg, TEST_WALLET as WALLET};
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    const WIF: &str = "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm";
+
+    fn usdc_only(mint: &str) -> Option<f64> {
+        (mint == USDC_MINT).then_some(1.0)
+    }
+
+    #[test]
+    fn test_summarize_multi_hop() {
+        // USDC -> SOL -> BONK, SOL only passes through
+        let legs = [
+            leg(SOL_MINT, "2", USDC_MINT, "300"),
+            leg(BONK, "15000000", SOL_MINT, "2"),
+        ];
+        let swap = summarize(&legs, usdc_only).unwrap();
+        assert_eq!(swap.sold, (USDC_MINT.to_string(), 300.0));
+        assert_eq!(swap.bought, (BONK.to_string(), 15_000_000.0));
+        assert_eq!(swap.usd, Some(300.0));
+        assert_eq!(swap.wallet, WALLET);
+
+        // A round trip with nothing left over is not a swap
+        let legs = [
+            leg(BONK, "10", USDC_MINT, "1"),
+            leg(USDC_MINT, "1", BONK, "10"),
+        ];
+        assert_eq!(summarize(&legs, usdc_only), None);
+        assert_eq!(summarize(&[], usdc_only), None);
+    }
+
+    #[test]
+    fn test_summarize_single_leg() {
+        // The sizes of the live feed are unsigned, the pair order tells buys from sells
+        let bought = summarize(&[leg(BONK, "1000000", SOL_MINT, "0.1234")], usdc_only).unwrap();
+        assert_eq!(bought.sold, (SOL_MINT.to_string(), 0.1234));
+        assert_eq!(bought.bought, (BONK.to_string(), 1_000_000.0));
+
+        let sold = summarize(&[leg(SOL_MINT, "0.1234", BONK, "1000000")], usdc_only).unwrap();
+        assert_eq!(sold.sold, (BONK.to_string(), 1_000_000.0));
+        assert_eq!(sold.bought, (SOL_MINT.to_string(), 0.1234));
+    }
+
+    #[test]
+    fn test_render_swap() {
+        let symbol = |mint: &str| match mint {
+            BONK => "Bonk".to_string(),
+            WIF => "WIF".to_string(),
+            _ => token_symbol(mint),
+        };
+        let swap = |sold: (&str, f64), bought: (&str, f64), usd| Swap {
+            wallet: WALLET.to_string(),
+            signature: "5sig".to_string(),
+            sold: (sold.0.to_string(), sold.1),
+            bought: (bought.0.to_string(), bought.1),
+            usd,
+        };
+
+        assert_eq!(
+            render_swap(
+                Lang::En,
+                "whale.sol",
+                &swap((USDC_MINT, 300.0), (BONK, 15_000_000.0), Some(300.0)),
+                symbol
+            ),
+            "🟢 *whale\\.sol* bought 15M Bonk for 300 USDC · \\~$300 · \
+             [tx](https://solscan.io/tx/5sig)"
+        );
+        assert_eq!(
+            render_swap(
+                Lang::En,
+                "9WzD…AWWM",
+                &swap((BONK, 15_000_000.0), (SOL_MINT, 2.0), None),
+                symbol
+            ),
+            "🔴 *9WzD…AWWM* sold 15M Bonk for 2 SOL · [tx](https://solscan.io/tx/5sig)"
+        );
+        assert!(render_swap(
+            Lang::En,
+            "whale",
+            &swap((BONK, 1.0), (WIF, 2.0), None),
+            symbol
+        )
+        .starts_with("🔄 *whale* swapped 1 Bonk for 2 WIF"));
+    }
+
+    #[test]
+    fn test_pending_swaps_settle_per_signature() {
+        let start = Instant::now();
+        let mut pending = PendingSwaps::default();
+
+        pending.push(leg(SOL_MINT, "2", USDC_MINT, "300"), start);
+        pending.push(
+            leg(BONK, "15000000", SOL_MINT, "2"),
+            start + Duration::from_millis(400),
+        );
+        let mut other = leg(WIF, "1", USDC_MINT, "2");
+        other.signature = "6sig".to_string();
+        pending.push(other, start + Duration::from_secs(1));
+
+        assert!(pending
+            .take_settled(start + Duration::from_secs(1))
+            .is_empty());
+        let settled = pending.take_settled(start + SETTLE_DELAY);
+        assert_eq!(settled.len(), 1);
+        assert_eq!(settled[0].len(), 2);
+        assert_eq!(
+            pending.take_settled(start + Duration::from_secs(3))[0][0].signature,
+            "6sig"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use std::{convert::Infallible, pin::Pin, sync::OnceLock};
+
+use futures_util::{stream, Stream, StreamExt};
+use teloxide::{
+    prelude::*,
+    stop::{mk_stop_token, StopToken},
+    update_listeners::{StatefulListener, UpdateListener},
+};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use url::Url;
+use utils::{config::TelegramUpdateMode, ENV_CONFIG};
+
+// Updates accepted by `/hook` but not yet picked up by the dispatcher
+const UPDATE_BUFFER: usize = 1024;
+
+pub const SECRET_TOKEN_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
+struct Webhook {
+    secret: String,
+    updates: mpsc::Sender<Update>,
+}
+
+static WEBHOOK: OnceLock<Webhook> = OnceLock::new();
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("the bot is not running in webhook mode")]
+    Disabled,
+    #[error("missing or invalid secret token")]
+    Unauthorized,
+    #[error("invalid update: {0}")]
+    InvalidUpdate(#[from] serde_json::Error),
+    #[error("the update queue is full")]
+    Busy,
+}
+
+type UpdateStream = Pin<Box<dyn Stream<Item = Result<Update, Infallible>> + Send>>;
+
+// Compare without bailing out on the first differing byte
+fn secret_matches(expected: &str, received: &str) -> bool {
+    expected.len() == received.len()
+        && expected
+            .bytes()
+            .zip(received.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Hand a raw `/hook` request body to the dispatcher.
+///
+/// `secret` is the value of the `X-Telegram-Bot-Api-Secret-Token` header.
+pub fn feed_update(secret: Option<&str>, body: &[u8]) -> Result<(), WebhookError> {
+    let webhook = WEBHOOK.get().ok_or(WebhookError::Disabled)?;
+
+    if !secret.is_some_and(|secret| secret_matches(&webhook.secret, secret)) {
+        return Err(WebhookError::Unauthorized);
+    }
+
+    let update = serde_json::from_slice::<Update>(body)?;
+    webhook.updates.try_send(update).map_err(|err| match err {
+        TrySendError::Full(_) => WebhookError::Busy,
+        TrySendError::Closed(_) => WebhookError::Disabled,
+    })
+}
+
+fn webhook_url() -> Result<Url, url::ParseError> {
+    let base = ENV_CONFIG.telegram_hook_url.trim_end_matches('/');
+    Url::parse(&format!("{}/hook", base))
+}
+
+/// Register the webhook with Telegram and build a listener fed by [`feed_update`].
+///
+/// Returns `None` when the bot should long poll instead, either because polling is
+/// configured or because the webhook could not be set up.
+pub async fn listener(bot: &Bot) -> Option<impl UpdateListener<Err = Infallible>> {
+    if ENV_CONFIG.telegram_update_mode != TelegramUpdateMode::Webhook {
+        return None;
+    }
+
+    let Some(secret) = ENV_CONFIG.telegram_hook_secret.clone() else {
+        tracing::error!("TELEGRAM_HOOK_SECRET is not set, falling back to polling");
+        return None;
+    };
+
+    let url = match webhook_url() {
+        Ok(url) => url,
+        Err(err) => {
+            tracing::error!(
+                "Invalid TELEGRAM_HOOK_URL, falling back to polling: {:?}",
+                err
+            );
+            return None;
+        }
+    };
+
+    if let Err(err) = bot
+        .set_webhook(url.clone())
+        .secret_token(secret.clone())
+        .await
+    {
+        tracing::error!("Failed to set webhook, falling back to polling: {:?}", err);
+        return None;
+    }
+
+    let (tx, mut rx) = mpsc::channel(UPDATE_BUFFER);
+    if WEBHOOK
+        .set(Webhook {
+            secret,
+            updates: tx,
+        })
+        .is_err()
+    {
+        tracing::error!("Webhook listener is already running, falling back to polling");
+        return None;
+    }
+
+    tracing::info!("Receiving Telegram updates through {}", url);
+
+    // The dispatcher stops through the token, which ends the stream at the flag
+    let (stop_token, stop_flag) = mk_stop_token();
+    let updates: UpdateStream = Box::pin(
+        stream::poll_fn(move |cx| rx.poll_recv(cx))
+            .map(Ok)
+            .take_until(stop_flag),
+    );
+
+    Some(StatefulListener::new(
+        (updates, stop_token),
+        listener_stream,
+        listener_stop_token,
+    ))
+}
+
+fn listener_stream(state: &mut (UpdateStream, StopToken)) -> &mut UpdateStream {
+    &mut state.0
+}
+
+fn listener_stop_token(state: &mut (UpdateStream, StopToken)) -> StopToken {
+    state.1.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_matches() {
+        assert!(secret_matches("s3cret_token", "s3cret_token"));
+        assert!(!secret_matches("s3cret_token", "s3cret_tokem"));
+        assert!(!secret_matches("s3cret_token", "s3cret"));
+        assert!(!secret_matches("s3cret_token", ""));
+    }
+}
@@ -0,0 +1,202 @@
+//! Handler tests through the harness, in their own process so the harness owns the
+//! environment `ENV_CONFIG` is loaded from
+
+use serde_json::json;
+use telegram::{
+    test_harness::{
+        encode_card_action, mock_token, run, token_json, translate, unique_user_id, CardAction,
+        CardPeriod, Lang, TestBot,
+    },
+    GlobalState,
+};
+
+const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+const WIF: &str = "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm";
+const JUP: &str = "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN";
+const POPCAT: &str = "7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr";
+
+#[test]
+fn test_token_lookup_sends_card() {
+    run(async {
+        let bot = TestBot::new().await;
+        let user_id = unique_user_id();
+        mock_token(token_json(
+            BONK,
+            "Bonk",
+            Some("https://example.com/bonk.png"),
+        ));
+        mock_token(token_json(WIF, "WIF", None));
+
+        bot.send_text(user_id, &format!("what about {}?", BONK))
+            .await;
+        let photos = bot.calls("sendPhoto");
+        assert_eq!(photos.len(), 1);
+        assert_eq!(
+            photos[0].params["photo"],
+            json!("https://example.com/bonk.png")
+        );
+        assert_eq!(photos[0].params["parse_mode"], json!("MarkdownV2"));
+        assert!(photos[0].params["caption"]
+            .as_str()
+            .unwrap()
+            .contains("Bonk"));
+
+        // Without a logo the card goes out as text
+        bot.send_text(user_id, WIF).await;
+        let texts = bot.sent_texts();
+        assert_eq!(texts.len(), 1);
+        assert!(texts[0].contains("WIF"));
+    });
+}
+
+#[test]
+fn test_alert_dialogue_from_card() {
+    run(async {
+        let bot = TestBot::new().await;
+        let user_id = unique_user_id();
+        mock_token(token_json(BONK, "Bonk", None));
+
+        let data = encode_card_action(CardAction::Alert, CardPeriod::Day, BONK);
+        bot.press_button(user_id, &data).await;
+        assert_eq!(bot.calls("answerCallbackQuery").len(), 1);
+        assert_eq!(
+            bot.sent_texts(),
+            [translate(Lang::En, "alert-prompt", None)]
+        );
+        assert!(matches!(
+            bot.state(user_id).await,
+            Some(GlobalState::AwaitingAlert { mint_address }) if mint_address == BONK
+        ));
+
+        // A condition that doesn't parse asks again
+        bot.send_text(user_id, "sideways").await;
+        assert_eq!(bot.sent_texts().len(), 2);
+        assert!(matches!(
+            bot.state(user_id).await,
+            Some(GlobalState::AwaitingAlert { .. })
+        ));
+
+        bot.send_text(user_id, "above 0.5").await;
+        assert!(matches!(bot.state(user_id).await, Some(GlobalState::Idle)));
+        let texts = bot.sent_texts();
+        assert_eq!(texts.len(), 3);
+        assert!(texts[2].contains("Bonk"), "{}", texts[2]);
+    });
+}
+
+#[test]
+fn test_token_pasted_at_alert_prompt() {
+    run(async {
+        let bot = TestBot::new().await;
+        let user_id = unique_user_id();
+        mock_token(token_json(WIF, "WIF", None));
+
+        let data = encode_card_action(CardAction::Alert, CardPeriod::Day, BONK);
+        bot.press_button(user_id, &data).await;
+        bot.send_text(user_id, &format!("https://solscan.io/token/{}", WIF))
+            .await;
+
+        // The prompt is left behind and the pasted token gets its card
+        assert!(matches!(bot.state(user_id).await, Some(GlobalState::Idle)));
+        let texts = bot.sent_texts();
+        assert_eq!(texts.len(), 2);
+        assert_eq!(texts[0], translate(Lang::En, "alert-prompt", None));
+        assert!(texts[1].contains("WIF"), "{}", texts[1]);
+    });
+}
+
+#[test]
+fn test_command_usage() {
+    run(async {
+        let bot = TestBot::new().await;
+        let user_id = unique_user_id();
+
+        bot.send_text(user_id, "/follow nonsense").await;
+        bot.send_text(user_id, "/track").await;
+        assert_eq!(
+            bot.sent_texts(),
+            [
+                translate(Lang::En, "follow-usage", None),
+                translate(Lang::En, "track-usage", None)
+            ]
+        );
+    });
+}
+
+#[test]
+fn test_referral_and_token_deep_links() {
+    run(async {
+        let bot = TestBot::new().await;
+        let referrer_id = unique_user_id();
+        let referred_id = unique_user_id();
+        mock_token(token_json(JUP, "Jupiter", None));
+
+        bot.send_text(referrer_id, "/start").await;
+        bot.send_text(referrer_id, "/referrals").await;
+        let texts = bot.sent_texts();
+        let link = texts[1]
+            .split_whitespace()
+            .find(|word| word.starts_with("https://t.me/pixa_bot?start=ref_"))
+            .unwrap();
+        let payload = link.split_once("start=").unwrap().1.to_string();
+
+        // A new user is credited to the referrer once, who hears about it
+        bot.send_text(referred_id, &format!("/start {}", payload))
+            .await;
+        bot.send_text(referred_id, &format!("/start {}", payload))
+            .await;
+        let notifications = bot
+            .calls("sendMessage")
+            .into_iter()
+            .filter(|call| call.params["chat_id"] == json!(referrer_id))
+            .count();
+        assert_eq!(notifications, 3);
+
+        bot.send_text(referrer_id, "/referrals").await;
+        let texts = bot.sent_texts();
+        assert!(
+            texts.last().unwrap().contains("1 in total"),
+            "{}",
+            texts.last().unwrap()
+        );
+
+        // Known users opening a token link only get the card
+        bot.send_text(referred_id, &format!("/start token_{}", JUP))
+            .await;
+        let texts = bot.sent_texts();
+        assert!(texts.last().unwrap().contains("Jupiter"));
+        assert_eq!(
+            texts
+                .iter()
+                .filter(|text| text.starts_with(&translate(Lang::En, "start-welcome", None)))
+                .count(),
+            3
+        );
+    });
+}
+
+#[test]
+fn test_disabled_command_in_group() {
+    run(async {
+        let bot = TestBot::new().await;
+        let user_id = unique_user_id();
+        let group_id = -unique_user_id();
+        mock_token(token_json(POPCAT, "Popcat", None));
+
+        bot.press_button_in(group_id, user_id, "gs:cmd:chart").await;
+        assert_eq!(bot.calls("editMessageReplyMarkup").len(), 1);
+
+        // Neither the chart nor a card of the mint in its arguments
+        bot.send_text_in(group_id, user_id, &format!("/chart {}", POPCAT))
+            .await;
+        bot.send_text_in(group_id, user_id, &format!("/chart@pixa_bot {}", POPCAT))
+            .await;
+        assert!(bot.calls("sendPhoto").is_empty());
+        assert!(bot.sent_texts().is_empty(), "{:?}", bot.sent_texts());
+
+        // Pasting the mint still looks it up
+        bot.send_text_in(group_id, user_id, POPCAT).await;
+        assert_eq!(bot.sent_texts().len(), 1);
+        assert!(bot.sent_texts()[0].contains("Popcat"));
+    });
+}
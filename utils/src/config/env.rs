@@ -1,11 +1,48 @@
 use std::env;
 
+use crate::rate_limit::RateTier;
+
+/// How the Telegram bot receives updates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TelegramUpdateMode {
+    /// Long polling `getUpdates`, handy for local development
+    #[default]
+    Polling,
+    /// Telegram pushes updates to `<TELEGRAM_HOOK_URL>/hook`
+    Webhook,
+}
+
+/// Where multi-step Telegram dialogues are kept between updates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DialogueStorageBackend {
+    /// Lost on restart and not shared between instances
+    Memory,
+    /// The `dialogue_states` table
+    #[default]
+    Postgres,
+}
+
 #[derive(Debug)]
 pub struct EnvConfig {
     pub port: u16,
     pub telegram_token: String,
     pub telegram_hook_url: String,
+    pub telegram_hook_secret: Option<String>,
+    pub telegram_update_mode: TelegramUpdateMode,
+    pub dialogue_storage: DialogueStorageBackend,
+    pub dialogue_ttl_secs: u64,
+    /// Wallet balances worth less than this many USD are hidden from /wallet
+    pub wallet_dust_usd: f64,
+    /// Token lookups allowed per user, and per group on top of that
+    pub rate_limit_user: RateTier,
+    pub rate_limit_chat: RateTier,
+    /// Vybe requests allowed across the whole process, matching the API plan
+    pub vybe_rate_limit: RateTier,
+    /// Telegram ids allowed to use `/stats`, `/broadcast`, `/ban` and `/unban`
+    pub admin_ids: Vec<i64>,
     pub vibe_api_key: String,
+    /// Base URL of the Vybe REST API, tests point it at a local mock
+    pub vybe_api_url: String,
     pub database_url: String,
 }
 
@@ -16,14 +53,68 @@ pub fn load_env_config() -> Result<EnvConfig, env::VarError> {
         .unwrap();
     let telegram_token = env::var("TELOXIDE_TOKEN")?;
     let telegram_hook_url = env::var("TELEGRAM_HOOK_URL")?;
+    let telegram_hook_secret = env::var("TELEGRAM_HOOK_SECRET")
+        .ok()
+        .filter(|secret| !secret.is_empty());
+    let telegram_update_mode = match env::var("TELEGRAM_UPDATE_MODE").as_deref() {
+        Ok("webhook") => TelegramUpdateMode::Webhook,
+        _ => TelegramUpdateMode::Polling,
+    };
+    let dialogue_storage = match env::var("DIALOGUE_STORAGE").as_deref() {
+        Ok("memory") => DialogueStorageBackend::Memory,
+        _ => DialogueStorageBackend::Postgres,
+    };
+    let dialogue_ttl_secs = env::var("DIALOGUE_TTL_SECS")
+        .unwrap_or("86400".to_string())
+        .parse()
+        .unwrap();
+    let wallet_dust_usd = env::var("WALLET_DUST_USD")
+        .unwrap_or("1".to_string())
+        .parse()
+        .unwrap();
+    let rate_limit_user = env::var("RATE_LIMIT_USER")
+        .unwrap_or("5/30".to_string())
+        .parse()
+        .unwrap();
+    let rate_limit_chat = env::var("RATE_LIMIT_CHAT")
+        .unwrap_or("20/60".to_string())
+        .parse()
+        .unwrap();
+    let vybe_rate_limit = env::var("VYBE_RATE_LIMIT")
+        .unwrap_or("60/60".to_string())
+        .parse()
+        .unwrap();
+    let admin_ids = env::var("ADMIN_IDS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse().unwrap())
+        .collect();
     let vibe_api_key = env::var("VIBE_API_KEY")?;
+    let vybe_api_url = env::var("VYBE_API_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .unwrap_or("https://api.vybenetwork.xyz".to_string())
+        .trim_end_matches('/')
+        .to_string();
     let database_url = env::var("DATABASE_URL")?;
 
     Ok(EnvConfig {
         port,
         telegram_token,
         telegram_hook_url,
+        telegram_hook_secret,
+        telegram_update_mode,
+        dialogue_storage,
+        dialogue_ttl_secs,
+        wallet_dust_usd,
+        rate_limit_user,
+        rate_limit_chat,
+        vybe_rate_limit,
+        admin_ids,
         vibe_api_key,
+        vybe_api_url,
         database_url,
     })
 }
@@ -1,3 +1,4 @@
 pub mod tokens;
 pub mod types;
 pub mod util;
+pub mod wallets;
@@ -1,10 +1,12 @@
 use crate::http::HttpError;
 use std::sync::Arc;
 
-use super::types::VybeTokenDetails;
+use super::types::{VybeOhlcvList, VybeTokenDetails, VybeTokenList, VybeTopHolderList};
 use super::util::VybeHttpClient;
 
 const TOKEN_SERVICE: &str = "token";
+const TOKENS_SERVICE: &str = "tokens";
+const PRICE_SERVICE: &str = "price";
 
 pub struct VybeTokenApi {
     client: Arc<VybeHttpClient>,
@@ -26,4 +28,55 @@ impl VybeTokenApi {
         );
         self.client.get(&endpoint).await
     }
+
+    /// One page of the token listing, sorted descending by `sort_by_desc` (e.g. `usdValueVolume24h`)
+    pub async fn get_tokens(
+        &self,
+        sort_by_desc: &str,
+        limit: u32,
+        page: u32,
+    ) -> Result<VybeTokenList, HttpError> {
+        let endpoint = format!(
+            "{}?sortByDesc={}&limit={}&page={}",
+            self.client.service_url(TOKENS_SERVICE),
+            sort_by_desc,
+            limit,
+            page
+        );
+        self.client.get(&endpoint).await
+    }
+
+    /// USD price candles of a token between two unix timestamps, `resolution` like `1h` or `1d`
+    pub async fn get_token_ohlcv(
+        &self,
+        mint_address: &str,
+        resolution: &str,
+        time_start: i64,
+        time_end: i64,
+    ) -> Result<VybeOhlcvList, HttpError> {
+        let endpoint = format!(
+            "{}/{}/token-ohlcv?resolution={}&timeStart={}&timeEnd={}",
+            self.client.service_url(PRICE_SERVICE),
+            mint_address,
+            resolution,
+            time_start,
+            time_end
+        );
+        self.client.get(&endpoint).await
+    }
+
+    /// The largest holders of a token, biggest first
+    pub async fn get_top_holders(
+        &self,
+        mint_address: &str,
+        limit: u32,
+    ) -> Result<VybeTopHolderList, HttpError> {
+        let endpoint = format!(
+            "{}/{}/top-holders?limit={}",
+            self.client.service_url(TOKEN_SERVICE),
+            mint_address,
+            limit
+        );
+        self.client.get(&endpoint).await
+    }
 }
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VybeTokenDetails {
@@ -29,3 +29,162 @@ pub struct VybeTokenDetails {
     #[serde(rename = "usdValueVolume24h")]
     pub usd_value_volume_24h: Option<f64>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VybeTokenList {
+    pub data: Vec<VybeTokenDetails>,
+}
+
+// Vybe sends some amounts as decimal strings to keep their precision
+fn f64_from_string_or_number<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        String(String),
+        Number(f64),
+    }
+
+    match StringOrNumber::deserialize(deserializer)? {
+        StringOrNumber::String(value) => value.parse().map_err(serde::de::Error::custom),
+        StringOrNumber::Number(value) => Ok(value),
+    }
+}
+
+/// One candle of a token's USD price
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VybeOhlcv {
+    /// Unix timestamp (seconds) of the candle start
+    pub time: i64,
+    #[serde(deserialize_with = "f64_from_string_or_number")]
+    pub open: f64,
+    #[serde(deserialize_with = "f64_from_string_or_number")]
+    pub high: f64,
+    #[serde(deserialize_with = "f64_from_string_or_number")]
+    pub low: f64,
+    #[serde(deserialize_with = "f64_from_string_or_number")]
+    pub close: f64,
+    #[serde(deserialize_with = "f64_from_string_or_number")]
+    pub volume: f64,
+    #[serde(rename = "volumeUsd", deserialize_with = "f64_from_string_or_number")]
+    pub volume_usd: f64,
+    pub count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VybeOhlcvList {
+    pub data: Vec<VybeOhlcv>,
+}
+
+/// One of the largest holders of a token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VybeTopHolder {
+    pub rank: u32,
+    #[serde(rename = "ownerAddress")]
+    pub owner_address: String,
+    /// Vybe's label of the owner, like an exchange name
+    #[serde(rename = "ownerName")]
+    pub owner_name: Option<String>,
+    #[serde(deserialize_with = "f64_from_string_or_number")]
+    pub balance: f64,
+    #[serde(rename = "percentageOfSupplyHeld")]
+    pub percentage_of_supply_held: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VybeTopHolderList {
+    pub data: Vec<VybeTopHolder>,
+}
+
+/// One SPL token held by a wallet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VybeWalletTokenBalance {
+    pub symbol: String,
+    pub name: Option<String>,
+    #[serde(rename = "mintAddress")]
+    pub mint_address: String,
+    /// Balance in whole tokens, already divided by the decimals
+    #[serde(deserialize_with = "f64_from_string_or_number")]
+    pub amount: f64,
+    #[serde(rename = "priceUsd", deserialize_with = "f64_from_string_or_number")]
+    pub price_usd: f64,
+    #[serde(rename = "valueUsd", deserialize_with = "f64_from_string_or_number")]
+    pub value_usd: f64,
+    /// Change of the USD value over the last day, in USD
+    #[serde(
+        rename = "valueUsd1dChange",
+        deserialize_with = "f64_from_string_or_number"
+    )]
+    pub value_usd_1d_change: f64,
+    #[serde(rename = "logoUrl")]
+    pub logo_url: Option<String>,
+    #[serde(default)]
+    pub verified: bool,
+}
+
+/// One page of a wallet's token balances with totals over the whole wallet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VybeWalletTokenBalances {
+    #[serde(rename = "ownerAddress")]
+    pub owner_address: String,
+    #[serde(
+        rename = "totalTokenValueUsd",
+        deserialize_with = "f64_from_string_or_number"
+    )]
+    pub total_token_value_usd: f64,
+    /// Change of the total value over the last day, in USD
+    #[serde(
+        rename = "totalTokenValueUsd1dChange",
+        deserialize_with = "f64_from_string_or_number"
+    )]
+    pub total_token_value_usd_1d_change: f64,
+    #[serde(rename = "totalTokenCount")]
+    pub total_token_count: u32,
+    pub data: Vec<VybeWalletTokenBalance>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ohlcv_accepts_strings_and_numbers() {
+        let list: VybeOhlcvList = serde_json::from_str(
+            r#"{"data":[{"time":1700000000,"open":"0.5","high":0.75,"low":"0.25","close":"0.5","volume":"1000","volumeUsd":500,"count":7}]}"#,
+        )
+        .unwrap();
+
+        let candle = &list.data[0];
+        assert_eq!(candle.time, 1700000000);
+        assert_eq!((candle.open, candle.high, candle.low), (0.5, 0.75, 0.25));
+        assert_eq!(candle.volume_usd, 500.0);
+        assert_eq!(candle.count, Some(7));
+    }
+
+    #[test]
+    fn test_wallet_token_balances() {
+        let balances: VybeWalletTokenBalances = serde_json::from_str(
+            r#"{"ownerAddress":"owner","totalTokenValueUsd":"150.5","totalTokenValueUsd1dChange":"-10","totalTokenCount":1,"data":[{"symbol":"BONK","name":"Bonk","mintAddress":"mint","amount":"1000000","priceUsd":"0.00002","valueUsd":"20","valueUsd1dChange":"1.5","logoUrl":null,"verified":true}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(balances.total_token_value_usd, 150.5);
+        assert_eq!(balances.total_token_value_usd_1d_change, -10.0);
+        assert_eq!(balances.data[0].amount, 1_000_000.0);
+        assert!(balances.data[0].verified);
+    }
+
+    #[test]
+    fn test_top_holders() {
+        let holders: VybeTopHolderList = serde_json::from_str(
+            r#"{"data":[{"rank":1,"ownerAddress":"owner","ownerName":null,"balance":"1234.5","percentageOfSupplyHeld":12.5}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(holders.data[0].balance, 1234.5);
+        assert_eq!(holders.data[0].percentage_of_supply_held, 12.5);
+        assert!(holders.data[0].owner_name.is_none());
+    }
+}
@@ -1,11 +1,15 @@
 use crate::{
     http::{HttpClient, HttpError},
+    rate_limit::TokenBucket,
     ENV_CONFIG,
 };
 use reqwest::header::HeaderMap;
-use std::sync::{Arc, LazyLock};
+use std::{
+    sync::{Arc, LazyLock, Mutex},
+    time::{Duration, Instant},
+};
 
-use super::tokens::VybeTokenApi;
+use super::{tokens::VybeTokenApi, wallets::VybeWalletApi};
 
 // Create a global singleton Vybe client
 pub static VYBE_TOKEN_API: LazyLock<Arc<VybeTokenApi>> = LazyLock::new(|| {
@@ -13,6 +17,48 @@ pub static VYBE_TOKEN_API: LazyLock<Arc<VybeTokenApi>> = LazyLock::new(|| {
     Arc::new(VybeTokenApi::new(vybe_client))
 });
 
+pub static VYBE_WALLET_API: LazyLock<Arc<VybeWalletApi>> = LazyLock::new(|| {
+    let vybe_client = create_vybe_client().expect("Failed to create Vybe client");
+    Arc::new(VybeWalletApi::new(vybe_client))
+});
+
+// Requests wait this long at most for the shared quota before failing
+const MAX_QUOTA_WAIT: Duration = Duration::from_secs(5);
+
+// Below this share of the quota optional lookups should back off
+const LOW_QUOTA: f64 = 0.2;
+
+// Every Vybe client draws from the same bucket, the API plan limits the whole key
+static VYBE_QUOTA: LazyLock<Mutex<TokenBucket>> =
+    LazyLock::new(|| Mutex::new(TokenBucket::new(ENV_CONFIG.vybe_rate_limit, Instant::now())));
+
+/// Whether most of the Vybe quota is used up, so callers can skip requests that aren't needed
+pub fn vybe_quota_low() -> bool {
+    VYBE_QUOTA.lock().unwrap().remaining(Instant::now()) < LOW_QUOTA
+}
+
+// Take a request from the shared quota, waiting a little for it to refill
+async fn acquire_quota() -> Result<(), HttpError> {
+    let mut waited = Duration::ZERO;
+    loop {
+        let wait = {
+            let mut quota = VYBE_QUOTA.lock().unwrap();
+            let now = Instant::now();
+            if quota.try_take(now) {
+                return Ok(());
+            }
+            quota.wait_time(now)
+        };
+        // Other waiting requests may take the token first, so this can loop
+        if waited + wait > MAX_QUOTA_WAIT {
+            tracing::warn!("Vybe quota exhausted, retry in {:?}", wait);
+            return Err(HttpError::RateLimited { retry_after: wait });
+        }
+        tokio::time::sleep(wait).await;
+        waited += wait;
+    }
+}
+
 /// A configured HTTP client specifically for Vybe API calls
 pub struct VybeHttpClient {
     client: HttpClient,
@@ -32,7 +78,7 @@ impl VybeHttpClient {
         Self {
             client,
             headers,
-            base_url: "https://api.vybenetwork.xyz".to_string(),
+            base_url: ENV_CONFIG.vybe_api_url.clone(),
         }
     }
 
@@ -46,6 +92,7 @@ impl VybeHttpClient {
     where
         T: serde::de::DeserializeOwned,
     {
+        acquire_quota().await?;
         self.client.get(endpoint, Some(self.headers.clone())).await
     }
 
@@ -55,6 +102,7 @@ impl VybeHttpClient {
         T: serde::de::DeserializeOwned,
         B: serde::Serialize,
     {
+        acquire_quota().await?;
         self.client
             .post(endpoint, body, Some(self.headers.clone()))
             .await
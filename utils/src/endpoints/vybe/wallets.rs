@@ -0,0 +1,34 @@
+use crate::http::HttpError;
+use std::sync::Arc;
+
+use super::types::VybeWalletTokenBalances;
+use super::util::VybeHttpClient;
+
+const ACCOUNT_SERVICE: &str = "account";
+
+pub struct VybeWalletApi {
+    client: Arc<VybeHttpClient>,
+}
+
+impl VybeWalletApi {
+    pub fn new(client: Arc<VybeHttpClient>) -> Self {
+        Self { client }
+    }
+
+    /// One page of the SPL token balances of a wallet, most valuable first
+    pub async fn get_token_balances(
+        &self,
+        owner_address: &str,
+        limit: u32,
+        page: u32,
+    ) -> Result<VybeWalletTokenBalances, HttpError> {
+        let endpoint = format!(
+            "{}/token-balance/{}?limit={}&page={}&sortByDesc=valueUsd",
+            self.client.service_url(ACCOUNT_SERVICE),
+            owner_address,
+            limit,
+            page
+        );
+        self.client.get(&endpoint).await
+    }
+}
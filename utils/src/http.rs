@@ -27,7 +27,14 @@ pub struct HttpClient {
 pub enum HttpError {
     RequestError(reqwest::Error),
     JsonError(serde_json::Error),
-    ApiError { status: u16, body: String },
+    ApiError {
+        status: u16,
+        body: String,
+    },
+    /// Our own request budget ran out, the request was not sent
+    RateLimited {
+        retry_after: Duration,
+    },
 }
 
 impl From<reqwest::Error> for HttpError {
@@ -6,6 +6,7 @@ pub mod endpoints;
 pub mod http;
 pub mod math;
 pub mod number;
+pub mod rate_limit;
 pub mod solana;
 
 // Cache env config to avoid loading it on every request
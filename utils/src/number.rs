@@ -77,6 +77,106 @@ pub fn format_long_number(num: f64) -> String {
     num.to_string()
 }
 
+/// Formats a price keeping a few significant digits for sub-dollar values
+///
+/// # Arguments
+///
+/// * `price` - The price to format
+///
+/// # Returns
+///
+/// A formatted string, e.g. `1.23` for `1.234` and `0.0000213` for `0.00002134`
+pub fn format_compact_price(price: f64) -> String {
+    if price == 0.0 || !price.is_finite() {
+        return "0".to_string();
+    }
+
+    if price.abs() >= 1.0 {
+        return format!("{:.2}", price);
+    }
+
+    // Three significant digits after the leading zeros
+    let leading_zeros = ((-price.abs().log10()).ceil() as usize).saturating_sub(1);
+    format!("{:.*}", leading_zeros + 3, price)
+}
+
+/// Decimal and thousands separators of a locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Separators {
+    pub decimal: char,
+    pub thousands: char,
+}
+
+impl Separators {
+    /// `1,234.5`
+    pub const ENGLISH: Separators = Separators {
+        decimal: '.',
+        thousands: ',',
+    };
+    /// `1.234,5`
+    pub const SPANISH: Separators = Separators {
+        decimal: ',',
+        thousands: '.',
+    };
+}
+
+/// Rewrites a number formatted by the functions above with the separators of a locale
+///
+/// # Arguments
+///
+/// * `formatted` - A number using `.` as decimal and `,` as thousands separator, suffixes are kept
+/// * `separators` - The separators to use instead
+///
+/// # Returns
+///
+/// The same number with swapped separators, e.g. `1,5M` for `1.5M` in Spanish
+pub fn localize_number(formatted: &str, separators: Separators) -> String {
+    formatted
+        .chars()
+        .map(|c| match c {
+            '.' => separators.decimal,
+            ',' => separators.thousands,
+            c => c,
+        })
+        .collect()
+}
+
+/// Formats a number with grouped thousands
+///
+/// # Arguments
+///
+/// * `num` - The number to format
+/// * `decimals` - Number of decimal places
+/// * `separators` - Separators of the locale
+///
+/// # Returns
+///
+/// A formatted string, e.g. `1,234,567.89` or `1.234.567,89`
+pub fn format_grouped_number(num: f64, decimals: usize, separators: Separators) -> String {
+    let formatted = format!("{:.*}", decimals, num.abs());
+    let (integer, fraction) = formatted
+        .split_once('.')
+        .map_or((formatted.as_str(), None), |(i, f)| (i, Some(f)));
+
+    let mut grouped = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(separators.thousands);
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push(separators.decimal);
+        grouped.push_str(fraction);
+    }
+
+    if num < 0.0 && grouped.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        format!("-{}", grouped)
+    } else {
+        grouped
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,4 +199,42 @@ mod tests {
         assert_eq!(format_long_number(1000000000.0), "1B");
         assert_eq!(format_long_number(123.0), "123");
     }
+
+    #[test]
+    fn test_format_compact_price() {
+        assert_eq!(format_compact_price(0.0), "0");
+        assert_eq!(format_compact_price(1.234), "1.23");
+        assert_eq!(format_compact_price(150.0), "150.00");
+        assert_eq!(format_compact_price(0.5), "0.500");
+        assert_eq!(format_compact_price(0.1), "0.100");
+        assert_eq!(format_compact_price(0.00002134), "0.0000213");
+    }
+
+    #[test]
+    fn test_localize_number() {
+        assert_eq!(localize_number("1.5M", Separators::SPANISH), "1,5M");
+        assert_eq!(localize_number("1,234.56", Separators::SPANISH), "1.234,56");
+        assert_eq!(localize_number("1,234.56", Separators::ENGLISH), "1,234.56");
+    }
+
+    #[test]
+    fn test_format_grouped_number() {
+        assert_eq!(
+            format_grouped_number(1234567.891, 2, Separators::ENGLISH),
+            "1,234,567.89"
+        );
+        assert_eq!(
+            format_grouped_number(1234567.891, 2, Separators::SPANISH),
+            "1.234.567,89"
+        );
+        assert_eq!(format_grouped_number(999.0, 0, Separators::ENGLISH), "999");
+        assert_eq!(
+            format_grouped_number(-1000.5, 1, Separators::ENGLISH),
+            "-1,000.5"
+        );
+        assert_eq!(
+            format_grouped_number(-0.001, 2, Separators::ENGLISH),
+            "0.00"
+        );
+    }
 }
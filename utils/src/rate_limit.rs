@@ -0,0 +1,149 @@
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// A burst of `burst` requests, refilled evenly over `per`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateTier {
+    pub burst: u32,
+    pub per: Duration,
+}
+
+impl RateTier {
+    pub const fn new(burst: u32, per_secs: u64) -> Self {
+        Self {
+            burst,
+            per: Duration::from_secs(per_secs),
+        }
+    }
+}
+
+/// Tiers are written as `<burst>/<seconds>`, `5/30` allows 5 requests every 30 seconds
+impl FromStr for RateTier {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (burst, per_secs) = value
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| format!("expected <burst>/<seconds>, got {:?}", value))?;
+        let burst = burst
+            .trim()
+            .parse::<u32>()
+            .map_err(|err| format!("invalid burst {:?}: {}", burst, err))?;
+        let per_secs = per_secs
+            .trim()
+            .parse::<u64>()
+            .map_err(|err| format!("invalid seconds {:?}: {}", per_secs, err))?;
+        if burst == 0 || per_secs == 0 {
+            return Err(format!(
+                "burst and seconds must be positive, got {:?}",
+                value
+            ));
+        }
+
+        Ok(Self::new(burst, per_secs))
+    }
+}
+
+/// Token bucket, every request takes a token and tokens trickle back at the tier's rate.
+///
+/// Methods take the current time so callers and tests control the clock.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    tier: RateTier,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket
+    pub fn new(tier: RateTier, now: Instant) -> Self {
+        Self {
+            tier,
+            tokens: tier.burst as f64,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+        let rate = self.tier.burst as f64 / self.tier.per.as_secs_f64();
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * rate).min(self.tier.burst as f64);
+        self.updated_at = self.updated_at.max(now);
+    }
+
+    /// Whether a request would be allowed, without taking a token
+    pub fn has_token(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= 1.0
+    }
+
+    /// Take a token if there is one
+    pub fn try_take(&mut self, now: Instant) -> bool {
+        let allowed = self.has_token(now);
+        if allowed {
+            self.tokens -= 1.0;
+        }
+        allowed
+    }
+
+    /// Share of the burst left, from 0 to 1
+    pub fn remaining(&mut self, now: Instant) -> f64 {
+        self.refill(now);
+        self.tokens / self.tier.burst as f64
+    }
+
+    /// Whether the bucket refilled completely, so forgetting it changes nothing
+    pub fn is_full(&mut self, now: Instant) -> bool {
+        self.remaining(now) >= 1.0
+    }
+
+    /// Time until the next token, zero when one is available
+    pub fn wait_time(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            return Duration::ZERO;
+        }
+        let missing = 1.0 - self.tokens;
+        self.tier.per.mul_f64(missing / self.tier.burst as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate_tier() {
+        assert_eq!("5/30".parse(), Ok(RateTier::new(5, 30)));
+        assert_eq!(" 20 / 60 ".parse(), Ok(RateTier::new(20, 60)));
+        assert!("5".parse::<RateTier>().is_err());
+        assert!("0/30".parse::<RateTier>().is_err());
+        assert!("5/x".parse::<RateTier>().is_err());
+    }
+
+    #[test]
+    fn test_token_bucket_refills() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(RateTier::new(2, 10), start);
+
+        assert!(bucket.try_take(start));
+        assert!(bucket.try_take(start));
+        assert!(!bucket.try_take(start));
+        assert_eq!(bucket.wait_time(start), Duration::from_secs(5));
+
+        // One token every 5 seconds
+        let later = start + Duration::from_secs(5);
+        assert!(bucket.try_take(later));
+        assert!(!bucket.try_take(later));
+
+        // Idle buckets never hold more than the burst
+        let much_later = later + Duration::from_secs(3600);
+        assert!(bucket.is_full(much_later));
+        assert!(bucket.try_take(much_later));
+        assert!(bucket.try_take(much_later));
+        assert!(!bucket.try_take(much_later));
+    }
+}